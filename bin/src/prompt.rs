@@ -1,7 +1,7 @@
-use {colored_json::ToColoredJson, serde::ser};
+use {colored_json::ToColoredJson, grug_types::to_json_pretty, serde::ser};
 
 pub fn print_json_pretty(data: impl ser::Serialize) -> anyhow::Result<()> {
-    let json = serde_json::to_string_pretty(&data)?;
+    let json = to_json_pretty(&data)?;
     let colored = json.to_colored_json_auto()?;
 
     println!("{colored}");
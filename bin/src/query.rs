@@ -4,7 +4,7 @@ use {
     clap::{Parser, Subcommand},
     grug_jmt::Proof,
     grug_sdk::Client,
-    grug_types::{Addr, Binary, Hash},
+    grug_types::{Addr, Binary, Hash, Order},
     serde::Serialize,
     serde_json::Value,
     std::{fs::File, io::Write, path::PathBuf},
@@ -43,6 +43,9 @@ enum SubCmd {
         start_after: Option<String>,
         /// Maximum number of items to display
         limit: Option<u32>,
+        /// Iterate in descending order [default: false]
+        #[arg(long, default_value_t = false)]
+        descending: bool,
     },
     /// Query a token's total supply
     Supply {
@@ -55,6 +58,9 @@ enum SubCmd {
         start_after: Option<String>,
         /// Maximum number of items to display
         limit: Option<u32>,
+        /// Iterate in descending order [default: false]
+        #[arg(long, default_value_t = false)]
+        descending: bool,
     },
     /// Query a Wasm binary code by hash
     Code { hash: Hash },
@@ -64,6 +70,9 @@ enum SubCmd {
         start_after: Option<Hash>,
         /// Maximum number of items to display
         limit: Option<u32>,
+        /// Iterate in descending order [default: false]
+        #[arg(long, default_value_t = false)]
+        descending: bool,
     },
     /// Query metadata of a single account by address
     Account {
@@ -76,6 +85,9 @@ enum SubCmd {
         start_after: Option<Addr>,
         /// Maximum number of items to display
         limit: Option<u32>,
+        /// Iterate in descending order [default: false]
+        #[arg(long, default_value_t = false)]
+        descending: bool,
     },
     /// Query the raw value in a contract store by raw key
     WasmRaw {
@@ -127,9 +139,10 @@ impl QueryCmd {
                 address,
                 start_after,
                 limit,
+                descending,
             } => {
                 let res = client
-                    .query_balances(address, start_after, limit, self.height)
+                    .query_balances(address, start_after, limit, order(descending), self.height)
                     .await?;
                 print_json_pretty(res)
             },
@@ -137,9 +150,13 @@ impl QueryCmd {
                 let res = client.query_supply(denom, self.height).await?;
                 print_json_pretty(res)
             },
-            SubCmd::Supplies { start_after, limit } => {
+            SubCmd::Supplies {
+                start_after,
+                limit,
+                descending,
+            } => {
                 let res = client
-                    .query_supplies(start_after, limit, self.height)
+                    .query_supplies(start_after, limit, order(descending), self.height)
                     .await?;
                 print_json_pretty(res)
             },
@@ -161,17 +178,27 @@ impl QueryCmd {
 
                 Ok(())
             },
-            SubCmd::Codes { start_after, limit } => {
-                let res = client.query_codes(start_after, limit, self.height).await?;
+            SubCmd::Codes {
+                start_after,
+                limit,
+                descending,
+            } => {
+                let res = client
+                    .query_codes(start_after, limit, order(descending), self.height)
+                    .await?;
                 print_json_pretty(res)
             },
             SubCmd::Account { address } => {
                 let res = client.query_account(address, self.height).await?;
                 print_json_pretty(res)
             },
-            SubCmd::Accounts { start_after, limit } => {
+            SubCmd::Accounts {
+                start_after,
+                limit,
+                descending,
+            } => {
                 let res = client
-                    .query_accounts(start_after, limit, self.height)
+                    .query_accounts(start_after, limit, order(descending), self.height)
                     .await?;
                 print_json_pretty(res)
             },
@@ -217,3 +244,7 @@ impl QueryCmd {
         }
     }
 }
+
+fn order(descending: bool) -> Option<Order> {
+    descending.then_some(Order::Descending)
+}
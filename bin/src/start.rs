@@ -1,4 +1,7 @@
-use {clap::Parser, grug_app::App, grug_db_disk::DiskDb, grug_vm_wasm::WasmVm, std::path::PathBuf};
+use {
+    clap::Parser, grug_app::App, grug_db_disk::DiskDb, grug_types::Udec128, grug_vm_wasm::WasmVm,
+    std::path::PathBuf,
+};
 
 #[derive(Parser)]
 pub struct StartCmd {
@@ -17,13 +20,17 @@ pub struct StartCmd {
     /// Gas limit when serving query requests [default: u64::MAX]
     #[arg(long)]
     query_gas_limit: Option<u64>,
+
+    /// Multiplier applied to the gas consumed by simulated transactions, to produce conservative gas estimates
+    #[arg(long, default_value = "1.0")]
+    gas_adjustment: Udec128,
 }
 
 impl StartCmd {
     pub async fn run(self, data_dir: PathBuf) -> anyhow::Result<()> {
         let db = DiskDb::open(data_dir)?;
         let vm = WasmVm::new(self.wasm_cache_capacity);
-        let app = App::new(db, vm, self.query_gas_limit);
+        let app = App::new(db, vm, self.query_gas_limit, self.gas_adjustment);
 
         Ok(app.start_abci_server(self.read_buf_size, self.abci_addr)?)
     }
@@ -6,7 +6,7 @@ use {
     grug_sdk::{Client, SigningKey, SigningOptions},
     grug_types::{from_json_slice, Addr, Binary, Coins, Hash, Message},
     serde::Serialize,
-    std::{fs::File, io::Read, path::PathBuf, str::FromStr},
+    std::{collections::BTreeSet, fs::File, io::Read, path::PathBuf, str::FromStr},
     tendermint_rpc::endpoint::broadcast::tx_sync,
 };
 
@@ -69,6 +69,19 @@ enum SubCmd {
         /// Administrator address for the contract
         #[arg(long)]
         admin: Option<Addr>,
+        /// Call the contract's `receive` entry point when it's sent coins
+        #[arg(long)]
+        receive_hook: bool,
+        /// Reject executing the contract with funds attached
+        #[arg(long)]
+        strict_funds: bool,
+        /// Comma-separated entry points that the host may call on the contract
+        /// [default: all of them]
+        #[arg(long, value_delimiter = ',')]
+        entry_points: Option<Vec<String>>,
+        /// Allow other contracts to read the contract's storage directly
+        #[arg(long)]
+        raw_queries: bool,
     },
     /// Execute a contract
     Execute {
@@ -118,12 +131,20 @@ impl TxCmd {
                 salt,
                 funds,
                 admin,
+                receive_hook,
+                strict_funds,
+                entry_points,
+                raw_queries,
             } => Message::Instantiate {
                 msg: msg.into_bytes().into(),
                 salt: salt.into_bytes().into(),
                 funds: Coins::from_str(&funds.unwrap_or_default())?,
                 code_hash,
                 admin,
+                receive_hook,
+                strict_funds,
+                entry_points: entry_points.map(BTreeSet::from_iter),
+                raw_queries,
             },
             SubCmd::Execute {
                 contract,
@@ -11,7 +11,7 @@ use {
 /// The bytes are defined as:
 ///
 /// ```plain
-/// bytes := hash(json(msgs) | sender | chain_id | sequence)
+/// bytes := hash(json(msgs) | sender | chain_id | sequence | json(memo))
 /// ```
 ///
 /// Parameters:
@@ -20,17 +20,22 @@ use {
 /// - `msgs` is the list of messages in the transaction;
 /// - `sender` is a 32 bytes address of the sender;
 /// - `chain_id` is the chain ID in UTF-8 encoding;
-/// - `sequence` is the sender account's sequence in 32-bit big endian encoding.
+/// - `sequence` is the sender account's sequence in 32-bit big endian encoding;
+/// - `memo` is the transaction's memo, `null` in JSON if there isn't one.
 ///
 /// Chain ID and sequence are included in the sign bytes, as they are necessary
 /// for preventing replat attacks (e.g. user signs a transaction for chain A;
 /// attacker uses the signature to broadcast another transaction on chain B.)
+///
+/// The memo is included, so that it can't be altered by anyone relaying the
+/// transaction, e.g. to redirect an exchange deposit.
 pub fn make_sign_bytes<Hasher, const HASH_LEN: usize>(
     hasher: Hasher,
     msgs: &[Message],
     sender: &Addr,
     chain_id: &str,
     sequence: u32,
+    memo: Option<&str>,
 ) -> StdResult<[u8; HASH_LEN]>
 where
     Hasher: Fn(&[u8]) -> [u8; HASH_LEN],
@@ -42,6 +47,7 @@ where
     prehash.extend(sender.as_ref());
     prehash.extend(chain_id.as_bytes());
     prehash.extend(sequence.to_be_bytes());
+    prehash.extend(to_json_vec(&memo)?);
     Ok(hasher(&prehash))
 }
 
@@ -78,6 +84,7 @@ pub fn authenticate_tx(ctx: AuthCtx, tx: Tx) -> StdResult<Response> {
         &tx.sender,
         &ctx.chain_id,
         sequence,
+        tx.memo.as_deref(),
     )?;
 
     // Verify the signature
@@ -1,9 +1,16 @@
 use {
-    crate::{BALANCES_BY_ADDR, BALANCES_BY_DENOM, SUPPLIES},
-    grug_types::{Addr, Coins, MutableCtx, Number, Response, StdResult, Storage, Uint128},
-    std::collections::HashMap,
+    crate::{BALANCES_BY_ADDR, BALANCES_BY_DENOM, HELD_DENOMS, METADATAS, SUPPLIES},
+    grug_types::{
+        Addr, Attribute, Coin, Coins, DenomMetadata, Event, MutableCtx, Number, Response,
+        StdResult, Storage, Uint128,
+    },
+    std::collections::{BTreeMap, HashMap},
 };
 
+/// Set the initial balances and supplies at the bank's instantiation.
+///
+/// Balances are written to storage directly, all within the one
+/// `instantiate` call, rather than being minted one by one.
 pub fn initialize(
     storage: &mut dyn Storage,
     initial_balances: impl IntoIterator<Item = (Addr, Coins)>,
@@ -17,6 +24,7 @@ pub fn initialize(
         for coin in coins {
             BALANCES_BY_ADDR.save(storage, (&address, &coin.denom), &coin.amount)?;
             BALANCES_BY_DENOM.save(storage, (&coin.denom, &address), &coin.amount)?;
+            HELD_DENOMS.insert(storage, (&address, &coin.denom))?;
             accumulate_supply(&mut supplies, &coin.denom, coin.amount)?;
         }
     }
@@ -57,9 +65,10 @@ pub fn mint(ctx: MutableCtx, to: Addr, denom: String, amount: Uint128) -> StdRes
 
     Ok(Response::new()
         .add_attribute("method", "mint")
-        .add_attribute("to", to)
-        .add_attribute("denom", denom)
-        .add_attribute("amount", amount))
+        .add_attribute("to", &to)
+        .add_attribute("denom", &denom)
+        .add_attribute("amount", amount)
+        .add_event(balance_change_event(&to, '+', [Coin { denom, amount }])))
 }
 
 /// Burn tokens of specified denom and amount from an account.
@@ -74,9 +83,26 @@ pub fn burn(ctx: MutableCtx, from: Addr, denom: String, amount: Uint128) -> StdR
 
     Ok(Response::new()
         .add_attribute("method", "burn")
-        .add_attribute("from", from)
-        .add_attribute("denom", denom)
-        .add_attribute("amount", amount))
+        .add_attribute("from", &from)
+        .add_attribute("denom", &denom)
+        .add_attribute("amount", amount)
+        .add_event(balance_change_event(&from, '-', [Coin { denom, amount }])))
+}
+
+/// Set the metadata of a denom.
+///
+/// The host only lets the denom's admin do this, so there's no need to check
+/// the sender here.
+pub fn set_metadata(
+    storage: &mut dyn Storage,
+    denom: String,
+    metadata: DenomMetadata,
+) -> StdResult<Response> {
+    METADATAS.save(storage, &denom, &metadata)?;
+
+    Ok(Response::new()
+        .add_attribute("method", "set_metadata")
+        .add_attribute("denom", denom))
 }
 
 /// Transfer tokens from one account to another.
@@ -95,12 +121,59 @@ pub fn transfer(
         .add_attribute("method", "send")
         .add_attribute("from", from)
         .add_attribute("to", to)
-        .add_attribute("coins", coins.to_string()))
+        .add_attribute("coins", coins.to_string())
+        .add_event(balance_change_event(from, '-', coins.clone()))
+        .add_event(balance_change_event(to, '+', coins.clone())))
+}
+
+/// Send coins from one account to multiple others. Each recipient appears
+/// once, so the sender's balance only has to be checked against the total.
+pub fn transfer_multi(
+    storage: &mut dyn Storage,
+    from: &Addr,
+    outputs: &BTreeMap<Addr, Coins>,
+) -> StdResult<Response> {
+    let total = outputs
+        .values()
+        .try_fold(Coins::new_empty(), |total, coins| total.checked_add(coins))?;
+
+    let mut response = Response::new()
+        .add_attribute("method", "send_multi")
+        .add_attribute("from", from)
+        .add_attribute("coins", total.to_string())
+        .add_event(balance_change_event(from, '-', total));
+
+    for (to, coins) in outputs {
+        for coin in coins {
+            decrease_balance(storage, from, coin.denom, *coin.amount)?;
+            increase_balance(storage, to, coin.denom, *coin.amount)?;
+        }
+
+        response = response.add_event(balance_change_event(to, '+', coins.clone()));
+    }
+
+    Ok(response)
+}
+
+/// Create an event recording the signed change in an account's balances, with
+/// one `delta` attribute per denom, e.g. `+uatom:100` or `-uatom:100`. This
+/// allows indexers to track balances without re-deriving them from messages.
+fn balance_change_event<I>(address: &Addr, sign: char, coins: I) -> Event
+where
+    I: IntoIterator<Item = Coin>,
+{
+    Event::new("balance_change")
+        .add_attribute("account", address)
+        .add_attributes(
+            coins
+                .into_iter()
+                .map(|coin| Attribute::new("delta", format!("{sign}{coin}"))),
+        )
 }
 
 /// Increase the total supply of a token by the given amount.
 /// Return the total supply value after the increase.
-fn increase_supply(
+pub(crate) fn increase_supply(
     storage: &mut dyn Storage,
     denom: &str,
     amount: Uint128,
@@ -131,7 +204,7 @@ fn decrease_supply(
 
 /// Increase an account's balance of a token by the given amount.
 /// Return the balance value after the increase.
-fn increase_balance(
+pub(crate) fn increase_balance(
     storage: &mut dyn Storage,
     address: &Addr,
     denom: &str,
@@ -141,6 +214,7 @@ fn increase_balance(
         let balance = balance.unwrap_or_default().checked_add(amount)?;
         Ok(Some(balance))
     };
+    HELD_DENOMS.insert(storage, (address, denom))?;
     BALANCES_BY_ADDR.update(storage, (address, denom), action)?;
     BALANCES_BY_DENOM.update(storage, (denom, address), action)
 }
@@ -1,12 +1,13 @@
 use {
     crate::{
-        burn, initialize, mint, query_balance, query_balances, query_holders, query_supplies,
-        query_supply, transfer, ExecuteMsg, InstantiateMsg, QueryMsg,
+        burn, initialize, mint, query_balance, query_balance_exact, query_balances, query_holders,
+        query_metadata, query_supplies, query_supply, set_metadata, transfer, transfer_multi,
+        ExecuteMsg, InstantiateMsg, QueryMsg,
     },
     anyhow::bail,
     grug_types::{
-        to_json_value, BankMsg, BankQuery, BankQueryResponse, ImmutableCtx, Json, MutableCtx,
-        Response, StdResult, SudoCtx,
+        to_json_value, BankAdminMsg, BankMsg, BankQuery, BankQueryResponse, ImmutableCtx, Json,
+        MutableCtx, Response, StdResult, SudoCtx,
     },
 };
 
@@ -43,6 +44,11 @@ mod __wasm_exports {
     extern "C" fn bank_query(ctx_ptr: usize, msg_ptr: usize) -> usize {
         grug_ffi::do_bank_query(&super::bank_query, ctx_ptr, msg_ptr)
     }
+
+    #[no_mangle]
+    extern "C" fn sudo(ctx_ptr: usize, msg_ptr: usize) -> usize {
+        grug_ffi::do_sudo(&super::sudo, ctx_ptr, msg_ptr)
+    }
 }
 
 pub fn instantiate(ctx: MutableCtx, msg: InstantiateMsg) -> StdResult<Response> {
@@ -68,16 +74,35 @@ pub fn execute(ctx: MutableCtx, msg: ExecuteMsg) -> StdResult<Response> {
 
 pub fn query(ctx: ImmutableCtx, msg: QueryMsg) -> StdResult<Json> {
     match msg {
+        QueryMsg::Metadata { denom } => to_json_value(&query_metadata(ctx.storage, denom)?),
         QueryMsg::Holders {
             denom,
             start_after,
             limit,
-        } => to_json_value(&query_holders(ctx.storage, denom, start_after, limit)?),
+            order,
+        } => to_json_value(&query_holders(
+            ctx.storage,
+            denom,
+            start_after,
+            limit,
+            order,
+        )?),
     }
 }
 
 pub fn bank_execute(ctx: SudoCtx, msg: BankMsg) -> StdResult<Response> {
-    transfer(ctx.storage, &msg.from, &msg.to, &msg.coins)
+    match msg {
+        BankMsg::Transfer { from, to, coins } => transfer(ctx.storage, &from, &to, &coins),
+        BankMsg::TransferMulti { from, outputs } => transfer_multi(ctx.storage, &from, &outputs),
+    }
+}
+
+// The host calls this with admin operations on denoms, having checked that the
+// sender is the denom's admin.
+pub fn sudo(ctx: SudoCtx, msg: BankAdminMsg) -> StdResult<Response> {
+    match msg {
+        BankAdminMsg::SetMetadata { denom, metadata } => set_metadata(ctx.storage, denom, metadata),
+    }
 }
 
 #[rustfmt::skip]
@@ -86,14 +111,18 @@ pub fn bank_query(ctx: ImmutableCtx, msg: BankQuery) -> StdResult<BankQueryRespo
         BankQuery::Balance { address, denom } => {
             query_balance(ctx.storage, address, denom).map(BankQueryResponse::Balance)
         },
-        BankQuery::Balances { address, start_after, limit } => {
-            query_balances(ctx.storage, address, start_after, limit).map(BankQueryResponse::Balances)
+        BankQuery::BalanceExact { address, denom } => {
+            query_balance_exact(ctx.storage, address, denom).map(BankQueryResponse::BalanceExact)
+        },
+        BankQuery::Balances { address, start_after, limit, order } => {
+            query_balances(ctx.storage, address, start_after, limit, order)
+                .map(BankQueryResponse::Balances)
         },
         BankQuery::Supply { denom } => {
             query_supply(ctx.storage, denom).map(BankQueryResponse::Supply)
         },
-        BankQuery::Supplies { start_after, limit } => {
-            query_supplies(ctx.storage, start_after, limit).map(BankQueryResponse::Supplies)
+        BankQuery::Supplies { start_after, limit, order } => {
+            query_supplies(ctx.storage, start_after, limit, order).map(BankQueryResponse::Supplies)
         },
     }
 }
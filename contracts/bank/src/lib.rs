@@ -2,6 +2,7 @@ mod execute;
 mod exports;
 mod query;
 mod state;
+mod store;
 mod types;
 
-pub use crate::{execute::*, exports::*, query::*, state::*, types::*};
+pub use crate::{execute::*, exports::*, query::*, state::*, store::*, types::*};
@@ -1,7 +1,10 @@
 use {
-    crate::{BALANCES_BY_ADDR, BALANCES_BY_DENOM, SUPPLIES},
-    grug_storage::Bound,
-    grug_types::{Addr, Coin, Coins, NumberConst, Order, StdResult, Storage, Uint128},
+    crate::{BALANCES_BY_ADDR, BALANCES_BY_DENOM, HELD_DENOMS, METADATAS, SUPPLIES},
+    grug_storage::{start_after_bounds, Bound},
+    grug_types::{
+        Addr, Coin, Coins, DenomMetadata, NumberConst, Order, StdResult, Storage, SuppliesResponse,
+        Uint128,
+    },
     std::collections::BTreeMap,
 };
 
@@ -15,20 +18,44 @@ pub fn query_balance(storage: &dyn Storage, address: Addr, denom: String) -> Std
     })
 }
 
+pub fn query_balance_exact(
+    storage: &dyn Storage,
+    address: Addr,
+    denom: String,
+) -> StdResult<Option<Coin>> {
+    if let Some(amount) = BALANCES_BY_ADDR.may_load(storage, (&address, &denom))? {
+        return Ok(Some(Coin { denom, amount }));
+    }
+
+    if HELD_DENOMS.has(storage, (&address, &denom)) {
+        return Ok(Some(Coin {
+            denom,
+            amount: Uint128::ZERO,
+        }));
+    }
+
+    Ok(None)
+}
+
 pub fn query_balances(
     storage: &dyn Storage,
     address: Addr,
     start_after: Option<String>,
     limit: Option<u32>,
+    order: Option<Order>,
 ) -> StdResult<Coins> {
     let start = start_after
         .as_ref()
         .map(|denom| Bound::Exclusive(denom.as_str()));
-    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize;
+    // A page is returned as `Coins`, which can't be decoded from more than
+    // `Coins::MAX_LEN` records.
+    let limit = (limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize).min(Coins::MAX_LEN);
+    let order = order.unwrap_or(Order::Ascending);
+    let (min, max) = start_after_bounds(start, order);
 
     BALANCES_BY_ADDR
         .prefix(&address)
-        .range(storage, start, None, Order::Ascending)
+        .range(storage, min, max, order)
         .take(limit)
         .collect::<StdResult<BTreeMap<_, _>>>()?
         .try_into()
@@ -42,21 +69,45 @@ pub fn query_supply(storage: &dyn Storage, denom: String) -> StdResult<Coin> {
     })
 }
 
+/// Enumerate total supplies in the given order of denoms (ascending by
+/// default), starting after `start_after` (exclusive). A `limit` of zero is
+/// treated as one.
 pub fn query_supplies(
     storage: &dyn Storage,
     start_after: Option<String>,
     limit: Option<u32>,
-) -> StdResult<Coins> {
+    order: Option<Order>,
+) -> StdResult<SuppliesResponse> {
     let start = start_after
         .as_ref()
         .map(|denom| Bound::Exclusive(denom.as_str()));
-    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize;
+    // Same as in `query_balances`. Also, a page must hold at least one record,
+    // or `next_key` couldn't tell whether there are more after it.
+    let limit = (limit.unwrap_or(DEFAULT_PAGE_LIMIT) as usize).clamp(1, Coins::MAX_LEN);
+    let order = order.unwrap_or(Order::Ascending);
+    let (min, max) = start_after_bounds(start, order);
 
-    SUPPLIES
-        .range(storage, start, None, Order::Ascending)
-        .take(limit)
-        .collect::<StdResult<BTreeMap<_, _>>>()?
-        .try_into()
+    // Load one more record than requested, to find out whether there are more
+    // denoms after this page.
+    let mut supplies = SUPPLIES
+        .range(storage, min, max, order)
+        .take(limit + 1)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    let next_key = if supplies.len() > limit {
+        supplies.pop();
+        supplies.last().map(|(denom, _)| denom.clone())
+    } else {
+        None
+    };
+
+    Ok(SuppliesResponse {
+        supplies: supplies
+            .into_iter()
+            .collect::<BTreeMap<_, _>>()
+            .try_into()?,
+        next_key,
+    })
 }
 
 pub fn query_holders(
@@ -64,13 +115,20 @@ pub fn query_holders(
     denom: String,
     start_after: Option<Addr>,
     limit: Option<u32>,
+    order: Option<Order>,
 ) -> StdResult<BTreeMap<Addr, Uint128>> {
     let start = start_after.as_ref().map(Bound::exclusive);
     let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+    let order = order.unwrap_or(Order::Ascending);
+    let (min, max) = start_after_bounds(start, order);
 
     BALANCES_BY_DENOM
         .prefix(&denom)
-        .range(storage, start, None, Order::Ascending)
+        .range(storage, min, max, order)
         .take(limit as usize)
         .collect()
 }
+
+pub fn query_metadata(storage: &dyn Storage, denom: String) -> StdResult<Option<DenomMetadata>> {
+    METADATAS.may_load(storage, &denom)
+}
@@ -1,8 +1,11 @@
 use {
-    grug_storage::Map,
-    grug_types::{Addr, Uint128},
+    grug_storage::{Map, Set},
+    grug_types::{Addr, DenomMetadata, Uint128},
 };
 
+/// Metadata of denoms, indexed by denoms.
+pub const METADATAS: Map<&str, DenomMetadata> = Map::new("m");
+
 /// Total supplies of tokens, indexed by denoms.
 pub const SUPPLIES: Map<&str, Uint128> = Map::new("s");
 
@@ -11,3 +14,10 @@ pub const BALANCES_BY_ADDR: Map<(&Addr, &str), Uint128> = Map::new("bu");
 
 /// Token balances, indexed first by denoms, then by user addresses.
 pub const BALANCES_BY_DENOM: Map<(&str, &Addr), Uint128> = Map::new("bd");
+
+/// Denoms that each address has ever received.
+///
+/// Zero balances are deleted from `BALANCES_BY_ADDR` to save disk space, so
+/// this is needed to tell a balance that has been spent down to zero apart
+/// from one that never existed.
+pub const HELD_DENOMS: Set<(&Addr, &str)> = Set::new("h");
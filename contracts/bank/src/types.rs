@@ -1,5 +1,5 @@
 use {
-    grug_types::{Addr, Coins, Uint128},
+    grug_types::{Addr, Coins, Order, Uint128},
     serde::{Deserialize, Serialize},
     std::collections::BTreeMap,
 };
@@ -29,11 +29,15 @@ pub enum ExecuteMsg {
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename = "snake_case")]
 pub enum QueryMsg {
+    /// Query the metadata of a denom.
+    /// Returns: `Option<DenomMetadata>`.
+    Metadata { denom: String },
     /// Enumerate all holders of a given token and their balances.
     /// Returns: `BTreeMap<Addr, Uint128>`.
     Holders {
         denom: String,
         start_after: Option<Addr>,
         limit: Option<u32>,
+        order: Option<Order>,
     },
 }
@@ -90,10 +90,18 @@ where
         }
     }
 
-    fn check_tx(&self, _req: RequestCheckTx) -> ResponseCheckTx {
-        // TODO
-        ResponseCheckTx {
-            ..Default::default()
+    fn check_tx(&self, req: RequestCheckTx) -> ResponseCheckTx {
+        match self.do_check_tx_raw(&req.tx) {
+            Ok(()) => ResponseCheckTx {
+                code: 0,
+                ..Default::default()
+            },
+            Err(err) => ResponseCheckTx {
+                code: 1,
+                codespace: "tx".into(),
+                log: err.to_string(),
+                ..Default::default()
+            },
         }
     }
 
@@ -106,6 +114,10 @@ where
     //
     // Currently we're going neither of these. We ignore `path`, `height`, and
     // `prove` fields, and interpret `data` as a JSON-encoded QueryRequest.
+    //
+    // The exception is `/gas_metrics`, which isn't a query of the state, but of
+    // the node's own metrics. `data` is the JSON-encoded address of a contract,
+    // and the response its total gas consumption, as a JSON-encoded integer.
     fn query(&self, req: RequestQuery) -> ResponseQuery {
         match req.path.as_str() {
             "/app" => match self.do_query_app_raw(&req.data, req.height as u64, req.prove) {
@@ -145,10 +157,25 @@ where
                     ..Default::default()
                 },
             },
+            "/gas_metrics" => match self.do_query_contract_gas_used_raw(&req.data) {
+                Ok(res) => ResponseQuery {
+                    code: 0,
+                    value: res.into(),
+                    ..Default::default()
+                },
+                Err(err) => ResponseQuery {
+                    code: 1,
+                    codespace: "gas_metrics".into(),
+                    log: err.to_string(),
+                    ..Default::default()
+                },
+            },
             unknown => ResponseQuery {
                 code: 1,
                 codespace: "app".into(),
-                log: format!("unknown path `{unknown}`; must be `/app` or `/store`"),
+                log: format!(
+                    "unknown path `{unknown}`; must be `/app`, `/store`, or `/gas_metrics`"
+                ),
                 ..Default::default()
             },
         }
@@ -2,18 +2,49 @@
 use tracing::{debug, info};
 use {
     crate::{
-        do_after_block, do_after_tx, do_before_block, do_before_tx, do_configure, do_execute,
-        do_instantiate, do_migrate, do_transfer, do_upload, query_account, query_accounts,
-        query_balance, query_balances, query_code, query_codes, query_info, query_supplies,
-        query_supply, query_wasm_raw, query_wasm_smart, AppError, AppResult, Buffer, Db,
-        GasTracker, Shared, Vm, CHAIN_ID, CONFIG, LAST_FINALIZED_BLOCK,
+        check_fee_denom, check_gas_refund_ratio, do_after_block, do_after_tx, do_bank_admin,
+        do_before_block, do_before_tx, do_configure, do_execute, do_instantiate,
+        do_instantiate_sequential, do_migrate, do_remove_code, do_sudo, do_transfer,
+        do_transfer_multi, do_upload, load_bank, migrate_store, query_account, query_accounts,
+        query_balance, query_balance_exact, query_balances, query_code, query_codes, query_info,
+        query_is_contract, query_supplies, query_supply, query_tx_result, query_wasm_raw,
+        query_wasm_smart, run_atomically, AppError, AppResult, Buffer, Db, GasMetrics, GasTracker,
+        Shared, StorageProvider, Vm, BLOCK_TRANSFERS, CHAIN_ID, CODES, CONFIG,
+        LAST_FINALIZED_BLOCK, LATEST_STORE_VERSION, STORE_VERSION, TX_HASHES, TX_RESULTS,
     },
+    grug_storage::Bound,
     grug_types::{
-        from_json_slice, hash, to_json_vec, Addr, BlockInfo, Event, GenesisState, Hash, Message,
-        Permission, QueryRequest, QueryResponse, StdResult, Storage, Tx, GENESIS_SENDER,
+        concat, from_json_slice, hash, to_json_vec, Addr, BankStore, Batch, BlockInfo, Config,
+        Event, GenesisState, Hash, Json, Message, MultiplyFraction, Order, Permission,
+        QueryRequest, QueryResponse, StdResult, Storage, Tx, TxOutcome, TxResult, Udec128, Uint128,
+        GENESIS_SENDER, MAX_MEMO_LENGTH,
     },
+    std::sync::Arc,
 };
 
+/// Entry points that the bank contract must export. Checked at genesis.
+pub const BANK_ENTRY_POINTS: [&str; 2] = ["bank_execute", "bank_query"];
+
+/// The storage changes made by a single transaction. Returned by
+/// `App::replay_block`.
+pub struct TxDiff {
+    pub tx_hash: Hash,
+    pub result: AppResult<Vec<Event>>,
+    /// The keys that the transaction inserted or deleted, and the new values.
+    pub changes: Batch,
+}
+
+/// The outcome of simulating a transaction. Returned by `App::simulate_tx`.
+#[derive(Debug)]
+pub struct SimulateOutcome {
+    /// The amount of gas consumed during the simulation.
+    pub gas_used: u64,
+    /// `gas_used` multiplied by the app's gas adjustment, rounded up. Clients
+    /// should use this as the transaction's gas limit.
+    pub gas_estimate: u64,
+    pub events: Vec<Event>,
+}
+
 /// The ABCI application.
 ///
 /// Must be clonable which is required by `tendermint-abci` library:
@@ -35,16 +66,64 @@ pub struct App<DB, VM> {
     /// Related config in CosmWasm:
     /// <https://github.com/CosmWasm/wasmd/blob/v0.51.0/x/wasm/types/types.go#L322-L323>
     query_gas_limit: Option<u64>,
+    /// The multiplier applied to the gas consumed by a simulated transaction.
+    ///
+    /// A transaction may consume slightly more gas when it's actually executed
+    /// than during simulation, because it may touch a slightly different state
+    /// by then. A multiplier greater than one ensures the estimate returned to
+    /// clients is conservative.
+    gas_adjustment: Udec128,
+    /// An optional prefix under which the app's entire state is namespaced.
+    ///
+    /// Apps with different namespaces can share a single backing store without
+    /// their states colliding.
+    namespace: Option<Vec<u8>>,
+    /// Gas consumed by each contract in the blocks this node has executed.
+    ///
+    /// This is a metric for the node's operator, and not part of the chain's
+    /// state. See `GasMetrics`.
+    gas_metrics: GasMetrics,
+    /// Direct access to the bank contract's storage, used to check that a
+    /// sender can afford the funds attached to a message without calling the
+    /// bank contract. `None` means the check is skipped, and a shortfall is
+    /// left to the bank contract to report.
+    ///
+    /// Like the VM, this must be set the same way by every node of a chain, and
+    /// must match the storage layout of the chain's bank contract.
+    bank_store: Option<Arc<dyn BankStore + Send + Sync>>,
 }
 
 impl<DB, VM> App<DB, VM> {
-    pub fn new(db: DB, vm: VM, query_gas_limit: Option<u64>) -> Self {
+    pub fn new(db: DB, vm: VM, query_gas_limit: Option<u64>, gas_adjustment: Udec128) -> Self {
         Self {
             db,
             vm,
             query_gas_limit,
+            gas_adjustment,
+            namespace: None,
+            gas_metrics: GasMetrics::new(),
+            bank_store: None,
         }
     }
+
+    /// Read balances directly from the bank contract's storage with the given
+    /// bank store, instead of calling the bank contract.
+    pub fn with_bank_store<B>(mut self, bank_store: B) -> Self
+    where
+        B: BankStore + Send + Sync + 'static,
+    {
+        self.bank_store = Some(Arc::new(bank_store));
+        self
+    }
+
+    /// Namespace the app's entire state under the given prefix.
+    pub fn with_namespace<N>(mut self, namespace: N) -> Self
+    where
+        N: Into<Vec<u8>>,
+    {
+        self.namespace = Some(namespace.into());
+        self
+    }
 }
 
 impl<DB, VM> App<DB, VM>
@@ -53,6 +132,16 @@ where
     VM: Vm + Clone,
     AppError: From<DB::Error> + From<VM::Error>,
 {
+    /// The state storage at the given version (`None` for the latest), under
+    /// the app's namespace if one is set.
+    fn state_storage(&self, version: Option<u64>) -> Box<dyn Storage> {
+        let storage: Box<dyn Storage> = Box::new(self.db.state_storage(version));
+        match &self.namespace {
+            Some(namespace) => Box::new(StorageProvider::new(storage, &[namespace])),
+            None => storage,
+        }
+    }
+
     pub fn do_init_chain_raw(
         &self,
         chain_id: String,
@@ -69,7 +158,7 @@ where
         block: BlockInfo,
         genesis_state: GenesisState,
     ) -> AppResult<Hash> {
-        let mut buffer = Shared::new(Buffer::new(self.db.state_storage(None), None));
+        let mut buffer = Shared::new(Buffer::new(self.state_storage(None), None));
 
         // make sure the block height during InitChain is zero. this is necessary
         // to ensure that block height always matches the BaseStore version.
@@ -82,9 +171,17 @@ where
 
         // Create gas tracker for genesis.
         // During genesis, there is no gas limit.
-        let gas_tracker = GasTracker::new_limitless();
+        let gas_tracker = GasTracker::new_limitless()
+            .with_config(genesis_state.config.gas_config)
+            .with_metrics(Some(self.gas_metrics.clone()))
+            .with_bank_store(self.bank_store.clone());
+
+        // make sure the config is valid before saving it
+        check_fee_denom(&genesis_state.config.fee_denom)?;
+        check_gas_refund_ratio(genesis_state.config.gas_refund_ratio)?;
 
         // save the config and genesis block. some genesis messages may need it
+        STORE_VERSION.save(&mut buffer, &LATEST_STORE_VERSION)?;
         CHAIN_ID.save(&mut buffer, &chain_id)?;
         CONFIG.save(&mut buffer, &genesis_state.config)?;
         LAST_FINALIZED_BLOCK.save(&mut buffer, &block)?;
@@ -93,7 +190,7 @@ where
         // it's expected that genesis messages should all successfully execute.
         // if anyone fails, it's fatal error and we abort the genesis.
         // the developer should examine the error, fix it, and retry.
-        for (_idx, msg) in genesis_state.msgs.into_iter().enumerate() {
+        for (_idx, msg) in genesis_state.msgs.iter().enumerate() {
             #[cfg(feature = "tracing")]
             info!(idx = _idx, "Processing genesis message");
 
@@ -101,18 +198,29 @@ where
                 self.vm.clone(),
                 Box::new(buffer.clone()),
                 gas_tracker.clone(),
+                0,
                 block.clone(),
                 GENESIS_SENDER,
                 msg,
             )?;
         }
 
+        // make sure the core contracts implement the entry points that we're
+        // going to call. if not, abort the genesis now, instead of letting the
+        // chain start and every transaction fail later.
+        check_core_contracts(self.vm.clone(), &buffer, &genesis_state.config)?;
+
+        // transfers made during genesis don't count toward the caps of the
+        // first block
+        BLOCK_TRANSFERS.clear(&mut buffer, None, None);
+
         // persist the state changes to disk
         let (_, pending) = buffer.disassemble().disassemble();
         let (version, root_hash) = self.db.flush_and_commit(pending)?;
 
-        // BaseStore version should be 0
-        debug_assert_eq!(version, 0);
+        // BaseStore version should be 0, unless the app is namespaced, in
+        // which case other apps may have written to the store before it.
+        debug_assert!(self.namespace.is_some() || version == 0);
 
         // the root hash should not be None. it's only None when the merkle tree
         // is empty, but we have written some data to it (like the chain ID and
@@ -153,9 +261,14 @@ where
         block: BlockInfo,
         txs: Vec<(Hash, Tx)>,
     ) -> AppResult<(Hash, Vec<Event>, Vec<AppResult<Vec<Event>>>)> {
-        let mut buffer = Shared::new(Buffer::new(self.db.state_storage(None), None));
+        let mut buffer = Shared::new(Buffer::new(self.state_storage(None), None));
         let mut events = vec![];
         let mut tx_results = vec![];
+        let mut tx_hashes = vec![];
+
+        // if the chain was started with an earlier release, upgrade its state
+        // before anything reads it
+        migrate_store(&mut buffer)?;
 
         let cfg = CONFIG.load(&buffer)?;
         let last_finalized_block = LAST_FINALIZED_BLOCK.load(&buffer)?;
@@ -186,25 +299,56 @@ where
             events.extend(do_before_block(
                 self.vm.clone(),
                 Box::new(buffer.share()),
-                GasTracker::new_limitless(),
+                GasTracker::new_limitless()
+                    .with_config(cfg.gas_config)
+                    .with_metrics(Some(self.gas_metrics.clone()))
+                    .with_bank_store(self.bank_store.clone()),
                 block.clone(),
                 contract,
             )?);
         }
 
         // process transactions one-by-one
-        for (_idx, (_tx_hash, tx)) in txs.into_iter().enumerate() {
+        for (_idx, (tx_hash, tx)) in txs.into_iter().enumerate() {
             #[cfg(feature = "tracing")]
-            debug!(idx = _idx, tx_hash = ?_tx_hash, "Processing transaction");
+            debug!(idx = _idx, tx_hash = ?tx_hash, "Processing transaction");
 
-            tx_results.push(process_tx(
+            let gas_tracker = GasTracker::new_limited(tx.gas_limit)
+                .with_config(cfg.gas_config)
+                .with_metrics(Some(self.gas_metrics.clone()))
+                .with_bank_store(self.bank_store.clone());
+            let result = process_tx(
                 self.vm.clone(),
                 buffer.share(),
+                gas_tracker.clone(),
                 block.clone(),
                 tx,
-            ));
+                false,
+            );
+
+            // index the result by the tx hash, so that clients can look it up
+            if cfg.tx_result_retention > 0 {
+                TX_RESULTS.save(&mut buffer, &tx_hash, &TxResult {
+                    height: block.height.number(),
+                    error: result.as_ref().err().map(|err| err.to_string()),
+                    events: result.as_ref().cloned().unwrap_or_default(),
+                    gas_used: gas_tracker.used(),
+                })?;
+                tx_hashes.push(tx_hash);
+            }
+
+            tx_results.push(result);
+        }
+
+        if !tx_hashes.is_empty() {
+            TX_HASHES.save(&mut buffer, block.height.number(), &tx_hashes)?;
         }
 
+        // prune the results of blocks that have fallen out of the retention
+        // window. everything up to the cutoff is pruned, rather than just the
+        // block at the cutoff, in case the window has been shrunk since.
+        prune_tx_results(&mut buffer, block.height.number(), cfg.tx_result_retention)?;
+
         // call end blockers
         for (_idx, contract) in cfg.end_blockers.into_iter().enumerate() {
             #[cfg(feature = "tracing")]
@@ -221,12 +365,18 @@ where
             events.extend(do_after_block(
                 self.vm.clone(),
                 Box::new(buffer.share()),
-                GasTracker::new_limitless(),
+                GasTracker::new_limitless()
+                    .with_config(cfg.gas_config)
+                    .with_metrics(Some(self.gas_metrics.clone()))
+                    .with_bank_store(self.bank_store.clone()),
                 block.clone(),
                 contract,
             )?);
         }
 
+        // the transfer caps apply per block, so forget this block's transfers
+        BLOCK_TRANSFERS.clear(&mut buffer, None, None);
+
         // save the last committed block
         //
         // note that we do this *after* the transactions have been executed, so
@@ -239,8 +389,9 @@ where
         let (_, batch) = buffer.disassemble().disassemble();
         let (version, root_hash) = self.db.flush_but_not_commit(batch)?;
 
-        // block height should match the DB version
-        debug_assert_eq!(block.height.number(), version);
+        // block height should match the DB version, unless the app shares
+        // the store with others
+        debug_assert!(self.namespace.is_some() || block.height.number() == version);
 
         // the merkle tree should never be empty because at least we always have
         // things like the config, last finalized block, ...
@@ -257,6 +408,129 @@ where
         Ok((root_hash.unwrap(), events, tx_results))
     }
 
+    /// Execute the transactions of a block on top of the state at the given
+    /// version (`None` for the latest), and return the storage changes made by
+    /// each transaction. Nothing is written to the DB.
+    ///
+    /// This is a debugging tool, intended for chasing nondeterminism when a
+    /// block produces an unexpected app hash. Note that only the transactions
+    /// are replayed; begin and end blockers are not called.
+    pub fn replay_block(
+        &self,
+        pre_state_version: Option<u64>,
+        block: BlockInfo,
+        txs: Vec<(Hash, Tx)>,
+    ) -> Vec<TxDiff> {
+        let mut buffer = Shared::new(Buffer::new(self.state_storage(pre_state_version), None));
+        let gas_config = CONFIG
+            .load(&buffer)
+            .map(|cfg| cfg.gas_config)
+            .unwrap_or_default();
+        let mut diffs = Vec::with_capacity(txs.len());
+
+        for (tx_hash, tx) in txs {
+            // run each tx in its own buffer, so that we can tell which changes
+            // are made by which tx.
+            let tx_buffer = Shared::new(Buffer::new(buffer.share(), None));
+            let result = process_tx(
+                self.vm.clone(),
+                tx_buffer.share(),
+                GasTracker::new_limited(tx.gas_limit)
+                    .with_config(gas_config)
+                    .with_bank_store(self.bank_store.clone()),
+                block.clone(),
+                tx,
+                false,
+            );
+            let (_, changes) = tx_buffer.disassemble().disassemble();
+
+            // apply the changes, so that the next tx sees them
+            buffer.flush(changes.clone());
+
+            diffs.push(TxDiff {
+                tx_hash,
+                result,
+                changes,
+            });
+        }
+
+        diffs
+    }
+
+    /// Call a contract's `sudo` entry point on top of the given storage.
+    ///
+    /// No `Message` leads to the entry point, so neither users nor contracts
+    /// can reach it. This is the path for host code, such as a governance
+    /// module pushing an approved parameter change into a contract. The caller
+    /// decides which storage the call writes to, and when the changes are
+    /// committed. Like begin and end blockers, the call is exempt from gas.
+    pub fn do_sudo(
+        &self,
+        storage: Box<dyn Storage>,
+        block: BlockInfo,
+        contract: Addr,
+        msg: &Json,
+    ) -> AppResult<Vec<Event>> {
+        let cfg = CONFIG.load(&storage)?;
+        let gas_tracker = GasTracker::new_limitless()
+            .with_config(cfg.gas_config)
+            .with_metrics(Some(self.gas_metrics.clone()))
+            .with_bank_store(self.bank_store.clone());
+
+        do_sudo(self.vm.clone(), storage, gas_tracker, block, contract, msg)
+    }
+
+    /// Execute a transaction on top of the latest state in simulation mode, and
+    /// return the amount of gas it consumes. Nothing is written to the DB.
+    ///
+    /// In simulation mode, the gas limit specified in the transaction is
+    /// ignored, and the sender account may skip certain authentication steps,
+    /// such as verifying the signature.
+    pub fn simulate_tx(&self, tx: Tx) -> AppResult<SimulateOutcome> {
+        let buffer = Shared::new(Buffer::new(self.state_storage(None), None));
+        let cfg = CONFIG.load(&buffer)?;
+        let block = LAST_FINALIZED_BLOCK.load(&buffer)?;
+        let gas_tracker = GasTracker::new_limitless()
+            .with_config(cfg.gas_config)
+            .with_simulate(true)
+            .with_bank_store(self.bank_store.clone());
+
+        let events = process_tx(
+            self.vm.clone(),
+            buffer.share(),
+            gas_tracker.clone(),
+            block,
+            tx,
+            true,
+        )?;
+
+        let gas_used = gas_tracker.used();
+        let gas_estimate = Uint128::new(gas_used.into())
+            .checked_mul_dec_ceil(self.gas_adjustment)?
+            .try_into()?;
+
+        #[cfg(feature = "tracing")]
+        info!(gas_used, gas_estimate, "Simulated transaction");
+
+        Ok(SimulateOutcome {
+            gas_used,
+            gas_estimate,
+            events,
+        })
+    }
+
+    pub fn do_check_tx_raw(&self, raw_tx: &[u8]) -> AppResult<()> {
+        let tx = from_json_slice(raw_tx)?;
+
+        self.do_check_tx(&tx)
+    }
+
+    /// Perform stateless checks on a transaction before it's admitted into the
+    /// mempool.
+    pub fn do_check_tx(&self, tx: &Tx) -> AppResult<()> {
+        check_memo(tx)
+    }
+
     // TODO: we need to think about what to do if the flush fails here?
     pub fn do_commit(&self) -> AppResult<()> {
         self.db.commit()?;
@@ -315,18 +589,34 @@ where
         };
 
         // use the state storage at the given version to perform the query
-        let store = self.db.state_storage(version);
+        let store = self.state_storage(version);
+        let cfg = CONFIG.load(&store)?;
         let block = LAST_FINALIZED_BLOCK.load(&store)?;
 
         process_query(
             self.vm.clone(),
-            Box::new(store),
-            GasTracker::new(self.query_gas_limit),
+            store,
+            GasTracker::new(self.query_gas_limit).with_config(cfg.gas_config),
             block,
             req,
         )
     }
 
+    pub fn do_query_contract_gas_used_raw(&self, raw_contract: &[u8]) -> AppResult<Vec<u8>> {
+        let contract = from_json_slice(raw_contract)?;
+        let gas_used = self.do_query_contract_gas_used(&contract);
+        Ok(to_json_vec(&gas_used)?)
+    }
+
+    /// Return the total gas consumed by a contract's entry points, excluding
+    /// queries, in the blocks this node has executed since it was started.
+    ///
+    /// This is read from the node's own metrics rather than the chain's state,
+    /// so it may differ between nodes, and can't be queried by contracts.
+    pub fn do_query_contract_gas_used(&self, contract: &Addr) -> u64 {
+        self.gas_metrics.contract_gas_used(contract)
+    }
+
     /// Performs a raw query of the app's underlying key-value store.
     /// Returns two values:
     /// - the value corresponding to the given key; `None` if the key doesn't exist;
@@ -345,123 +635,375 @@ where
             Some(height)
         };
 
+        let key = match &self.namespace {
+            Some(namespace) => concat(namespace, key),
+            None => key.to_vec(),
+        };
+
         let proof = if prove {
-            Some(to_json_vec(&self.db.prove(key, version)?)?)
+            Some(to_json_vec(&self.db.prove(&key, version)?)?)
         } else {
             None
         };
 
-        let value = self.db.state_storage(version).read(key);
+        let value = self.db.state_storage(version).read(&key);
 
         Ok((value, proof))
     }
 }
 
-fn process_tx<S, VM>(vm: VM, storage: S, block: BlockInfo, tx: Tx) -> AppResult<Vec<Event>>
+fn process_tx<S, VM>(
+    vm: VM,
+    storage: S,
+    gas_tracker: GasTracker,
+    block: BlockInfo,
+    tx: Tx,
+    simulate: bool,
+) -> AppResult<Vec<Event>>
 where
     S: Storage + Clone + 'static,
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
-    let mut events = vec![];
-
-    // create buffer storage and gas tracker for this tx
-    let buffer = Shared::new(Buffer::new(storage, None));
-    let gas_tracker = GasTracker::new_limited(tx.gas_limit);
+    // a tx may be proposed in a block without having passed `CheckTx`, so the
+    // checks done there must be repeated.
+    check_memo(&tx)?;
 
     // call the sender account's `before_tx` method.
-    // if this fails, abort, discard uncommitted state changes.
-    events.extend(do_before_tx(
+    // if this fails, abort; the tx has no effect on the state. otherwise,
+    // update the account state. as long as authentication succeeds, regardless
+    // of whether the message are successful, we update account state.
+    let mut events = run_atomically(storage.clone(), |buffer| {
+        do_before_tx(
+            vm.clone(),
+            Box::new(buffer),
+            gas_tracker.clone(),
+            block.clone(),
+            &tx,
+            simulate,
+        )
+    })?;
+
+    if let Some(memo) = &tx.memo {
+        events.push(Event::memo(memo));
+    }
+
+    // now that the tx is authenticated, we loop through the messages and
+    // execute them one by one, then call the sender account's `after_tx`
+    // method.
+    // the messages are executed atomically: if any one of them fails, abort
+    // and discard the changes made by all of them.
+    let buffer = Shared::new(Buffer::new(storage.clone(), None));
+    let gas_used_before = gas_tracker.used();
+    let msgs_result = process_msgs(
         vm.clone(),
         Box::new(buffer.share()),
         gas_tracker.clone(),
         block.clone(),
         &tx,
-    )?);
+    );
 
-    // update the account state. as long as authentication succeeds, regardless
-    // of whether the message are successful, we update account state. if auth
-    // fails, we don't update account state.
-    buffer.write_access().commit();
+    let tx_outcome = TxOutcome {
+        error: msgs_result.as_ref().err().map(|err| err.to_string()),
+        events: msgs_result.as_ref().map_or(0, |events| events.len() as u32),
+        gas_used: gas_tracker.used() - gas_used_before,
+        gas_refund: compute_gas_refund(&storage, &gas_tracker)?,
+    };
+
+    match msgs_result {
+        Ok(msg_events) => {
+            // `after_tx` can still reject the tx, in which case the changes
+            // made by the messages are discarded as well.
+            let after_tx_events = call_after_tx(
+                vm,
+                Box::new(buffer.share()),
+                gas_tracker,
+                block,
+                &tx,
+                simulate,
+                tx_outcome,
+            )?;
+
+            buffer.disassemble().consume();
+
+            events.extend(msg_events);
+            events.extend(after_tx_events);
+
+            Ok(events)
+        },
+        Err(err) => {
+            // the changes made by the messages are discarded along with the
+            // buffer, but still let the account react to the failure. like
+            // those made by `before_tx`, the changes made by `after_tx` are
+            // persisted. the tx fails with the message's error regardless of
+            // whether `after_tx` succeeds, but if it fails too, its error is
+            // reported alongside.
+            let after_tx_result = run_atomically(storage, |buffer| {
+                call_after_tx(
+                    vm,
+                    Box::new(buffer),
+                    gas_tracker,
+                    block,
+                    &tx,
+                    simulate,
+                    tx_outcome,
+                )
+            });
+
+            match after_tx_result {
+                Ok(_) => Err(err),
+                Err(after_tx_error) => Err(AppError::AfterTxFailed {
+                    msgs_error: Box::new(err),
+                    after_tx_error: Box::new(after_tx_error),
+                }),
+            }
+        },
+    }
+}
+
+/// Return the size of the message, for enforcing the size limit.
+///
+/// An upload is sized by its code, which makes up the bulk of it, so that the
+/// code doesn't have to be serialized just to be measured.
+fn msg_size(msg: &Message) -> AppResult<usize> {
+    match msg {
+        Message::Upload { code } => Ok(code.len()),
+        _ => Ok(to_json_vec(msg)?.len()),
+    }
+}
+
+/// Ensure the transaction's memo, if any, doesn't exceed the maximum length.
+fn check_memo(tx: &Tx) -> AppResult<()> {
+    if let Some(memo) = &tx.memo {
+        if memo.len() > MAX_MEMO_LENGTH {
+            return Err(AppError::MemoTooLong {
+                length: memo.len(),
+                max: MAX_MEMO_LENGTH,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Remove the results of transactions delivered in blocks that have fallen out
+/// of the retention window, i.e. those at or below `height - retention`.
+fn prune_tx_results(storage: &mut dyn Storage, height: u64, retention: u64) -> AppResult<()> {
+    let Some(cutoff) = height.checked_sub(retention) else {
+        return Ok(());
+    };
+
+    let expired = TX_HASHES
+        .range(
+            storage,
+            None,
+            Some(Bound::Inclusive(cutoff)),
+            Order::Ascending,
+        )
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (height, tx_hashes) in expired {
+        for tx_hash in &tx_hashes {
+            TX_RESULTS.remove(storage, tx_hash);
+        }
+        TX_HASHES.remove(storage, height);
+    }
+
+    Ok(())
+}
+
+/// Compute the amount of unused gas to be refunded to the transaction's sender,
+/// rounded down. The gas reserved for `after_tx` isn't considered unused, so
+/// it's never refunded.
+fn compute_gas_refund(storage: &dyn Storage, gas_tracker: &GasTracker) -> AppResult<u64> {
+    let Some(remaining) = gas_tracker.remaining() else {
+        return Ok(0);
+    };
+
+    let reserve = gas_tracker.config().after_tx_reserve;
+    let ratio = CONFIG.load(storage)?.gas_refund_ratio;
+
+    Ok(Uint128::new(remaining.saturating_sub(reserve).into())
+        .checked_mul_dec_floor(ratio)?
+        .try_into()?)
+}
+
+/// Call the sender account's `after_tx` entry point with the refunded gas
+/// withheld, so that it can't consume gas that has already been promised back
+/// to the sender. The gas it does consume is charged to the transaction.
+fn call_after_tx<VM>(
+    vm: VM,
+    storage: Box<dyn Storage>,
+    gas_tracker: GasTracker,
+    block: BlockInfo,
+    tx: &Tx,
+    simulate: bool,
+    tx_outcome: TxOutcome,
+) -> AppResult<Vec<Event>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    let after_tx_gas_tracker = match gas_tracker.remaining() {
+        Some(remaining) => GasTracker::new_limited(remaining - tx_outcome.gas_refund),
+        None => GasTracker::new_limitless(),
+    }
+    .with_config(gas_tracker.config())
+    .with_simulate(gas_tracker.simulate())
+    .with_metrics(gas_tracker.metrics())
+    .with_bank_store(gas_tracker.bank_store());
+
+    let result = do_after_tx(
+        vm,
+        storage,
+        after_tx_gas_tracker.clone(),
+        block,
+        tx,
+        simulate,
+        tx_outcome,
+    );
+
+    // This can't run out of gas, as `after_tx` was limited to less than what
+    // remains.
+    gas_tracker.consume(after_tx_gas_tracker.used(), "after_tx")?;
+
+    result
+}
+
+fn process_msgs<VM>(
+    vm: VM,
+    storage: Box<dyn Storage>,
+    gas_tracker: GasTracker,
+    block: BlockInfo,
+    tx: &Tx,
+) -> AppResult<Vec<Event>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    let mut events = vec![];
 
-    // now that the tx is authenticated, we loop through the messages and
-    // execute them one by one.
-    // if any one of the msgs fails, the entire tx fails; abort, discard
-    // uncommitted changes (the changes from the before_tx call earlier are
-    // persisted)
     for (_idx, msg) in tx.msgs.iter().enumerate() {
         #[cfg(feature = "tracing")]
         debug!(idx = _idx, "Processing message");
 
         events.extend(process_msg(
             vm.clone(),
-            Box::new(buffer.share()),
+            storage.clone(),
             gas_tracker.clone(),
+            0,
             block.clone(),
             tx.sender.clone(),
-            msg.clone(),
+            msg,
         )?);
     }
 
-    // call the sender account's `after_tx` method.
-    // if this fails, abort, discard uncommitted state changes from messages.
-    // state changes from `before_tx` are always kept.
-    events.extend(do_after_tx(
-        vm,
-        Box::new(buffer.share()),
-        gas_tracker.clone(),
-        block,
-        &tx,
-    )?);
-
-    // all messages succeeded. commit the state changes
-    buffer.write_access().commit();
-
     Ok(events)
 }
 
+/// Process a single message.
+///
+/// The message is borrowed, so that a caller can still record it after it
+/// fails, without keeping a copy of every message in case it does.
 pub fn process_msg<VM>(
     vm: VM,
     mut storage: Box<dyn Storage>,
     gas_tracker: GasTracker,
+    msg_depth: u32,
     block: BlockInfo,
     sender: Addr,
-    msg: Message,
+    msg: &Message,
 ) -> AppResult<Vec<Event>>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
+    // Reject oversized messages before doing any work on them.
+    let size = msg_size(msg)?;
+    let max = CONFIG.load(&storage)?.max_msg_size as usize;
+    if size > max {
+        return Err(AppError::MessageTooLarge { size, max });
+    }
+
     match msg {
-        Message::Configure { new_cfg } => do_configure(&mut storage, &sender, &new_cfg),
+        Message::Configure { new_cfg } => do_configure(&mut storage, &sender, new_cfg),
         Message::Transfer { to, coins } => do_transfer(
             vm,
             storage,
             gas_tracker,
+            msg_depth,
             block,
             sender.clone(),
-            to,
-            coins,
+            to.clone(),
+            coins.clone(),
             true,
         ),
-        Message::Upload { code } => do_upload(&mut storage, &sender, code.into()),
+        Message::TransferMulti { outputs } => do_transfer_multi(
+            vm,
+            storage,
+            gas_tracker,
+            msg_depth,
+            block,
+            sender.clone(),
+            outputs.clone(),
+        ),
+        Message::Upload { code } => {
+            do_upload(vm, &mut storage, gas_tracker, &sender, code.to_vec())
+        },
+        Message::RemoveCode { code_hash } => {
+            do_remove_code(&mut storage, &sender, code_hash.clone())
+        },
         Message::Instantiate {
             code_hash,
             msg,
             salt,
             funds,
             admin,
+            receive_hook,
+            strict_funds,
+            entry_points,
+            raw_queries,
         } => do_instantiate(
             vm,
             storage,
             gas_tracker,
+            msg_depth,
             block,
             sender,
+            code_hash.clone(),
+            msg,
+            salt.clone(),
+            funds.clone(),
+            admin.clone(),
+            *receive_hook,
+            *strict_funds,
+            entry_points.clone(),
+            *raw_queries,
+        ),
+        Message::InstantiateSequential {
             code_hash,
-            &msg,
-            salt,
+            msg,
             funds,
             admin,
+            receive_hook,
+            strict_funds,
+            entry_points,
+            raw_queries,
+        } => do_instantiate_sequential(
+            vm,
+            storage,
+            gas_tracker,
+            msg_depth,
+            block,
+            sender,
+            code_hash.clone(),
+            msg,
+            funds.clone(),
+            admin.clone(),
+            *receive_hook,
+            *strict_funds,
+            entry_points.clone(),
+            *raw_queries,
         ),
         Message::Execute {
             contract,
@@ -471,11 +1013,12 @@ where
             vm,
             storage,
             gas_tracker,
+            msg_depth,
             block,
-            contract,
+            contract.clone(),
             sender,
-            &msg,
-            funds,
+            msg,
+            funds.clone(),
         ),
         Message::Migrate {
             contract,
@@ -485,11 +1028,21 @@ where
             vm,
             storage,
             gas_tracker,
+            msg_depth,
             block,
-            contract,
+            contract.clone(),
             sender,
-            new_code_hash,
-            &msg,
+            new_code_hash.clone(),
+            msg,
+        ),
+        Message::BankAdmin { msg } => do_bank_admin(
+            vm,
+            storage,
+            gas_tracker,
+            msg_depth,
+            block,
+            sender,
+            msg.clone(),
         ),
     }
 }
@@ -507,33 +1060,57 @@ where
 {
     match req {
         QueryRequest::Info {} => query_info(&storage).map(QueryResponse::Info),
+        QueryRequest::BlockInfo {} => Ok(QueryResponse::BlockInfo(block)),
         QueryRequest::Balance { address, denom } => {
             query_balance(vm, storage, block, gas_tracker, address, denom)
                 .map(QueryResponse::Balance)
         },
+        QueryRequest::BalanceExact { address, denom } => {
+            query_balance_exact(vm, storage, block, gas_tracker, address, denom)
+                .map(QueryResponse::BalanceExact)
+        },
         QueryRequest::Balances {
             address,
             start_after,
             limit,
-        } => query_balances(vm, storage, block, gas_tracker, address, start_after, limit)
-            .map(QueryResponse::Balances),
+            order,
+        } => query_balances(
+            vm,
+            storage,
+            block,
+            gas_tracker,
+            address,
+            start_after,
+            limit,
+            order,
+        )
+        .map(QueryResponse::Balances),
         QueryRequest::Supply { denom } => {
             query_supply(vm, storage, block, gas_tracker, denom).map(QueryResponse::Supply)
         },
-        QueryRequest::Supplies { start_after, limit } => {
-            query_supplies(vm, storage, block, gas_tracker, start_after, limit)
-                .map(QueryResponse::Supplies)
-        },
+        QueryRequest::Supplies {
+            start_after,
+            limit,
+            order,
+        } => query_supplies(vm, storage, block, gas_tracker, start_after, limit, order)
+            .map(QueryResponse::Supplies),
         QueryRequest::Code { hash } => query_code(&storage, hash).map(QueryResponse::Code),
-        QueryRequest::Codes { start_after, limit } => {
-            query_codes(&storage, start_after, limit).map(QueryResponse::Codes)
-        },
+        QueryRequest::Codes {
+            start_after,
+            limit,
+            order,
+        } => query_codes(&storage, start_after, limit, order).map(QueryResponse::Codes),
         QueryRequest::Account { address } => {
             query_account(&storage, address).map(QueryResponse::Account)
         },
-        QueryRequest::Accounts { start_after, limit } => {
-            query_accounts(&storage, start_after, limit).map(QueryResponse::Accounts)
+        QueryRequest::IsContract { address } => {
+            query_is_contract(&storage, address).map(QueryResponse::IsContract)
         },
+        QueryRequest::Accounts {
+            start_after,
+            limit,
+            order,
+        } => query_accounts(&storage, start_after, limit, order).map(QueryResponse::Accounts),
         QueryRequest::WasmRaw { contract, key } => {
             query_wasm_raw(storage, contract, key).map(QueryResponse::WasmRaw)
         },
@@ -541,7 +1118,30 @@ where
             query_wasm_smart(vm, storage, block, gas_tracker, contract, msg)
                 .map(QueryResponse::WasmSmart)
         },
+        QueryRequest::TxResult { hash } => {
+            query_tx_result(&storage, hash).map(QueryResponse::TxResult)
+        },
+    }
+}
+
+fn check_core_contracts<VM>(mut vm: VM, storage: &dyn Storage, cfg: &Config) -> AppResult<()>
+where
+    VM: Vm,
+    AppError: From<VM::Error>,
+{
+    let account = load_bank(storage, cfg)?;
+    let code = CODES.load(storage, &account.code_hash)?;
+
+    for name in BANK_ENTRY_POINTS {
+        if !vm.has_entry_point(&code.byte_code, &account.code_hash, name)? {
+            return Err(AppError::MissingEntryPoint {
+                contract: cfg.bank.clone(),
+                name,
+            });
+        }
     }
+
+    Ok(())
 }
 
 pub fn has_permission(permission: &Permission, owner: Option<&Addr>, sender: &Addr) -> bool {
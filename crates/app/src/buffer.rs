@@ -1,4 +1,5 @@
 use {
+    crate::{GasTracker, Shared},
     grug_types::{Batch, Op, Order, Record, Storage},
     std::{
         cmp::Ordering,
@@ -32,6 +33,40 @@ impl<S: Clone> Buffer<S> {
     pub fn disassemble(self) -> (S, Batch) {
         (self.base, self.pending)
     }
+
+    /// Take a checkpoint of the pending ops and of the gas used so far, before
+    /// running an action that may fail but whose failure is to be handled,
+    /// such as a submessage that requests a reply on error.
+    pub fn gas_checkpoint(&self, gas_tracker: &GasTracker) -> Checkpoint {
+        Checkpoint {
+            pending: self.pending.clone(),
+            gas_used: gas_tracker.used(),
+        }
+    }
+
+    /// Discard the ops written since the checkpoint was taken.
+    ///
+    /// Gas is not rolled back: the work done by the failed action has been
+    /// performed, so it's still charged for, and the gas tracker continues
+    /// counting from where it is.
+    pub fn rollback_to(&mut self, checkpoint: Checkpoint) {
+        self.pending = checkpoint.pending;
+    }
+}
+
+/// A snapshot of a buffer's pending ops, to be rolled back to. See
+/// [`Buffer::gas_checkpoint`].
+pub struct Checkpoint {
+    pending: Batch,
+    gas_used: u64,
+}
+
+impl Checkpoint {
+    /// Return the amount of gas that had been used when the checkpoint was
+    /// taken.
+    pub fn gas_used(&self) -> u64 {
+        self.gas_used
+    }
 }
 
 impl<S: Storage + Clone> Buffer<S> {
@@ -49,6 +84,26 @@ impl<S: Storage + Clone> Buffer<S> {
     }
 }
 
+/// Run the given action in a new buffer layered on top of `base`.
+///
+/// This is the atomicity boundary: if the action succeeds, its writes are
+/// flushed to `base`; if it fails, they are discarded, leaving `base` untouched.
+/// This way, handlers don't need to undo their own partial writes.
+///
+/// The action must not retain any share of the buffer beyond its return.
+pub fn run_atomically<S, F, T, E>(base: S, action: F) -> Result<T, E>
+where
+    S: Storage + Clone,
+    F: FnOnce(Shared<Buffer<S>>) -> Result<T, E>,
+{
+    let buffer = Shared::new(Buffer::new(base, None));
+    let output = action(buffer.share())?;
+
+    buffer.disassemble().consume();
+
+    Ok(output)
+}
+
 impl<S: Storage + Clone> Storage for Buffer<S> {
     fn read(&self, key: &[u8]) -> Option<Vec<u8>> {
         match self.pending.get(key) {
@@ -202,7 +257,7 @@ where
 
 #[cfg(test)]
 mod tests {
-    use {super::*, grug_types::MockStorage};
+    use {super::*, crate::AppError, grug_types::MockStorage};
 
     // illustration of this test case:
     //
@@ -250,5 +305,64 @@ mod tests {
         assert_eq!(collect_records(&buffer, Order::Descending), merged);
     }
 
+    #[test]
+    fn run_atomically_works() {
+        let mut base = Shared::new(MockStorage::new());
+        base.write(&[1], &[1]);
+
+        // The action writes and deletes, then fails midway. The base store
+        // should be untouched.
+        let result = run_atomically(base.share(), |mut buffer| {
+            buffer.write(&[2], &[2]);
+            buffer.remove(&[1]);
+            Err::<(), _>("something went wrong")
+        });
+        assert!(result.is_err());
+        assert_eq!(collect_records(&base, Order::Ascending), vec![(
+            vec![1],
+            vec![1]
+        )]);
+
+        // The action succeeds. Its writes should be committed.
+        let result = run_atomically(base.share(), |mut buffer| {
+            buffer.write(&[2], &[2]);
+            buffer.remove(&[1]);
+            Ok::<_, ()>(())
+        });
+        assert!(result.is_ok());
+        assert_eq!(collect_records(&base, Order::Ascending), vec![(
+            vec![2],
+            vec![2]
+        )]);
+    }
+
+    #[test]
+    fn rolling_back_keeps_gas_consumed() {
+        let gas_tracker = GasTracker::new_limitless();
+
+        let mut buffer = Buffer::new(MockStorage::new(), None);
+        buffer.write(&[1], &[1]);
+
+        let checkpoint = buffer.gas_checkpoint(&gas_tracker);
+        assert_eq!(checkpoint.gas_used(), 0);
+
+        // A submessage writes and consumes gas, then fails.
+        let result = (|| {
+            buffer.write(&[2], &[2]);
+            buffer.remove(&[1]);
+            gas_tracker.consume(100, "submessage")?;
+            Err::<(), _>(AppError::Unauthorized)
+        })();
+        assert!(result.is_err());
+
+        // Its writes are discarded, but the gas it consumed is still charged.
+        buffer.rollback_to(checkpoint);
+        assert_eq!(collect_records(&buffer, Order::Ascending), vec![(
+            vec![1],
+            vec![1]
+        )]);
+        assert_eq!(gas_tracker.used(), 100);
+    }
+
     // TODO: add fuzz test
 }
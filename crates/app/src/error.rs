@@ -1,5 +1,6 @@
 use {
-    grug_types::{Addr, Hash, StdError},
+    crate::OutOfGasError,
+    grug_types::{Addr, Coins, Hash, StdError, Udec128, Uint128},
     thiserror::Error,
 };
 
@@ -11,6 +12,12 @@ pub enum AppError {
     #[error("VM error: {0}")]
     Vm(String),
 
+    #[error(transparent)]
+    OutOfGas(#[from] OutOfGasError),
+
+    #[error("contract error: {msg}")]
+    Contract { msg: String },
+
     #[error("DB error: {0}")]
     Db(String),
 
@@ -35,14 +42,93 @@ pub enum AppError {
     #[error("sender is not the admin! sender: {sender}, admin: {admin}")]
     NotAdmin { sender: Addr, admin: Addr },
 
+    #[error("sender is not the admin of denom `{denom}`! sender: {sender}")]
+    NotDenomAdmin { denom: String, sender: Addr },
+
     #[error("code with hash `{code_hash}` already exists")]
     CodeExists { code_hash: Hash },
 
+    #[error("code with hash `{code_hash}` is still used by contracts: {contracts:?}")]
+    CodeInUse {
+        code_hash: Hash,
+        contracts: Vec<Addr>,
+    },
+
     #[error("account with address `{address}` already exists")]
     AccountExists { address: Addr },
 
+    #[error("account with address `{address}` not found")]
+    AccountNotFound { address: Addr },
+
+    #[error("bank contract not found! address: {address}")]
+    BankContractMissing { address: Addr },
+
     #[error("code hash is not allowed as IBC client: `{code_hash}`")]
     NotAllowedClient { code_hash: Hash },
+
+    #[error("code `{code_hash}` does not declare an interface version")]
+    MissingInterfaceVersion { code_hash: Hash },
+
+    #[error("code `{code_hash}` is built against interface version {version}, but the host only supports version {supported}")]
+    UnsupportedInterfaceVersion {
+        code_hash: Hash,
+        version: u32,
+        supported: u32,
+    },
+
+    #[error("max message depth exceeded! depth: {depth}, max: {max}")]
+    CallDepthExceeded { depth: u32, max: u32 },
+
+    #[error("too many submessages! count: {count}, max: {max}")]
+    TooManySubmessages { count: usize, max: u32 },
+
+    #[error("transfer cap exceeded for denom `{denom}`! cap: {cap}, attempted: {amount}")]
+    TransferCapExceeded {
+        denom: String,
+        cap: Uint128,
+        amount: Uint128,
+    },
+
+    #[error("denom `{denom}` is not registered")]
+    UnregisteredDenom { denom: String },
+
+    #[error("too many transfer outputs! count: {count}, max: {max}")]
+    TooManyTransferOutputs { count: usize, max: usize },
+
+    #[error("memo too long! length: {length}, max: {max}")]
+    MemoTooLong { length: usize, max: usize },
+
+    #[error("message too large! size: {size}, max: {max}")]
+    MessageTooLarge { size: usize, max: usize },
+
+    #[error("{msgs_error}; `after_tx` also failed: {after_tx_error}")]
+    AfterTxFailed {
+        msgs_error: Box<AppError>,
+        after_tx_error: Box<AppError>,
+    },
+
+    #[error("core contract `{contract}` does not export the required entry point `{name}`")]
+    MissingEntryPoint { contract: Addr, name: &'static str },
+
+    #[error("entry point `{name}` is not allowed to be called on contract `{contract}`")]
+    EntryPointNotAllowed { contract: Addr, name: String },
+
+    #[error("insufficient funds! address: {address}, denom: {denom}, balance: {balance}, required: {amount}")]
+    InsufficientFunds {
+        address: Addr,
+        denom: String,
+        balance: Uint128,
+        amount: Uint128,
+    },
+
+    #[error("invalid fee denom: `{denom}`")]
+    InvalidFeeDenom { denom: String },
+
+    #[error("gas refund ratio must not be greater than one, got: {ratio}")]
+    InvalidGasRefundRatio { ratio: Udec128 },
+
+    #[error("contract `{contract}` does not accept funds! funds: {funds}")]
+    UnexpectedFunds { contract: Addr, funds: Coins },
 }
 
 pub type AppResult<T> = core::result::Result<T, AppError>;
@@ -4,6 +4,11 @@
 // attribute that impersonates state machine attributes in order to fool indexers.
 pub const CONTRACT_ADDRESS_KEY: &str = "_contract_address";
 
+// Similarly, the types of custom events emitted by contracts are prefixed, so
+// that a contract can't emit an event that impersonates one emitted by the
+// state machine, such as `transfer`. Unprefixed types are reserved for the app.
+pub const CONTRACT_EVENT_TYPE_PREFIX: &str = "wasm-";
+
 // Below: IBC event attribute keys.
 // For IBC events, we keep them consistent with ibc-go, which may make relayer
 // itegration easier.
@@ -3,12 +3,19 @@ use tracing::{debug, info, warn};
 use {
     crate::{
         call_in_0_out_1_handle_response, call_in_1_out_1_handle_response,
-        call_in_2_out_1_handle_response, has_permission, AppError, AppResult, GasTracker, Vm,
-        ACCOUNTS, CHAIN_ID, CODES, CONFIG,
+        call_in_2_out_1_handle_response, has_permission, AppError, AppResult, GasTracker,
+        StorageProvider, Vm, ACCOUNTS, BLOCK_TRANSFERS, CHAIN_ID, CODES, CONFIG,
+        CONTRACT_NAMESPACE, INSTANTIATE_COUNTER, INTERFACE_VERSION,
     },
     grug_types::{
-        hash, Account, Addr, BankMsg, Binary, BlockInfo, Coins, Config, Context, Event, Hash, Json,
-        Storage, SubMsgResult, Tx,
+        hash, Account, Addr, BankAdminMsg, BankMsg, BankStore, Binary, BlockInfo, Code, Coins,
+        Config, Context, DenomKind, Event, Hash, Json, Number, NumberConst, Order, StdError,
+        StdResult, Storage, SubMsgResult, Tx, TxOutcome, Udec128, Uint128, FACTORY_DENOM_PREFIX,
+        MAX_TRANSFER_OUTPUTS,
+    },
+    std::{
+        collections::{BTreeMap, BTreeSet},
+        str::FromStr,
     },
 };
 
@@ -46,20 +53,50 @@ fn _do_configure(storage: &mut dyn Storage, sender: &Addr, new_cfg: &Config) ->
         });
     }
 
+    check_fee_denom(&new_cfg.fee_denom)?;
+    check_gas_refund_ratio(new_cfg.gas_refund_ratio)?;
+
     // save the new config
     CONFIG.save(storage, new_cfg)?;
 
-    Ok(Event::new("configure").add_attribute("sender", sender))
+    Ok(Event::configure(sender))
+}
+
+/// Ensure the chain's fee denom is a valid denom.
+pub(crate) fn check_fee_denom(denom: &str) -> AppResult<()> {
+    if DenomKind::of(denom) == DenomKind::Invalid {
+        return Err(AppError::InvalidFeeDenom {
+            denom: denom.to_string(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Ensure the chain's gas refund ratio is no greater than one, so that no more
+/// than the unused gas is refunded.
+pub(crate) fn check_gas_refund_ratio(ratio: Udec128) -> AppResult<()> {
+    if ratio > Udec128::ONE {
+        return Err(AppError::InvalidGasRefundRatio { ratio });
+    }
+
+    Ok(())
 }
 
 // ---------------------------------- upload -----------------------------------
 
-pub fn do_upload(
+pub fn do_upload<VM>(
+    vm: VM,
     storage: &mut dyn Storage,
+    gas_tracker: GasTracker,
     uploader: &Addr,
     code: Vec<u8>,
-) -> AppResult<Vec<Event>> {
-    match _do_upload(storage, uploader, code) {
+) -> AppResult<Vec<Event>>
+where
+    VM: Vm,
+    AppError: From<VM::Error>,
+{
+    match _do_upload(vm, storage, gas_tracker, uploader, code) {
         Ok((event, _code_hash)) => {
             #[cfg(feature = "tracing")]
             info!(code_hash = _code_hash.to_string(), "Uploaded code");
@@ -74,11 +111,17 @@ pub fn do_upload(
 }
 
 // Return the hash of the code that is stored, for logging purpose.
-fn _do_upload(
+fn _do_upload<VM>(
+    mut vm: VM,
     storage: &mut dyn Storage,
+    gas_tracker: GasTracker,
     uploader: &Addr,
     code: Vec<u8>,
-) -> AppResult<(Event, Hash)> {
+) -> AppResult<(Event, Hash)>
+where
+    VM: Vm,
+    AppError: From<VM::Error>,
+{
     // Make sure the user has the permission to upload contracts
     let cfg = CONFIG.load(storage)?;
     if !has_permission(&cfg.permissions.upload, cfg.owner.as_ref(), uploader) {
@@ -91,12 +134,99 @@ fn _do_upload(
         return Err(AppError::CodeExists { code_hash });
     }
 
-    CODES.save(storage, &code_hash, &code)?;
+    // Reading the interface version below requires the VM to compile the code,
+    // which takes time proportional to the code's size, so charge for it.
+    let per_byte = gas_tracker.config().upload_per_byte;
+    gas_tracker.consume((code.len() as u64).saturating_mul(per_byte), "upload")?;
 
-    Ok((
-        Event::new("upload").add_attribute("code_hash", &code_hash),
-        code_hash,
-    ))
+    // Make sure the code is built against an interface version that the host
+    // supports. Otherwise, calls into it may fail in unexpected ways later.
+    let interface_version = match vm.interface_version(&code, &code_hash)? {
+        Some(INTERFACE_VERSION) => INTERFACE_VERSION,
+        Some(version) => {
+            return Err(AppError::UnsupportedInterfaceVersion {
+                code_hash,
+                version,
+                supported: INTERFACE_VERSION,
+            });
+        },
+        None => return Err(AppError::MissingInterfaceVersion { code_hash }),
+    };
+
+    let event = Event::upload(&code_hash, code.len());
+
+    CODES.save(storage, &code_hash, &Code {
+        byte_code: code,
+        interface_version,
+    })?;
+
+    Ok((event, code_hash))
+}
+
+// -------------------------------- remove code --------------------------------
+
+pub fn do_remove_code(
+    storage: &mut dyn Storage,
+    sender: &Addr,
+    code_hash: Hash,
+) -> AppResult<Vec<Event>> {
+    match _do_remove_code(storage, sender, code_hash) {
+        Ok((event, _code_hash)) => {
+            #[cfg(feature = "tracing")]
+            info!(code_hash = _code_hash.to_string(), "Removed code");
+            Ok(vec![event])
+        },
+        Err(err) => {
+            #[cfg(feature = "tracing")]
+            warn!(err = err.to_string(), "Failed to remove code");
+            Err(err)
+        },
+    }
+}
+
+// Return the hash of the code that is removed, for logging purpose.
+fn _do_remove_code(
+    storage: &mut dyn Storage,
+    sender: &Addr,
+    code_hash: Hash,
+) -> AppResult<(Event, Hash)> {
+    // Make sure the sender is authorized to remove codes
+    let cfg = CONFIG.load(storage)?;
+    let Some(owner) = cfg.owner else {
+        return Err(AppError::OwnerNotSet);
+    };
+    if sender != owner {
+        return Err(AppError::NotOwner {
+            sender: sender.clone(),
+            owner,
+        });
+    }
+
+    // Make sure the code exists
+    if !CODES.has(storage, &code_hash) {
+        return Err(StdError::data_not_found::<Code>(code_hash.as_ref()).into());
+    }
+
+    // Make sure no account is using the code. This requires iterating all
+    // accounts, which is expensive, but removing codes should be rare.
+    let contracts = ACCOUNTS
+        .range(storage, None, None, Order::Ascending)
+        .filter_map(|item| match item {
+            Ok((address, account)) if account.code_hash == code_hash => Some(Ok(address)),
+            Ok(_) => None,
+            Err(err) => Some(Err(err)),
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    if !contracts.is_empty() {
+        return Err(AppError::CodeInUse {
+            code_hash,
+            contracts,
+        });
+    }
+
+    CODES.remove(storage, &code_hash);
+
+    Ok((Event::remove_code(&code_hash), code_hash))
 }
 
 // --------------------------------- transfer ----------------------------------
@@ -105,6 +235,7 @@ pub fn do_transfer<VM>(
     vm: VM,
     storage: Box<dyn Storage>,
     gas_tracker: GasTracker,
+    msg_depth: u32,
     block: BlockInfo,
     from: Addr,
     to: Addr,
@@ -119,6 +250,7 @@ where
         vm,
         storage,
         gas_tracker,
+        msg_depth,
         block,
         from.clone(),
         to.clone(),
@@ -145,14 +277,16 @@ where
 
 fn _do_transfer<VM>(
     vm: VM,
-    storage: Box<dyn Storage>,
+    mut storage: Box<dyn Storage>,
     gas_tracker: GasTracker,
+    msg_depth: u32,
     block: BlockInfo,
     from: Addr,
     to: Addr,
     coins: Coins,
     // Whether to call the receipient account's `receive` entry point following
-    // the transfer, to inform it that the transfer has happened.
+    // the transfer, to inform it that the transfer has happened, if it has
+    // opted into it.
     // - `true` when handling `Message::Transfer`
     // - `false` when handling `Message::{Instantaite,Execute}`
     do_receive: bool,
@@ -161,9 +295,19 @@ where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
+    // Transferring an empty set of coins is a no-op: the bank contract isn't
+    // called, and neither is the recipient's `receive` entry point. This saves
+    // us from spinning up VM instances that do nothing.
+    if coins.is_empty() {
+        return Ok(vec![]);
+    }
+
     let chain_id = CHAIN_ID.load(&storage)?;
     let cfg = CONFIG.load(&storage)?;
-    let account = ACCOUNTS.load(&storage, &cfg.bank)?;
+    let account = load_bank(&storage, &cfg)?;
+
+    ensure_registered_denoms(&cfg, &coins)?;
+    enforce_transfer_caps(&mut storage, &cfg, &coins)?;
 
     let ctx = Context {
         chain_id,
@@ -172,13 +316,19 @@ where
         sender: None,
         funds: None,
         simulate: None,
+        tx_outcome: None,
+    };
+    let msg = BankMsg::Transfer {
+        from: from.clone(),
+        to: to.clone(),
+        coins: coins.clone(),
     };
-    let msg = BankMsg { from, to, coins };
 
     let mut events = call_in_1_out_1_handle_response(
         vm.clone(),
         storage.clone(),
         gas_tracker.clone(),
+        msg_depth,
         "bank_execute",
         &account.code_hash,
         &ctx,
@@ -187,38 +337,286 @@ where
     )?;
 
     if do_receive {
-        events.extend(_do_receive(vm, storage, gas_tracker, ctx.block, msg)?);
+        events.extend(_do_receive(
+            vm,
+            storage,
+            gas_tracker,
+            msg_depth,
+            ctx.block,
+            from,
+            to,
+            coins,
+        )?);
+    }
+
+    Ok(events)
+}
+
+// ------------------------------ transfer multi -------------------------------
+
+pub fn do_transfer_multi<VM>(
+    vm: VM,
+    storage: Box<dyn Storage>,
+    gas_tracker: GasTracker,
+    msg_depth: u32,
+    block: BlockInfo,
+    from: Addr,
+    outputs: Vec<(Addr, Coins)>,
+) -> AppResult<Vec<Event>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    #[cfg(feature = "tracing")]
+    let num_outputs = outputs.len();
+
+    match _do_transfer_multi(
+        vm,
+        storage,
+        gas_tracker,
+        msg_depth,
+        block,
+        from.clone(),
+        outputs,
+    ) {
+        Ok(events) => {
+            #[cfg(feature = "tracing")]
+            info!(
+                from = from.to_string(),
+                num_outputs = num_outputs,
+                "Transferred coins to multiple recipients"
+            );
+            Ok(events)
+        },
+        Err(err) => {
+            #[cfg(feature = "tracing")]
+            warn!(
+                err = err.to_string(),
+                "Failed to transfer coins to multiple recipients"
+            );
+            Err(err)
+        },
+    }
+}
+
+fn _do_transfer_multi<VM>(
+    vm: VM,
+    mut storage: Box<dyn Storage>,
+    gas_tracker: GasTracker,
+    msg_depth: u32,
+    block: BlockInfo,
+    from: Addr,
+    outputs: Vec<(Addr, Coins)>,
+) -> AppResult<Vec<Event>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    // Bound the work done below, which isn't metered.
+    if outputs.len() > MAX_TRANSFER_OUTPUTS {
+        return Err(AppError::TooManyTransferOutputs {
+            count: outputs.len(),
+            max: MAX_TRANSFER_OUTPUTS,
+        });
+    }
+
+    // Merge the outputs to the same recipient, and drop recipients that end up
+    // with nothing, same as an empty `Message::Transfer` being a no-op.
+    let mut merged = BTreeMap::<Addr, Coins>::new();
+    for (to, coins) in outputs {
+        merged
+            .entry(to)
+            .or_insert_with(Coins::new_empty)
+            .checked_add_assign(&coins)?;
+    }
+    merged.retain(|_, coins| !coins.is_empty());
+
+    if merged.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let total = merged
+        .values()
+        .try_fold(Coins::new_empty(), |total, coins| total.checked_add(coins))?;
+
+    let chain_id = CHAIN_ID.load(&storage)?;
+    let cfg = CONFIG.load(&storage)?;
+    let account = load_bank(&storage, &cfg)?;
+
+    ensure_registered_denoms(&cfg, &total)?;
+    enforce_transfer_caps(&mut storage, &cfg, &total)?;
+
+    // Make sure the sender can afford all the outputs combined before making
+    // any transfer, so that a shortfall is reported against the total.
+    ensure_sufficient_funds(storage.clone(), &gas_tracker, &from, &total)?;
+
+    let ctx = Context {
+        chain_id,
+        block,
+        contract: cfg.bank,
+        sender: None,
+        funds: None,
+        simulate: None,
+        tx_outcome: None,
+    };
+    let msg = BankMsg::TransferMulti {
+        from: from.clone(),
+        outputs: merged.clone(),
+    };
+
+    // All the transfers are made in a single call to the bank contract. If any
+    // of them fails, the message fails, and they are all reverted.
+    let mut events = call_in_1_out_1_handle_response(
+        vm.clone(),
+        storage.clone(),
+        gas_tracker.clone(),
+        msg_depth,
+        "bank_execute",
+        &account.code_hash,
+        &ctx,
+        false,
+        &msg,
+    )?;
+
+    for (to, coins) in merged {
+        events.extend(_do_receive(
+            vm.clone(),
+            storage.clone(),
+            gas_tracker.clone(),
+            msg_depth,
+            ctx.block.clone(),
+            from.clone(),
+            to,
+            coins,
+        )?);
     }
 
     Ok(events)
 }
 
+/// Error if the chain only allows registered denoms, and any of the coins is
+/// of a denom that isn't registered.
+fn ensure_registered_denoms(cfg: &Config, coins: &Coins) -> AppResult<()> {
+    let Some(registered_denoms) = &cfg.registered_denoms else {
+        return Ok(());
+    };
+
+    for coin in coins {
+        if !registered_denoms.contains(coin.denom) {
+            return Err(AppError::UnregisteredDenom {
+                denom: coin.denom.clone(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Add the coins to the amounts transferred in the current block, and error if
+/// any denom goes over its cap.
+fn enforce_transfer_caps(storage: &mut dyn Storage, cfg: &Config, coins: &Coins) -> AppResult<()> {
+    for coin in coins {
+        let Some(cap) = cfg.transfer_caps.get(coin.denom) else {
+            continue;
+        };
+
+        let transferred = BLOCK_TRANSFERS
+            .may_load(storage, coin.denom)?
+            .unwrap_or(Uint128::ZERO)
+            .checked_add(*coin.amount)?;
+
+        if transferred > *cap {
+            return Err(AppError::TransferCapExceeded {
+                denom: coin.denom.clone(),
+                cap: *cap,
+                amount: transferred,
+            });
+        }
+
+        BLOCK_TRANSFERS.save(storage, coin.denom, &transferred)?;
+    }
+
+    Ok(())
+}
+
+/// Ensure the sender holds enough of each denom in the funds attached to a
+/// message, before any work is done on the message. This reports the shortfall
+/// with a typed error, instead of whichever error the bank contract fails the
+/// transfer with.
+///
+/// Only the denoms in the funds are read, directly from the bank contract's
+/// storage with the gas tracker's bank store, so the bank contract isn't
+/// called. Without a bank store, the check is skipped.
+fn ensure_sufficient_funds(
+    storage: Box<dyn Storage>,
+    gas_tracker: &GasTracker,
+    sender: &Addr,
+    funds: &Coins,
+) -> AppResult<()> {
+    let Some(bank_store) = gas_tracker.bank_store() else {
+        return Ok(());
+    };
+
+    if funds.is_empty() {
+        return Ok(());
+    }
+
+    let cfg = CONFIG.load(&storage)?;
+    let bank_storage = StorageProvider::new(storage, &[CONTRACT_NAMESPACE, &cfg.bank]);
+
+    for coin in funds {
+        let balance = bank_store.balance(&bank_storage, sender, coin.denom)?;
+
+        if balance < *coin.amount {
+            return Err(AppError::InsufficientFunds {
+                address: sender.clone(),
+                denom: coin.denom.clone(),
+                balance,
+                amount: *coin.amount,
+            });
+        }
+    }
+
+    Ok(())
+}
+
 fn _do_receive<VM>(
     vm: VM,
     storage: Box<dyn Storage>,
     gas_tracker: GasTracker,
+    msg_depth: u32,
     block: BlockInfo,
-    msg: BankMsg,
+    from: Addr,
+    to: Addr,
+    coins: Coins,
 ) -> AppResult<Vec<Event>>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
+    // Contracts that haven't opted into the `receive` hook are credited
+    // without being called.
+    let account = load_account(&storage, &to)?;
+    if !account.receive_hook {
+        return Ok(vec![]);
+    }
+
     let chain_id = CHAIN_ID.load(&storage)?;
-    let account = ACCOUNTS.load(&storage, &msg.to)?;
     let ctx = Context {
         chain_id,
         block,
-        contract: msg.to,
-        sender: Some(msg.from),
-        funds: Some(msg.coins),
+        contract: to,
+        sender: Some(from),
+        funds: Some(coins),
         simulate: None,
+        tx_outcome: None,
     };
 
     call_in_0_out_1_handle_response(
         vm,
         storage,
         gas_tracker,
+        msg_depth,
         "receive",
         &account.code_hash,
         &ctx,
@@ -232,6 +630,7 @@ pub fn do_instantiate<VM>(
     vm: VM,
     storage: Box<dyn Storage>,
     gas_tracker: GasTracker,
+    msg_depth: u32,
     block: BlockInfo,
     sender: Addr,
     code_hash: Hash,
@@ -239,23 +638,123 @@ pub fn do_instantiate<VM>(
     salt: Binary,
     funds: Coins,
     admin: Option<Addr>,
+    receive_hook: bool,
+    strict_funds: bool,
+    entry_points: Option<BTreeSet<String>>,
+    raw_queries: bool,
 ) -> AppResult<Vec<Event>>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
-    match _do_instantiate(
+    let address = Addr::compute(&sender, &code_hash, &salt);
+
+    trace_instantiate(_do_instantiate(
         vm,
         storage,
         gas_tracker,
+        msg_depth,
         block,
         sender,
+        address,
         code_hash,
         msg,
-        salt,
         funds,
         admin,
-    ) {
+        receive_hook,
+        strict_funds,
+        entry_points,
+        raw_queries,
+    ))
+}
+
+pub fn do_instantiate_sequential<VM>(
+    vm: VM,
+    storage: Box<dyn Storage>,
+    gas_tracker: GasTracker,
+    msg_depth: u32,
+    block: BlockInfo,
+    sender: Addr,
+    code_hash: Hash,
+    msg: &Json,
+    funds: Coins,
+    admin: Option<Addr>,
+    receive_hook: bool,
+    strict_funds: bool,
+    entry_points: Option<BTreeSet<String>>,
+    raw_queries: bool,
+) -> AppResult<Vec<Event>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    trace_instantiate(_do_instantiate_sequential(
+        vm,
+        storage,
+        gas_tracker,
+        msg_depth,
+        block,
+        sender,
+        code_hash,
+        msg,
+        funds,
+        admin,
+        receive_hook,
+        strict_funds,
+        entry_points,
+        raw_queries,
+    ))
+}
+
+fn _do_instantiate_sequential<VM>(
+    vm: VM,
+    mut storage: Box<dyn Storage>,
+    gas_tracker: GasTracker,
+    msg_depth: u32,
+    block: BlockInfo,
+    sender: Addr,
+    code_hash: Hash,
+    msg: &Json,
+    funds: Coins,
+    admin: Option<Addr>,
+    receive_hook: bool,
+    strict_funds: bool,
+    entry_points: Option<BTreeSet<String>>,
+    raw_queries: bool,
+) -> AppResult<(Vec<Event>, Addr)>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    // Bump the counter and derive the address from its new value. If the
+    // instantiation fails, the state changes are discarded, so the counter
+    // value will be reused by the next instantiation.
+    let counter = INSTANTIATE_COUNTER.may_load(&storage)?.unwrap_or(0) + 1;
+    INSTANTIATE_COUNTER.save(&mut storage, &counter)?;
+
+    let address = Addr::compute_sequential(counter);
+
+    _do_instantiate(
+        vm,
+        storage,
+        gas_tracker,
+        msg_depth,
+        block,
+        sender,
+        address,
+        code_hash,
+        msg,
+        funds,
+        admin,
+        receive_hook,
+        strict_funds,
+        entry_points,
+        raw_queries,
+    )
+}
+
+fn trace_instantiate(result: AppResult<(Vec<Event>, Addr)>) -> AppResult<Vec<Event>> {
+    match result {
         Ok((events, _address)) => {
             #[cfg(feature = "tracing")]
             info!(address = _address.to_string(), "Instantiated contract");
@@ -273,13 +772,18 @@ pub fn _do_instantiate<VM>(
     vm: VM,
     mut storage: Box<dyn Storage>,
     gas_tracker: GasTracker,
+    msg_depth: u32,
     block: BlockInfo,
     sender: Addr,
+    address: Addr,
     code_hash: Hash,
     msg: &Json,
-    salt: Binary,
     funds: Coins,
     admin: Option<Addr>,
+    receive_hook: bool,
+    strict_funds: bool,
+    entry_points: Option<BTreeSet<String>>,
+    raw_queries: bool,
 ) -> AppResult<(Vec<Event>, Addr)>
 where
     VM: Vm + Clone,
@@ -293,15 +797,22 @@ where
         return Err(AppError::Unauthorized);
     }
 
-    // Compute the contract address, and make sure there isn't already an
-    // account of the same address.
-    let address = Addr::compute(&sender, &code_hash, &salt);
+    // Make sure there isn't already an account of the same address.
     if ACCOUNTS.has(&storage, &address) {
         return Err(AppError::AccountExists { address });
     }
 
+    ensure_sufficient_funds(storage.clone(), &gas_tracker, &sender, &funds)?;
+
     // Save the account info
-    let account = Account { code_hash, admin };
+    let account = Account {
+        code_hash,
+        admin,
+        receive_hook,
+        strict_funds,
+        entry_points,
+        raw_queries,
+    };
     ACCOUNTS.save(&mut storage, &address, &account)?;
 
     // Make the fund transfer
@@ -311,6 +822,7 @@ where
             vm.clone(),
             storage.clone(),
             gas_tracker.clone(),
+            msg_depth,
             block.clone(),
             sender.clone(),
             address.clone(),
@@ -327,11 +839,13 @@ where
         sender: Some(sender),
         funds: Some(funds),
         simulate: None,
+        tx_outcome: None,
     };
     events.extend(call_in_1_out_1_handle_response(
         vm,
         storage,
         gas_tracker,
+        msg_depth,
         "instantiate",
         &account.code_hash,
         &ctx,
@@ -348,6 +862,7 @@ pub fn do_execute<VM>(
     vm: VM,
     storage: Box<dyn Storage>,
     gas_tracker: GasTracker,
+    msg_depth: u32,
     block: BlockInfo,
     contract: Addr,
     sender: Addr,
@@ -362,6 +877,7 @@ where
         vm,
         storage,
         gas_tracker,
+        msg_depth,
         block,
         contract.clone(),
         sender,
@@ -385,6 +901,7 @@ fn _do_execute<VM>(
     vm: VM,
     storage: Box<dyn Storage>,
     gas_tracker: GasTracker,
+    msg_depth: u32,
     block: BlockInfo,
     contract: Addr,
     sender: Addr,
@@ -396,7 +913,13 @@ where
     AppError: From<VM::Error>,
 {
     let chain_id = CHAIN_ID.load(&storage)?;
-    let account = ACCOUNTS.load(&storage, &contract)?;
+    let account = load_account(&storage, &contract)?;
+
+    if account.strict_funds && !funds.is_empty() {
+        return Err(AppError::UnexpectedFunds { contract, funds });
+    }
+
+    ensure_sufficient_funds(storage.clone(), &gas_tracker, &sender, &funds)?;
 
     // Make the fund transfer
     let mut events = vec![];
@@ -405,6 +928,7 @@ where
             vm.clone(),
             storage.clone(),
             gas_tracker.clone(),
+            msg_depth,
             block.clone(),
             sender.clone(),
             contract.clone(),
@@ -421,11 +945,13 @@ where
         sender: Some(sender),
         funds: Some(funds),
         simulate: None,
+        tx_outcome: None,
     };
     events.extend(call_in_1_out_1_handle_response(
         vm,
         storage,
         gas_tracker,
+        msg_depth,
         "execute",
         &account.code_hash,
         &ctx,
@@ -442,6 +968,7 @@ pub fn do_migrate<VM>(
     vm: VM,
     storage: Box<dyn Storage>,
     gas_tracker: GasTracker,
+    msg_depth: u32,
     block: BlockInfo,
     contract: Addr,
     sender: Addr,
@@ -456,6 +983,7 @@ where
         vm,
         storage,
         gas_tracker,
+        msg_depth,
         block,
         contract.clone(),
         sender,
@@ -469,7 +997,7 @@ where
         },
         Err(err) => {
             #[cfg(feature = "tracing")]
-            warn!(err = err.to_string(), "Failed to execute contract");
+            warn!(err = err.to_string(), "Failed to migrate contract");
             Err(err)
         },
     }
@@ -479,6 +1007,7 @@ fn _do_migrate<VM>(
     vm: VM,
     mut storage: Box<dyn Storage>,
     gas_tracker: GasTracker,
+    msg_depth: u32,
     block: BlockInfo,
     contract: Addr,
     sender: Addr,
@@ -490,7 +1019,7 @@ where
     AppError: From<VM::Error>,
 {
     let chain_id = CHAIN_ID.load(&storage)?;
-    let mut account = ACCOUNTS.load(&storage, &contract)?;
+    let mut account = load_account(&storage, &contract)?;
 
     // Only the account's admin can migrate it
     let Some(admin) = &account.admin else {
@@ -503,6 +1032,11 @@ where
         });
     }
 
+    // Make sure the new code exists
+    if !CODES.has(&storage, &new_code_hash) {
+        return Err(StdError::data_not_found::<Code>(new_code_hash.as_ref()).into());
+    }
+
     // Update account info and save
     account.code_hash = new_code_hash;
     ACCOUNTS.save(&mut storage, &contract, &account)?;
@@ -514,12 +1048,14 @@ where
         sender: Some(sender),
         funds: None,
         simulate: None,
+        tx_outcome: None,
     };
 
     call_in_1_out_1_handle_response(
         vm,
         storage,
         gas_tracker,
+        msg_depth,
         "migrate",
         &account.code_hash,
         &ctx,
@@ -528,12 +1064,109 @@ where
     )
 }
 
+// -------------------------------- bank admin ---------------------------------
+
+pub fn do_bank_admin<VM>(
+    vm: VM,
+    storage: Box<dyn Storage>,
+    gas_tracker: GasTracker,
+    msg_depth: u32,
+    block: BlockInfo,
+    sender: Addr,
+    msg: BankAdminMsg,
+) -> AppResult<Vec<Event>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    match _do_bank_admin(vm, storage, gas_tracker, msg_depth, block, sender, &msg) {
+        Ok(events) => {
+            #[cfg(feature = "tracing")]
+            info!(denom = msg.denom(), "Performed bank admin operation");
+            Ok(events)
+        },
+        Err(err) => {
+            #[cfg(feature = "tracing")]
+            warn!(
+                err = err.to_string(),
+                "Failed to perform bank admin operation"
+            );
+            Err(err)
+        },
+    }
+}
+
+fn _do_bank_admin<VM>(
+    vm: VM,
+    storage: Box<dyn Storage>,
+    gas_tracker: GasTracker,
+    msg_depth: u32,
+    block: BlockInfo,
+    sender: Addr,
+    msg: &BankAdminMsg,
+) -> AppResult<Vec<Event>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    let chain_id = CHAIN_ID.load(&storage)?;
+    let cfg = CONFIG.load(&storage)?;
+
+    // Only the denom's admin can perform admin operations on it
+    if denom_admin(&cfg, msg.denom()).as_ref() != Some(&sender) {
+        return Err(AppError::NotDenomAdmin {
+            denom: msg.denom().to_string(),
+            sender,
+        });
+    }
+
+    // The sender has been authorized above, so the bank contract can trust the
+    // message. Deliver it through `sudo`, which no message can otherwise reach.
+    let account = load_bank(&storage, &cfg)?;
+    let ctx = Context {
+        chain_id,
+        block,
+        contract: cfg.bank,
+        sender: None,
+        funds: None,
+        simulate: None,
+        tx_outcome: None,
+    };
+
+    call_in_1_out_1_handle_response(
+        vm,
+        storage,
+        gas_tracker,
+        msg_depth,
+        "sudo",
+        &account.code_hash,
+        &ctx,
+        false,
+        msg,
+    )
+}
+
+/// Return the admin of the given denom: the creator for a factory denom, the
+/// chain's owner for a native denom. An invalid denom doesn't have an admin.
+fn denom_admin(cfg: &Config, denom: &str) -> Option<Addr> {
+    match DenomKind::of(denom) {
+        DenomKind::Factory => {
+            let rest = denom.strip_prefix(FACTORY_DENOM_PREFIX)?;
+            let (creator, _) = rest.split_once('/')?;
+            Addr::from_str(creator).ok()
+        },
+        DenomKind::Native => cfg.owner.clone(),
+        DenomKind::Invalid => None,
+    }
+}
+
 // ----------------------------------- reply -----------------------------------
 
 pub fn do_reply<VM>(
     vm: VM,
     storage: Box<dyn Storage>,
     gas_tracker: GasTracker,
+    msg_depth: u32,
     block: BlockInfo,
     contract: Addr,
     msg: &Json,
@@ -547,6 +1180,7 @@ where
         vm,
         storage,
         gas_tracker,
+        msg_depth,
         block,
         contract.clone(),
         msg,
@@ -569,6 +1203,7 @@ fn _do_reply<VM>(
     vm: VM,
     storage: Box<dyn Storage>,
     gas_tracker: GasTracker,
+    msg_depth: u32,
     block: BlockInfo,
     contract: Addr,
     msg: &Json,
@@ -579,7 +1214,7 @@ where
     AppError: From<VM::Error>,
 {
     let chain_id = CHAIN_ID.load(&storage)?;
-    let account = ACCOUNTS.load(&storage, &contract)?;
+    let account = load_account(&storage, &contract)?;
     let ctx = Context {
         chain_id,
         block,
@@ -587,12 +1222,14 @@ where
         sender: None,
         funds: None,
         simulate: None,
+        tx_outcome: None,
     };
 
     call_in_2_out_1_handle_response(
         vm,
         storage,
         gas_tracker,
+        msg_depth,
         "reply",
         &account.code_hash,
         &ctx,
@@ -602,6 +1239,76 @@ where
     )
 }
 
+// ----------------------------------- sudo ------------------------------------
+
+/// Call a contract's `sudo` entry point.
+///
+/// No `Message` leads here, so the entry point can't be reached by users, only
+/// by the host itself, e.g. to push a governance-approved parameter change into
+/// a contract. Accordingly, the call has no sender and carries no funds.
+pub fn do_sudo<VM>(
+    vm: VM,
+    storage: Box<dyn Storage>,
+    gas_tracker: GasTracker,
+    block: BlockInfo,
+    contract: Addr,
+    msg: &Json,
+) -> AppResult<Vec<Event>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    match _do_sudo(vm, storage, gas_tracker, block, contract.clone(), msg) {
+        Ok(events) => {
+            #[cfg(feature = "tracing")]
+            info!(contract = contract.to_string(), "Called sudo");
+            Ok(events)
+        },
+        Err(err) => {
+            #[cfg(feature = "tracing")]
+            warn!(err = err.to_string(), "Failed to call sudo");
+            Err(err)
+        },
+    }
+}
+
+fn _do_sudo<VM>(
+    vm: VM,
+    storage: Box<dyn Storage>,
+    gas_tracker: GasTracker,
+    block: BlockInfo,
+    contract: Addr,
+    msg: &Json,
+) -> AppResult<Vec<Event>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    let chain_id = CHAIN_ID.load(&storage)?;
+    let account = load_account(&storage, &contract)?;
+    let ctx = Context {
+        chain_id,
+        block,
+        contract,
+        sender: None,
+        funds: None,
+        simulate: None,
+        tx_outcome: None,
+    };
+
+    call_in_1_out_1_handle_response(
+        vm,
+        storage,
+        gas_tracker,
+        0,
+        "sudo",
+        &account.code_hash,
+        &ctx,
+        false,
+        msg,
+    )
+}
+
 // ------------------------- before/after transaction --------------------------
 
 pub fn do_before_tx<VM>(
@@ -610,12 +1317,22 @@ pub fn do_before_tx<VM>(
     gas_tracker: GasTracker,
     block: BlockInfo,
     tx: &Tx,
+    simulate: bool,
 ) -> AppResult<Vec<Event>>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
-    match _do_before_or_after_tx(vm, storage, gas_tracker, block, "before_tx", tx) {
+    match _do_before_or_after_tx(
+        vm,
+        storage,
+        gas_tracker,
+        block,
+        "before_tx",
+        tx,
+        simulate,
+        None,
+    ) {
         Ok(events) => {
             // TODO: add txhash here?
             #[cfg(feature = "tracing")]
@@ -642,12 +1359,23 @@ pub fn do_after_tx<VM>(
     gas_tracker: GasTracker,
     block: BlockInfo,
     tx: &Tx,
+    simulate: bool,
+    tx_outcome: TxOutcome,
 ) -> AppResult<Vec<Event>>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
-    match _do_before_or_after_tx(vm, storage, gas_tracker, block, "after_tx", tx) {
+    match _do_before_or_after_tx(
+        vm,
+        storage,
+        gas_tracker,
+        block,
+        "after_tx",
+        tx,
+        simulate,
+        Some(tx_outcome),
+    ) {
         Ok(events) => {
             // TODO: add txhash here?
             #[cfg(feature = "tracing")]
@@ -675,26 +1403,30 @@ fn _do_before_or_after_tx<VM>(
     block: BlockInfo,
     name: &'static str,
     tx: &Tx,
+    simulate: bool,
+    tx_outcome: Option<TxOutcome>,
 ) -> AppResult<Vec<Event>>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
     let chain_id = CHAIN_ID.load(&storage)?;
-    let account = ACCOUNTS.load(&storage, &tx.sender)?;
+    let account = load_account(&storage, &tx.sender)?;
     let ctx = Context {
         chain_id,
         block,
         contract: tx.sender.clone(),
         sender: None,
         funds: None,
-        simulate: Some(false),
+        simulate: Some(simulate),
+        tx_outcome,
     };
 
     call_in_1_out_1_handle_response(
         vm,
         storage,
         gas_tracker,
+        0,
         name,
         &account.code_hash,
         &ctx,
@@ -782,7 +1514,7 @@ where
     AppError: From<VM::Error>,
 {
     let chain_id = CHAIN_ID.load(&storage)?;
-    let account = ACCOUNTS.load(&storage, &contract)?;
+    let account = load_account(&storage, &contract)?;
     let ctx = Context {
         chain_id,
         block,
@@ -790,15 +1522,69 @@ where
         sender: None,
         funds: None,
         simulate: None,
+        tx_outcome: None,
     };
 
     call_in_0_out_1_handle_response(
         vm,
         storage,
         gas_tracker,
+        0,
         name,
         &account.code_hash,
         &ctx,
         false,
     )
 }
+
+// ---------------------------- direct bank access -----------------------------
+
+/// Mint coins to the given accounts by writing to the bank contract's storage
+/// directly, without calling the bank contract.
+///
+/// This is a fast path for trusted operations of the host, such as minting the
+/// genesis balances. No `Message` leads here, so it can't be reached by users
+/// or contracts. `bank_store` must match the storage layout of the chain's bank
+/// contract.
+pub fn do_mint_direct<B>(
+    storage: Box<dyn Storage>,
+    bank_store: &B,
+    balances: &BTreeMap<Addr, Coins>,
+) -> AppResult<()>
+where
+    B: BankStore,
+{
+    let cfg = CONFIG.load(&storage)?;
+    load_bank(&storage, &cfg)?;
+
+    let mut bank_storage = StorageProvider::new(storage, &[CONTRACT_NAMESPACE, &cfg.bank]);
+    for (address, coins) in balances {
+        bank_store.mint(&mut bank_storage, address, coins)?;
+    }
+
+    Ok(())
+}
+
+// ---------------------------------- helpers ----------------------------------
+
+/// Load the account of the given address, erroring with
+/// `AppError::AccountNotFound` instead of a generic storage error if it
+/// doesn't exist.
+pub(crate) fn load_account(storage: &dyn Storage, address: &Addr) -> AppResult<Account> {
+    ACCOUNTS
+        .may_load(storage, address)?
+        .ok_or_else(|| AppError::AccountNotFound {
+            address: address.clone(),
+        })
+}
+
+/// Load the account of the bank contract, erroring with
+/// `AppError::BankContractMissing` if the config points to an address where
+/// no account exists.
+pub(crate) fn load_bank(storage: &dyn Storage, cfg: &Config) -> AppResult<Account> {
+    ACCOUNTS
+        .may_load(storage, &cfg.bank)?
+        .ok_or_else(|| AppError::BankContractMissing {
+            address: cfg.bank.clone(),
+        })
+}
@@ -2,17 +2,30 @@
 use tracing::{debug, warn};
 use {
     crate::Shared,
-    std::{fmt, fmt::Display},
+    grug_types::{Addr, BankStore, GasConfig},
+    std::{
+        collections::BTreeMap,
+        fmt::{self, Display},
+        sync::Arc,
+    },
 };
 
 // We create an error type specifically for the gas tracker, such that there's
 // an linear dependency relation between error types:
 // > `OutOfGasError` --> `VmError` --> `AppError`
+//
+// Besides the limit and the would-be total usage, the error records which call
+// attempted the charge that ran the meter dry and how much it tried to charge,
+// so that contract developers can locate the expensive call.
 #[derive(Debug, thiserror::Error)]
-#[error("not enough gas! limit: {limit}, used: {used}")]
+#[error(
+    "not enough gas! limit: {limit}, used: {used}, attempted to consume {consumed} in `{comment}`"
+)]
 pub struct OutOfGasError {
-    limit: u64,
-    used: u64,
+    pub limit: u64,
+    pub used: u64,
+    pub consumed: u64,
+    pub comment: String,
 }
 
 struct GasTrackerInner {
@@ -26,6 +39,15 @@ struct GasTrackerInner {
 #[derive(Clone)]
 pub struct GasTracker {
     inner: Shared<GasTrackerInner>,
+    config: GasConfig,
+    // Whether the gas is tracked for a simulated transaction. The gas meter can
+    // only be read by contracts during simulation.
+    simulate: bool,
+    // Where to record the gas used by each contract, if anywhere.
+    metrics: Option<GasMetrics>,
+    // Direct access to the bank contract's storage, for reading balances
+    // without calling the bank contract, if the host has set one.
+    bank_store: Option<Arc<dyn BankStore + Send + Sync>>,
 }
 
 impl GasTracker {
@@ -36,6 +58,10 @@ impl GasTracker {
                 limit: maybe_limit,
                 used: 0,
             }),
+            config: GasConfig::default(),
+            simulate: false,
+            metrics: None,
+            bank_store: None,
         }
     }
 
@@ -46,6 +72,10 @@ impl GasTracker {
                 limit: None,
                 used: 0,
             }),
+            config: GasConfig::default(),
+            simulate: false,
+            metrics: None,
+            bank_store: None,
         }
     }
 
@@ -56,9 +86,58 @@ impl GasTracker {
                 limit: Some(limit),
                 used: 0,
             }),
+            config: GasConfig::default(),
+            simulate: false,
+            metrics: None,
+            bank_store: None,
         }
     }
 
+    /// Use the given gas costs for host operations, instead of the default.
+    pub fn with_config(mut self, config: GasConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Mark whether the gas is tracked for a simulated transaction.
+    pub fn with_simulate(mut self, simulate: bool) -> Self {
+        self.simulate = simulate;
+        self
+    }
+
+    /// Return whether the gas is tracked for a simulated transaction.
+    pub fn simulate(&self) -> bool {
+        self.simulate
+    }
+
+    /// Record the gas used by each contract into the given metrics.
+    pub fn with_metrics(mut self, metrics: Option<GasMetrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Return the metrics that the gas used by each contract is recorded into.
+    pub fn metrics(&self) -> Option<GasMetrics> {
+        self.metrics.clone()
+    }
+
+    /// Read balances directly from the bank contract's storage with the given
+    /// bank store, where the host only needs to read them.
+    pub fn with_bank_store(mut self, bank_store: Option<Arc<dyn BankStore + Send + Sync>>) -> Self {
+        self.bank_store = bank_store;
+        self
+    }
+
+    /// Return the bank store that balances are read with, if any.
+    pub fn bank_store(&self) -> Option<Arc<dyn BankStore + Send + Sync>> {
+        self.bank_store.clone()
+    }
+
+    /// Return the gas costs of host operations.
+    pub fn config(&self) -> GasConfig {
+        self.config
+    }
+
     /// Return the gas limit. `None` if there isn't a limit.
     ///
     /// Panics if lock is poisoned.
@@ -83,10 +162,42 @@ impl GasTracker {
         })
     }
 
+    /// Charge the fixed cost of reading the gas meter, then return the amount
+    /// of gas remaining. If there isn't a limit, return `u64::MAX` minus the
+    /// amount of gas used.
+    ///
+    /// The meter can only be read during simulation, so that contracts can't
+    /// branch on it when the transaction is executed for real. Otherwise, return
+    /// `None`. The cost is charged either way, so that a simulated transaction
+    /// uses the same amount of gas as when it's executed.
+    ///
+    /// This backs the `gas_remaining` import available to contracts.
+    ///
+    /// Panics if lock is poisoned.
+    pub fn read_remaining(&self) -> Result<Option<u64>, OutOfGasError> {
+        self.consume(self.config.gas_remaining, "gas_remaining")?;
+
+        if !self.simulate {
+            return Ok(None);
+        }
+
+        Ok(Some(
+            self.remaining().unwrap_or_else(|| u64::MAX - self.used()),
+        ))
+    }
+
+    /// Add the gas used by a call to the contract's total in the metrics, if
+    /// there are metrics to record into.
+    pub fn record_contract_gas(&self, contract: &Addr, gas_used: u64) {
+        if let Some(metrics) = &self.metrics {
+            metrics.record(contract, gas_used);
+        }
+    }
+
     /// Consume the given amount of gas. Error if the limit is exceeded.
     ///
     /// Panics if lock is poisoned.
-    pub fn consume(&self, consumed: u64, _comment: &str) -> Result<(), OutOfGasError> {
+    pub fn consume(&self, consumed: u64, comment: &str) -> Result<(), OutOfGasError> {
         self.inner.write_with(|mut inner| {
             let used = inner.used + consumed;
 
@@ -94,19 +205,19 @@ impl GasTracker {
             if let Some(limit) = inner.limit {
                 if used > limit {
                     #[cfg(feature = "tracing")]
-                    warn!(limit = inner.limit, used, "Out of gas");
+                    warn!(limit = inner.limit, used, comment, "Out of gas");
 
-                    return Err(OutOfGasError { limit, used });
+                    return Err(OutOfGasError {
+                        limit,
+                        used,
+                        consumed,
+                        comment: comment.to_string(),
+                    });
                 }
             }
 
             #[cfg(feature = "tracing")]
-            debug!(
-                limit = inner.limit,
-                used,
-                comment = _comment,
-                "Gas consumed"
-            );
+            debug!(limit = inner.limit, used, comment, "Gas consumed");
 
             inner.used = used;
 
@@ -126,3 +237,65 @@ impl Display for GasTracker {
         })
     }
 }
+
+// --------------------------------- metrics -----------------------------------
+
+/// Total gas consumed by each contract's entry points, excluding queries, as
+/// observed by this node: address => gas
+///
+/// This is a metric for node operators to spot gas-hungry contracts, and not
+/// part of the chain's state: it's kept in memory, so it counts from when the
+/// node was started, and it can't be queried by contracts. Unlike the state, it
+/// isn't rolled back if a call fails, so the gas of failed calls is counted.
+#[derive(Clone, Default)]
+pub struct GasMetrics {
+    inner: Shared<BTreeMap<Addr, u64>>,
+}
+
+impl GasMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the given amount of gas to the contract's total.
+    ///
+    /// Panics if lock is poisoned.
+    pub fn record(&self, contract: &Addr, gas_used: u64) {
+        self.inner.write_with(|mut inner| {
+            let total = inner.entry(contract.clone()).or_default();
+            *total = total.saturating_add(gas_used);
+        });
+    }
+
+    /// Return the contract's total. Zero if none has been recorded.
+    ///
+    /// Panics if lock is poisoned.
+    pub fn contract_gas_used(&self, contract: &Addr) -> u64 {
+        self.inner
+            .read_with(|inner| inner.get(contract).copied().unwrap_or(0))
+    }
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn out_of_gas_reports_the_call() {
+        let tracker = GasTracker::new_limited(100);
+
+        tracker.consume(60, "instantiate").unwrap();
+
+        let err = tracker.consume(50, "bank_execute").unwrap_err();
+        assert_eq!(err.limit, 100);
+        assert_eq!(err.used, 110);
+        assert_eq!(err.consumed, 50);
+        assert_eq!(err.comment, "bank_execute");
+        assert!(err.to_string().contains("`bank_execute`"));
+
+        // The failed charge must not be recorded.
+        assert_eq!(tracker.used(), 60);
+    }
+}
@@ -6,6 +6,7 @@ mod error;
 mod events;
 mod execute;
 mod gas;
+mod migrations;
 mod providers;
 mod query;
 mod shared;
@@ -15,6 +16,6 @@ mod traits;
 mod vm;
 
 pub use crate::{
-    app::*, buffer::*, error::*, events::*, execute::*, gas::*, providers::*, query::*, shared::*,
-    state::*, submessage::*, traits::*, vm::*,
+    app::*, buffer::*, error::*, events::*, execute::*, gas::*, migrations::*, providers::*,
+    query::*, shared::*, state::*, submessage::*, traits::*, vm::*,
 };
@@ -0,0 +1,237 @@
+use {
+    crate::{AppResult, ACCOUNTS, CODES, CONFIG, STORE_VERSION},
+    borsh::{BorshDeserialize, BorshSerialize},
+    grug_storage::{Item, Map},
+    grug_types::{
+        Account, Addr, Code, Config, GasConfig, Hash, Order, Permissions, StdResult, Storage,
+        Udec128, DEFAULT_FEE_DENOM, DEFAULT_MAX_MSG_DEPTH, DEFAULT_MAX_MSG_SIZE,
+        DEFAULT_MAX_SUBMSGS, DEFAULT_TX_RESULT_RETENTION,
+    },
+    std::collections::{BTreeMap, BTreeSet},
+};
+
+/// The version of the layout in which the app's state is stored, saved in the
+/// state as `STORE_VERSION`.
+///
+/// - v0: the original layout. Stores of this version don't have a
+///   `STORE_VERSION` record.
+/// - v1: `Config` has the fields added after its original release, codes are
+///   stored as `Code`s instead of bare byte codes, and accounts are stored in
+///   the layout versioned by `ACCOUNT_LAYOUT_VERSION`.
+pub const LATEST_STORE_VERSION: u32 = 1;
+
+// ---------------------------------- v0 -> v1 ---------------------------------
+
+// The records of the original layout, as they were first released. These must
+// never change.
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct ConfigV0 {
+    owner: Option<Addr>,
+    bank: Addr,
+    begin_blockers: Vec<Addr>,
+    end_blockers: Vec<Addr>,
+    permissions: Permissions,
+    allowed_clients: BTreeSet<Hash>,
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct AccountV0 {
+    code_hash: Hash,
+    admin: Option<Addr>,
+}
+
+const CONFIG_V0: Item<ConfigV0> = Item::new("config");
+
+const CODES_V0: Map<&Hash, Vec<u8>> = Map::new("code");
+
+const ACCOUNTS_V0: Map<&Addr, AccountV0> = Map::new("account");
+
+fn migrate_v0_to_v1(storage: &mut dyn Storage) -> AppResult<()> {
+    let cfg = CONFIG_V0.load(storage)?;
+    CONFIG.save(storage, &Config {
+        owner: cfg.owner,
+        bank: cfg.bank,
+        begin_blockers: cfg.begin_blockers,
+        end_blockers: cfg.end_blockers,
+        permissions: cfg.permissions,
+        allowed_clients: cfg.allowed_clients,
+        max_msg_depth: DEFAULT_MAX_MSG_DEPTH,
+        max_submsgs: DEFAULT_MAX_SUBMSGS,
+        max_msg_size: DEFAULT_MAX_MSG_SIZE,
+        transfer_caps: BTreeMap::new(),
+        fee_denom: DEFAULT_FEE_DENOM.to_string(),
+        registered_denoms: None,
+        gas_refund_ratio: Udec128::ZERO,
+        gas_config: GasConfig::default(),
+        tx_result_retention: DEFAULT_TX_RESULT_RETENTION,
+    })?;
+
+    let codes = CODES_V0
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (code_hash, byte_code) in codes {
+        // There's no telling which interface version these were built against.
+        CODES.save(storage, &code_hash, &Code {
+            byte_code,
+            interface_version: 0,
+        })?;
+    }
+
+    let accounts = ACCOUNTS_V0
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    for (address, account) in accounts {
+        // Before these flags existed, every contract was sent `receive` calls
+        // and could have its storage read by others, so keep it that way.
+        ACCOUNTS.save(storage, &address, &Account {
+            code_hash: account.code_hash,
+            admin: account.admin,
+            receive_hook: true,
+            strict_funds: false,
+            entry_points: None,
+            raw_queries: true,
+        })?;
+    }
+
+    Ok(())
+}
+
+// ---------------------------------- migrate ----------------------------------
+
+/// Upgrade the app's state to the latest layout, if it isn't already.
+///
+/// Stores created by `App::do_init_chain` are already of the latest layout.
+/// This is for chains that were started with an earlier release of the app.
+pub fn migrate_store(storage: &mut dyn Storage) -> AppResult<()> {
+    let version = STORE_VERSION.may_load(storage)?.unwrap_or(0);
+    if version >= LATEST_STORE_VERSION {
+        return Ok(());
+    }
+
+    migrate_v0_to_v1(storage)?;
+
+    STORE_VERSION.save(storage, &LATEST_STORE_VERSION)?;
+
+    Ok(())
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        grug_types::{hash, to_borsh_vec, MockStorage, Permission},
+    };
+
+    // Copies of the original `Config` and `Account`, exactly as they were
+    // first released, to produce records of the original layout with.
+
+    #[derive(BorshSerialize)]
+    struct OriginalConfig {
+        owner: Option<Addr>,
+        bank: Addr,
+        begin_blockers: Vec<Addr>,
+        end_blockers: Vec<Addr>,
+        permissions: Permissions,
+        allowed_clients: BTreeSet<Hash>,
+    }
+
+    #[derive(BorshSerialize)]
+    struct OriginalAccount {
+        code_hash: Hash,
+        admin: Option<Addr>,
+    }
+
+    #[test]
+    fn migrating_v0_store() {
+        let mut storage = MockStorage::new();
+
+        let permissions = Permissions {
+            upload: Permission::Everybody,
+            instantiate: Permission::Nobody,
+            create_client: Permission::Everybody,
+            create_connection: Permission::Everybody,
+            create_channel: Permission::Everybody,
+        };
+        storage.write(
+            b"config",
+            &to_borsh_vec(&OriginalConfig {
+                owner: Some(Addr::mock(1)),
+                bank: Addr::mock(2),
+                begin_blockers: vec![Addr::mock(3)],
+                end_blockers: vec![],
+                permissions: permissions.clone(),
+                allowed_clients: BTreeSet::new(),
+            })
+            .unwrap(),
+        );
+
+        let byte_code = b"byte code".to_vec();
+        let code_hash = hash(&byte_code);
+        CODES_V0.save_raw(&mut storage, &code_hash, &to_borsh_vec(&byte_code).unwrap());
+
+        for (address, admin) in [(Addr::mock(5), None), (Addr::mock(6), Some(Addr::mock(1)))] {
+            let account = OriginalAccount {
+                code_hash: code_hash.clone(),
+                admin,
+            };
+            ACCOUNTS_V0.save_raw(&mut storage, &address, &to_borsh_vec(&account).unwrap());
+        }
+
+        migrate_store(&mut storage).unwrap();
+
+        assert_eq!(STORE_VERSION.load(&storage).unwrap(), LATEST_STORE_VERSION);
+
+        let cfg = CONFIG.load(&storage).unwrap();
+        assert_eq!(cfg.owner, Some(Addr::mock(1)));
+        assert_eq!(cfg.bank, Addr::mock(2));
+        assert_eq!(cfg.begin_blockers, vec![Addr::mock(3)]);
+        assert_eq!(cfg.permissions, permissions);
+        assert_eq!(cfg.max_msg_depth, DEFAULT_MAX_MSG_DEPTH);
+        assert_eq!(cfg.fee_denom, DEFAULT_FEE_DENOM);
+
+        assert_eq!(CODES.load(&storage, &code_hash).unwrap(), Code {
+            byte_code,
+            interface_version: 0,
+        });
+
+        let accounts = ACCOUNTS
+            .range(&storage, None, None, Order::Ascending)
+            .collect::<StdResult<Vec<_>>>()
+            .unwrap();
+        assert_eq!(accounts, vec![
+            (Addr::mock(5), Account {
+                code_hash: code_hash.clone(),
+                admin: None,
+                receive_hook: true,
+                strict_funds: false,
+                entry_points: None,
+                raw_queries: true,
+            }),
+            (Addr::mock(6), Account {
+                code_hash,
+                admin: Some(Addr::mock(1)),
+                receive_hook: true,
+                strict_funds: false,
+                entry_points: None,
+                raw_queries: true,
+            }),
+        ]);
+
+        // Migrating again is a no-op.
+        let before = storage.clone();
+        migrate_store(&mut storage).unwrap();
+        assert_eq!(
+            storage
+                .scan(None, None, Order::Ascending)
+                .collect::<Vec<_>>(),
+            before
+                .scan(None, None, Order::Ascending)
+                .collect::<Vec<_>>()
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use {
-    crate::{process_query, AppError, GasTracker, Vm},
+    crate::{process_query, AppError, GasTracker, Vm, ACCOUNTS},
     grug_types::{
         concat, increment_last_byte, BlockInfo, Order, Querier, QueryRequest, QueryResponse,
         Record, StdError, StdResult, Storage,
@@ -135,6 +135,27 @@ where
     AppError: From<VM::Error>,
 {
     fn query_chain(&self, req: QueryRequest) -> StdResult<QueryResponse> {
+        // Contracts may only read each other's storage directly if the one
+        // being read has opted into it. Queries made from outside of the chain
+        // don't go through here, so they aren't restricted.
+        if let QueryRequest::WasmRaw { contract, .. } = &req {
+            let allowed = ACCOUNTS
+                .may_load(&self.storage, contract)?
+                .is_some_and(|account| account.raw_queries);
+            if !allowed {
+                return Err(StdError::RawQueryNotAllowed {
+                    contract: contract.clone(),
+                });
+            }
+        }
+
+        // If a contract is being queried, note which one in the error, so that
+        // the caller can tell its failure apart from other errors.
+        let contract = match &req {
+            QueryRequest::WasmSmart { contract, .. } => Some(contract.clone()),
+            _ => None,
+        };
+
         process_query(
             self.vm.clone(),
             self.storage.clone(),
@@ -142,6 +163,12 @@ where
             self.block.clone(),
             req,
         )
-        .map_err(|err| StdError::Generic(err.to_string()))
+        .map_err(|err| match contract {
+            Some(contract) => StdError::QueryFailed {
+                contract,
+                msg: err.to_string(),
+            },
+            None => StdError::Generic(err.to_string()),
+        })
     }
 }
@@ -1,13 +1,14 @@
 use {
     crate::{
-        call_in_1_out_1, AppError, AppResult, GasTracker, StorageProvider, Vm, ACCOUNTS, CHAIN_ID,
-        CODES, CONFIG, CONTRACT_NAMESPACE, LAST_FINALIZED_BLOCK,
+        call_in_1_out_1, into_app_result, load_account, load_bank, AppError, AppResult, GasTracker,
+        StorageProvider, Vm, ACCOUNTS, CHAIN_ID, CODES, CONFIG, CONTRACT_NAMESPACE,
+        LAST_FINALIZED_BLOCK, TX_RESULTS,
     },
-    grug_storage::Bound,
+    grug_storage::{start_after_bounds, Bound},
     grug_types::{
-        AccountResponse, Addr, BankQuery, BankQueryResponse, Binary, BlockInfo, Coin, Coins,
-        Context, GenericResult, Hash, InfoResponse, Json, Order, StdResult, Storage,
-        WasmRawResponse, WasmSmartResponse,
+        AccountResponse, Addr, BankQuery, BankQueryResponse, BankStore, Binary, BlockInfo, Coin,
+        Coins, Context, GenericResult, Hash, InfoResponse, Json, Order, StdResult, Storage,
+        SuppliesResponse, TxResult, WasmRawResponse, WasmSmartResponse,
     },
 };
 
@@ -40,6 +41,25 @@ where
     .map(|res| res.as_balance())
 }
 
+pub fn query_balance_exact<VM>(
+    vm: VM,
+    storage: Box<dyn Storage>,
+    block: BlockInfo,
+    gas_tracker: GasTracker,
+    address: Addr,
+    denom: String,
+) -> AppResult<Option<Coin>>
+where
+    VM: Vm + Clone,
+    AppError: From<VM::Error>,
+{
+    _query_bank(vm, storage, block, gas_tracker, &BankQuery::BalanceExact {
+        address,
+        denom,
+    })
+    .map(|res| res.as_balance_exact())
+}
+
 pub fn query_balances<VM>(
     vm: VM,
     storage: Box<dyn Storage>,
@@ -48,6 +68,7 @@ pub fn query_balances<VM>(
     address: Addr,
     start_after: Option<String>,
     limit: Option<u32>,
+    order: Option<Order>,
 ) -> AppResult<Coins>
 where
     VM: Vm + Clone,
@@ -57,10 +78,38 @@ where
         address,
         start_after,
         limit,
+        order,
     })
     .map(|res| res.as_balances())
 }
 
+/// Read an account's balance from the bank contract's storage directly, without
+/// calling the bank contract.
+///
+/// This is a fast path for trusted operations of the host. No query request
+/// leads here, so it can't be reached by users or contracts. `bank_store` must
+/// match the storage layout of the chain's bank contract.
+pub fn query_balance_direct<B>(
+    storage: Box<dyn Storage>,
+    bank_store: &B,
+    address: &Addr,
+    denom: &str,
+) -> AppResult<Coin>
+where
+    B: BankStore,
+{
+    let cfg = CONFIG.load(&storage)?;
+    load_bank(&storage, &cfg)?;
+
+    let bank_storage = StorageProvider::new(storage, &[CONTRACT_NAMESPACE, &cfg.bank]);
+    let amount = bank_store.balance(&bank_storage, address, denom)?;
+
+    Ok(Coin {
+        denom: denom.to_string(),
+        amount,
+    })
+}
+
 pub fn query_supply<VM>(
     vm: VM,
     storage: Box<dyn Storage>,
@@ -85,7 +134,8 @@ pub fn query_supplies<VM>(
     gas_tracker: GasTracker,
     start_after: Option<String>,
     limit: Option<u32>,
-) -> AppResult<Coins>
+    order: Option<Order>,
+) -> AppResult<SuppliesResponse>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
@@ -93,6 +143,7 @@ where
     _query_bank(vm, storage, block, gas_tracker, &BankQuery::Supplies {
         start_after,
         limit,
+        order,
     })
     .map(|res| res.as_supplies())
 }
@@ -110,7 +161,7 @@ where
 {
     let chain_id = CHAIN_ID.load(&storage)?;
     let cfg = CONFIG.load(&storage)?;
-    let account = ACCOUNTS.load(&storage, &cfg.bank)?;
+    let account = load_bank(&storage, &cfg)?;
     let ctx = Context {
         chain_id,
         block,
@@ -118,6 +169,7 @@ where
         sender: None,
         funds: None,
         simulate: None,
+        tx_outcome: None,
     };
 
     call_in_1_out_1::<_, _, GenericResult<BankQueryResponse>>(
@@ -129,49 +181,66 @@ where
         &ctx,
         true,
         msg,
-    )?
-    .into_std_result()
-    .map_err(AppError::Std)
+    )
+    .and_then(into_app_result)
 }
 
 pub fn query_code(storage: &dyn Storage, hash: Hash) -> AppResult<Binary> {
-    Ok(CODES.load(storage, &hash)?.into())
+    Ok(CODES.load(storage, &hash)?.byte_code.into())
 }
 
 pub fn query_codes(
     storage: &dyn Storage,
     start_after: Option<Hash>,
     limit: Option<u32>,
+    order: Option<Order>,
 ) -> AppResult<Vec<Hash>> {
     let start = start_after.as_ref().map(Bound::exclusive);
     let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+    let order = order.unwrap_or(Order::Ascending);
+    let (min, max) = start_after_bounds(start, order);
 
     CODES
-        .keys(storage, start, None, Order::Ascending)
+        .keys(storage, min, max, order)
         .take(limit as usize)
         .collect::<StdResult<Vec<_>>>()
         .map_err(Into::into)
 }
 
 pub fn query_account(storage: &dyn Storage, address: Addr) -> AppResult<AccountResponse> {
-    let account = ACCOUNTS.load(storage, &address)?;
+    let account = load_account(storage, &address)?;
     Ok(AccountResponse {
         address,
         code_hash: account.code_hash,
         admin: account.admin,
+        receive_hook: account.receive_hook,
+        strict_funds: account.strict_funds,
+        entry_points: account.entry_points,
+        raw_queries: account.raw_queries,
     })
 }
 
+pub fn query_is_contract(storage: &dyn Storage, address: Addr) -> AppResult<bool> {
+    Ok(ACCOUNTS.has(storage, &address))
+}
+
+pub fn query_tx_result(storage: &dyn Storage, hash: Hash) -> AppResult<TxResult> {
+    TX_RESULTS.load(storage, &hash).map_err(Into::into)
+}
+
 pub fn query_accounts(
     storage: &dyn Storage,
     start_after: Option<Addr>,
     limit: Option<u32>,
+    order: Option<Order>,
 ) -> AppResult<Vec<AccountResponse>> {
     let start = start_after.as_ref().map(Bound::exclusive);
     let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+    let order = order.unwrap_or(Order::Ascending);
+    let (min, max) = start_after_bounds(start, order);
 
     ACCOUNTS
-        .range(storage, start, None, Order::Ascending)
+        .range(storage, min, max, order)
         .take(limit as usize)
         .map(|item| {
             let (address, account) = item?;
@@ -179,6 +248,10 @@ pub fn query_accounts(
                 address,
                 code_hash: account.code_hash,
                 admin: account.admin,
+                receive_hook: account.receive_hook,
+                strict_funds: account.strict_funds,
+                entry_points: account.entry_points,
+                raw_queries: account.raw_queries,
             })
         })
         .collect()
@@ -211,7 +284,7 @@ where
     AppError: From<VM::Error>,
 {
     let chain_id = CHAIN_ID.load(&storage)?;
-    let account = ACCOUNTS.load(&storage, &contract)?;
+    let account = load_account(&storage, &contract)?;
     let ctx = Context {
         chain_id,
         block,
@@ -219,6 +292,7 @@ where
         sender: None,
         funds: None,
         simulate: None,
+        tx_outcome: None,
     };
     let data = call_in_1_out_1::<_, _, GenericResult<Json>>(
         vm,
@@ -229,8 +303,8 @@ where
         &ctx,
         true,
         &msg,
-    )?
-    .into_std_result()?;
+    )
+    .and_then(into_app_result)?;
 
     Ok(WasmSmartResponse {
         contract: ctx.contract,
@@ -1,8 +1,12 @@
 use {
     grug_storage::{Item, Map},
-    grug_types::{Account, Addr, BlockInfo, Config, Hash},
+    grug_types::{Account, Addr, BlockInfo, Code, Config, Hash, TxResult, Uint128},
 };
 
+/// The version of the layout in which the state is stored. See
+/// `LATEST_STORE_VERSION`.
+pub const STORE_VERSION: Item<u32> = Item::new("store_version");
+
 /// A string that identifies the chain
 pub const CHAIN_ID: Item<String> = Item::new("chain_id");
 
@@ -12,12 +16,36 @@ pub const CONFIG: Item<Config> = Item::new("config");
 /// The most recently finalized block
 pub const LAST_FINALIZED_BLOCK: Item<BlockInfo> = Item::new("last_finalized_block");
 
-/// Wasm contract byte codes: code_hash => byte_code
-pub const CODES: Map<&Hash, Vec<u8>> = Map::new("code");
+/// Wasm contract byte codes: code_hash => code
+pub const CODES: Map<&Hash, Code> = Map::new("code");
 
 /// Account metadata: address => account
 pub const ACCOUNTS: Map<&Addr, Account> = Map::new("account");
 
+/// The number of contracts instantiated with sequential addresses so far.
+/// See `Message::InstantiateSequential`.
+pub const INSTANTIATE_COUNTER: Item<u64> = Item::new("instantiate_counter");
+
+/// Amount of each capped denom transferred in the current block:
+/// denom => amount
+///
+/// Cleared at the end of each block (and of genesis), so the tally resets each
+/// block, and entries never outlive the block they were made in.
+pub const BLOCK_TRANSFERS: Map<&str, Uint128> = Map::new("block_transfers");
+
+/// Results of transactions delivered in blocks: tx hash => result
+///
+/// Only the results of the blocks within `Config::tx_result_retention` are
+/// kept. See `TX_HASHES`.
+pub const TX_RESULTS: Map<&Hash, TxResult> = Map::new("tx_result");
+
+/// Hashes of the transactions delivered in each block whose results are still
+/// kept: block height => tx hashes
+///
+/// Used to find the results to prune once a block falls out of the retention
+/// window.
+pub const TX_HASHES: Map<u64, Vec<Hash>> = Map::new("tx_hashes");
+
 /// Each contract has its own storage space, which we term the "substore".
 /// A key in a contract's substore is prefixed by the word "wasm" + contract address.
 pub const CONTRACT_NAMESPACE: &[u8] = b"wasm";
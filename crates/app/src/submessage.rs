@@ -1,11 +1,17 @@
 use {
-    crate::{do_reply, process_msg, AppError, AppResult, Buffer, GasTracker, Shared, Vm},
-    grug_types::{Addr, BlockInfo, Event, GenericResult, ReplyOn, Storage, SubMessage},
+    crate::{do_reply, process_msg, AppError, AppResult, Buffer, GasTracker, Shared, Vm, CONFIG},
+    grug_types::{
+        to_json_value, Addr, BlockInfo, Event, GenericResult, ReplyOn, Storage, SubMessage,
+    },
 };
 
 /// Recursively execute submessages emitted in a contract response using a
 /// depth-first approach.
 ///
+/// Submessages are executed strictly in the order the contract returned them.
+/// They must therefore always be passed in as a `Vec`, never collected from a
+/// `HashMap` or similar, which would make block execution nondeterministic.
+///
 /// Note: The `sender` in this function signature is the contract, i.e. the
 /// account that emitted the submessages, not the transaction's sender.
 pub fn handle_submessages<VM>(
@@ -36,6 +42,7 @@ pub fn handle_submessages<VM>(
     storage: Box<dyn Storage>,
     block: BlockInfo,
     gas_tracker: GasTracker,
+    msg_depth: u32,
     sender: Addr,
     submsgs: Vec<SubMessage>,
 ) -> AppResult<Vec<Event>>
@@ -43,27 +50,55 @@ where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
+    if !submsgs.is_empty() {
+        let cfg = CONFIG.load(&storage)?;
+
+        // Make sure the submessages don't go deeper than allowed. Without this,
+        // a contract that recursively executes itself would overflow the stack.
+        if msg_depth >= cfg.max_msg_depth {
+            return Err(AppError::CallDepthExceeded {
+                depth: msg_depth + 1,
+                max: cfg.max_msg_depth,
+            });
+        }
+
+        // Make sure a single response doesn't fan out into too many
+        // submessages.
+        if submsgs.len() > cfg.max_submsgs as usize {
+            return Err(AppError::TooManySubmessages {
+                count: submsgs.len(),
+                max: cfg.max_submsgs,
+            });
+        }
+    }
+
+    // All the submessages run in a single buffer on top of the storage. Before
+    // each one, a checkpoint is taken; if it fails and the failure is handled
+    // by the contract, the buffer is rolled back to it, discarding the
+    // submessage's writes, while the gas it consumed is still charged.
+    let buffer = Shared::new(Buffer::new(storage, None));
     let mut events = vec![];
     for submsg in submsgs {
-        let buffer = Shared::new(Buffer::new(storage.clone(), None));
+        let checkpoint = buffer.read_access().gas_checkpoint(&gas_tracker);
         let result = process_msg(
             vm.clone(),
             Box::new(buffer.share()),
             gas_tracker.clone(),
+            msg_depth + 1,
             block.clone(),
             sender.clone(),
-            submsg.msg,
+            &submsg.msg,
         );
         match (submsg.reply_on, result) {
             // success - callback requested
-            // flush state changes, log events, give callback
+            // keep state changes, log events, give callback
             (ReplyOn::Success(payload) | ReplyOn::Always(payload), Result::Ok(submsg_events)) => {
-                buffer.disassemble().consume();
                 events.extend(submsg_events.clone());
                 events.extend(do_reply(
                     vm.clone(),
-                    storage.clone(),
+                    Box::new(buffer.share()),
                     gas_tracker.clone(),
+                    msg_depth + 1,
                     block.clone(),
                     sender.clone(),
                     &payload,
@@ -71,12 +106,22 @@ where
                 )?);
             },
             // error - callback requested
-            // discard uncommitted state changes, give callback
+            // roll back state changes, log the failure, give callback
             (ReplyOn::Error(payload) | ReplyOn::Always(payload), Result::Err(err)) => {
+                let gas_used = gas_tracker.used() - checkpoint.gas_used();
+                buffer.write_access().rollback_to(checkpoint);
+
+                events.push(Event::submessage_failed(
+                    &sender,
+                    &to_json_value(&submsg.msg)?,
+                    &err.to_string(),
+                    gas_used,
+                ));
                 events.extend(do_reply(
                     vm.clone(),
-                    storage.clone(),
+                    Box::new(buffer.share()),
                     gas_tracker.clone(),
+                    msg_depth + 1,
                     block.clone(),
                     sender.clone(),
                     &payload,
@@ -84,9 +129,8 @@ where
                 )?);
             },
             // success - callback not requested
-            // flush state changes, log events, move on to the next submsg
+            // keep state changes, log events, move on to the next submsg
             (ReplyOn::Error(_) | ReplyOn::Never, Result::Ok(submsg_events)) => {
-                buffer.disassemble().consume();
                 events.extend(submsg_events);
             },
             // error - callback not requested
@@ -96,5 +140,8 @@ where
             },
         };
     }
+
+    buffer.disassemble().consume();
+
     Ok(events)
 }
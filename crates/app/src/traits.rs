@@ -43,6 +43,12 @@ pub trait Db {
 
     /// Return the state storage as an owned, read-only, `Storage` object.
     /// This should be a _flat_ KV store that stores _raw_ keys and _raw_ values.
+    ///
+    /// If version is unspecified, the latest committed version is used. Either
+    /// way, the version is fixed when this method is called: the returned
+    /// storage must keep reading from it even if more versions are flushed or
+    /// committed afterwards. Queries rely on this to be served from a
+    /// consistent snapshot while a block is being applied.
     fn state_storage(&self, version: Option<u64>) -> impl Storage + Clone + 'static;
 
     /// Return the most recent version that has been committed.
@@ -85,6 +91,10 @@ pub trait Db {
 
 // ------------------------------------ vm -------------------------------------
 
+/// The version of the host-guest interface that this host implements. Code
+/// built against any other version is rejected at upload time.
+pub const INTERFACE_VERSION: u32 = 1;
+
 /// Represents a virtual machine that can execute programs.
 pub trait Vm: Sized {
     type Error: ToString;
@@ -104,6 +114,25 @@ pub trait Vm: Sized {
         querier: QuerierProvider<Self>,
         gas_tracker: GasTracker,
     ) -> Result<Self::Instance, Self::Error>;
+
+    /// Return whether the guest program exports a function of the given name.
+    ///
+    /// Used during genesis to make sure core contracts (such as the bank)
+    /// implement the entry points that the host is going to call.
+    fn has_entry_point(
+        &mut self,
+        code: &[u8],
+        code_hash: &Hash,
+        name: &str,
+    ) -> Result<bool, Self::Error>;
+
+    /// Return the version of the host-guest interface that the guest program
+    /// is built against, or `None` if the program doesn't declare one.
+    fn interface_version(
+        &mut self,
+        code: &[u8],
+        code_hash: &Hash,
+    ) -> Result<Option<u32>, Self::Error>;
 }
 
 pub trait Instance {
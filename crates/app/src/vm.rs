@@ -1,7 +1,8 @@
 use {
     crate::{
         handle_submessages, AppError, AppResult, GasTracker, Instance, QuerierProvider,
-        StorageProvider, Vm, CODES, CONTRACT_ADDRESS_KEY, CONTRACT_NAMESPACE,
+        StorageProvider, Vm, ACCOUNTS, CODES, CONTRACT_ADDRESS_KEY, CONTRACT_EVENT_TYPE_PREFIX,
+        CONTRACT_NAMESPACE,
     },
     grug_types::{
         from_json_slice, to_json_vec, Addr, BlockInfo, Context, Event, GenericResult, Hash,
@@ -26,22 +27,26 @@ where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
-    // Create the VM instance
-    let instance = create_vm_instance(
-        vm,
-        storage,
-        gas_tracker,
-        ctx.block.clone(),
-        &ctx.contract,
-        code_hash,
-        storage_readonly,
-    )?;
+    record_contract_gas(&gas_tracker, ctx, storage_readonly, || {
+        // Create the VM instance
+        let instance = create_vm_instance(
+            vm,
+            storage,
+            gas_tracker.clone(),
+            ctx.block.clone(),
+            &ctx.contract,
+            code_hash,
+            name,
+            storage_readonly,
+        )?;
 
-    // Call the function; deserialize the output as JSON
-    let out_raw = instance.call_in_0_out_1(name, ctx)?;
-    let out = from_json_slice(out_raw)?;
+        // Call the function; deserialize the output as JSON
+        let out_raw = instance.call_in_0_out_1(name, ctx)?;
+        consume_json_codec_gas(&gas_tracker, out_raw.len(), "json_deserialize")?;
+        let out = from_json_slice(out_raw)?;
 
-    Ok(out)
+        Ok(out)
+    })
 }
 
 /// Create a VM instance, and call a function that takes exactly one parameter
@@ -62,25 +67,30 @@ where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
-    // Create the VM instance
-    let instance = create_vm_instance(
-        vm,
-        storage,
-        gas_tracker,
-        ctx.block.clone(),
-        &ctx.contract,
-        code_hash,
-        storage_readonly,
-    )?;
+    record_contract_gas(&gas_tracker, ctx, storage_readonly, || {
+        // Create the VM instance
+        let instance = create_vm_instance(
+            vm,
+            storage,
+            gas_tracker.clone(),
+            ctx.block.clone(),
+            &ctx.contract,
+            code_hash,
+            name,
+            storage_readonly,
+        )?;
 
-    // Serialize the param as JSON
-    let param_raw = to_json_vec(param)?;
+        // Serialize the param as JSON
+        let param_raw = to_json_vec(param)?;
+        consume_json_codec_gas(&gas_tracker, param_raw.len(), "json_serialize")?;
 
-    // Call the function; deserialize the output as JSON
-    let out_raw = instance.call_in_1_out_1(name, ctx, &param_raw)?;
-    let out = from_json_slice(out_raw)?;
+        // Call the function; deserialize the output as JSON
+        let out_raw = instance.call_in_1_out_1(name, ctx, &param_raw)?;
+        consume_json_codec_gas(&gas_tracker, out_raw.len(), "json_deserialize")?;
+        let out = from_json_slice(out_raw)?;
 
-    Ok(out)
+        Ok(out)
+    })
 }
 
 /// Create a VM instance, and call a function that takes exactly two parameters
@@ -103,26 +113,35 @@ where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
-    // Create the VM instance
-    let instance = create_vm_instance(
-        vm,
-        storage,
-        gas_tracker,
-        ctx.block.clone(),
-        &ctx.contract,
-        code_hash,
-        storage_readonly,
-    )?;
+    record_contract_gas(&gas_tracker, ctx, storage_readonly, || {
+        // Create the VM instance
+        let instance = create_vm_instance(
+            vm,
+            storage,
+            gas_tracker.clone(),
+            ctx.block.clone(),
+            &ctx.contract,
+            code_hash,
+            name,
+            storage_readonly,
+        )?;
 
-    // Serialize the params as JSON
-    let param1_raw = to_json_vec(param1)?;
-    let param2_raw = to_json_vec(param2)?;
+        // Serialize the params as JSON
+        let param1_raw = to_json_vec(param1)?;
+        let param2_raw = to_json_vec(param2)?;
+        consume_json_codec_gas(
+            &gas_tracker,
+            param1_raw.len() + param2_raw.len(),
+            "json_serialize",
+        )?;
 
-    // Call the function; deserialize the output as JSON
-    let out_raw = instance.call_in_2_out_1(name, ctx, &param1_raw, &param2_raw)?;
-    let out = from_json_slice(out_raw)?;
+        // Call the function; deserialize the output as JSON
+        let out_raw = instance.call_in_2_out_1(name, ctx, &param1_raw, &param2_raw)?;
+        consume_json_codec_gas(&gas_tracker, out_raw.len(), "json_deserialize")?;
+        let out = from_json_slice(out_raw)?;
 
-    Ok(out)
+        Ok(out)
+    })
 }
 
 /// Create a VM instance, call a function that takes exactly no input parameter
@@ -132,6 +151,7 @@ pub fn call_in_0_out_1_handle_response<VM>(
     vm: VM,
     storage: Box<dyn Storage>,
     gas_tracker: GasTracker,
+    msg_depth: u32,
     name: &'static str,
     code_hash: &Hash,
     ctx: &Context,
@@ -149,10 +169,10 @@ where
         code_hash,
         ctx,
         storage_readonly,
-    )?
-    .into_std_result()?;
+    )
+    .and_then(into_app_result)?;
 
-    handle_response(vm, storage, gas_tracker, name, ctx, response)
+    handle_response(vm, storage, gas_tracker, msg_depth, name, ctx, response)
 }
 
 /// Create a VM instance, call a function that takes exactly one parameter and
@@ -162,6 +182,7 @@ pub fn call_in_1_out_1_handle_response<VM, P>(
     vm: VM,
     storage: Box<dyn Storage>,
     gas_tracker: GasTracker,
+    msg_depth: u32,
     name: &'static str,
     code_hash: &Hash,
     ctx: &Context,
@@ -182,10 +203,10 @@ where
         ctx,
         storage_readonly,
         param,
-    )?
-    .into_std_result()?;
+    )
+    .and_then(into_app_result)?;
 
-    handle_response(vm, storage, gas_tracker, name, ctx, response)
+    handle_response(vm, storage, gas_tracker, msg_depth, name, ctx, response)
 }
 
 /// Create a VM instance, call a function that takes exactly two parameter and
@@ -195,6 +216,7 @@ pub fn call_in_2_out_1_handle_response<VM, P1, P2>(
     vm: VM,
     storage: Box<dyn Storage>,
     gas_tracker: GasTracker,
+    msg_depth: u32,
     name: &'static str,
     code_hash: &Hash,
     ctx: &Context,
@@ -218,10 +240,44 @@ where
         storage_readonly,
         param1,
         param2,
-    )?
-    .into_std_result()?;
+    )
+    .and_then(into_app_result)?;
 
-    handle_response(vm, storage, gas_tracker, name, ctx, response)
+    handle_response(vm, storage, gas_tracker, msg_depth, name, ctx, response)
+}
+
+/// Charge gas for the host serializing or deserializing the given number of
+/// bytes of JSON, at the rate specified by the tracker's [`GasConfig`].
+///
+/// [`GasConfig`]: grug_types::GasConfig
+fn consume_json_codec_gas(gas_tracker: &GasTracker, len: usize, comment: &str) -> AppResult<()> {
+    let per_byte = gas_tracker.config().json_codec_per_byte;
+    gas_tracker.consume(per_byte.saturating_mul(len as u64), comment)?;
+
+    Ok(())
+}
+
+/// Make a contract call, then add the gas it consumed to the contract's total
+/// in the gas metrics, whether the call succeeded or not.
+///
+/// Only calls that can write to storage are recorded; queries can't be.
+fn record_contract_gas<F, T>(
+    gas_tracker: &GasTracker,
+    ctx: &Context,
+    storage_readonly: bool,
+    call: F,
+) -> AppResult<T>
+where
+    F: FnOnce() -> AppResult<T>,
+{
+    let gas_before = gas_tracker.used();
+    let result = call();
+
+    if !storage_readonly {
+        gas_tracker.record_contract_gas(&ctx.contract, gas_tracker.used() - gas_before);
+    }
+
+    result
 }
 
 fn create_vm_instance<VM>(
@@ -231,12 +287,27 @@ fn create_vm_instance<VM>(
     block: BlockInfo,
     address: &Addr,
     code_hash: &Hash,
+    name: &str,
     storage_readonly: bool,
 ) -> AppResult<VM::Instance>
 where
     VM: Vm + Clone,
     AppError: From<VM::Error>,
 {
+    // Make sure the entry point is in the account's allow-list, if it has one.
+    // This is checked by the host regardless of what the code exports.
+    if let Some(entry_points) = ACCOUNTS
+        .may_load(&storage, address)?
+        .and_then(|account| account.entry_points)
+    {
+        if !entry_points.contains(name) {
+            return Err(AppError::EntryPointNotAllowed {
+                contract: address.clone(),
+                name: name.to_string(),
+            });
+        }
+    }
+
     // Load the program code from storage and deserialize
     let code = CODES.load(&storage, code_hash)?;
 
@@ -245,7 +316,7 @@ where
     let storage = StorageProvider::new(storage, &[CONTRACT_NAMESPACE, address]);
 
     Ok(vm.build_instance(
-        &code,
+        &code.byte_code,
         code_hash,
         storage,
         storage_readonly,
@@ -254,10 +325,22 @@ where
     )?)
 }
 
+/// Convert the result returned by a contract's entry point into an `AppResult`.
+///
+/// If the contract returned an error, its message is kept verbatim in an
+/// `AppError::Contract`, to be distinguishable from errors raised by the host.
+pub fn into_app_result<T>(result: GenericResult<T>) -> AppResult<T> {
+    match result {
+        GenericResult::Ok(data) => Ok(data),
+        GenericResult::Err(msg) => Err(AppError::Contract { msg }),
+    }
+}
+
 pub(crate) fn handle_response<VM>(
     vm: VM,
     storage: Box<dyn Storage>,
     gas_tracker: GasTracker,
+    msg_depth: u32,
     name: &'static str,
     ctx: &Context,
     response: Response,
@@ -271,13 +354,23 @@ where
         .add_attribute(CONTRACT_ADDRESS_KEY, &ctx.contract)
         .add_attributes(response.attributes);
 
-    // Handle submessages; append events emitted during submessage handling
     let mut events = vec![event];
+
+    // Append custom events emitted by the contract, with their types prefixed
+    // so they can't impersonate events emitted by the app.
+    events.extend(response.events.into_iter().map(|event| {
+        Event::new(format!("{CONTRACT_EVENT_TYPE_PREFIX}{}", event.r#type))
+            .add_attribute(CONTRACT_ADDRESS_KEY, &ctx.contract)
+            .add_attributes(event.attributes)
+    }));
+
+    // Handle submessages; append events emitted during submessage handling
     events.extend(handle_submessages(
         vm,
         storage,
         ctx.block.clone(),
         gas_tracker,
+        msg_depth,
         ctx.contract.clone(),
         response.submsgs,
     )?);
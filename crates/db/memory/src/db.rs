@@ -305,3 +305,33 @@ impl Storage for StateStorage {
         unreachable!("write function called on read-only storage");
     }
 }
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_storage_is_a_snapshot() {
+        let db = MemDb::new();
+        let batch = Batch::from([(b"larry".to_vec(), Op::Insert(b"engineer".to_vec()))]);
+        db.flush_and_commit(batch).unwrap();
+
+        // Obtain the storage before the next block is applied, as a query
+        // would, then flush and commit the block.
+        let storage = db.state_storage(None);
+        assert_eq!(storage.read(b"larry"), Some(b"engineer".to_vec()));
+
+        let batch = Batch::from([(b"larry".to_vec(), Op::Insert(b"founder".to_vec()))]);
+        db.flush_but_not_commit(batch).unwrap();
+        assert_eq!(storage.read(b"larry"), Some(b"engineer".to_vec()));
+
+        db.commit().unwrap();
+        assert_eq!(storage.read(b"larry"), Some(b"engineer".to_vec()));
+
+        // Storages obtained afterwards see the new value.
+        let storage = db.state_storage(None);
+        assert_eq!(storage.read(b"larry"), Some(b"founder".to_vec()));
+    }
+}
@@ -29,6 +29,16 @@ extern "C" fn deallocate(region_addr: usize) {
     // data is dropped here, which calls Vec<u8> destructor, freeing the memory
 }
 
+// ----------------------------- interface version -----------------------------
+
+/// Declare the version of the host-guest interface this contract is built
+/// against. The host checks for this export when the code is uploaded, and
+/// rejects the code if the version isn't supported.
+///
+/// The function is never called; only its name matters.
+#[no_mangle]
+extern "C" fn interface_version_1() {}
+
 // -------------------------------- instantiate --------------------------------
 
 pub fn do_instantiate<M, E>(
@@ -213,6 +223,42 @@ where
     reply_fn(sudo_ctx, msg, events).into()
 }
 
+// ----------------------------------- sudo ------------------------------------
+
+pub fn do_sudo<M, E>(
+    sudo_fn: &dyn Fn(SudoCtx, M) -> Result<Response, E>,
+    ctx_ptr: usize,
+    msg_ptr: usize,
+) -> usize
+where
+    M: DeserializeOwned,
+    E: ToString,
+{
+    let ctx_bytes = unsafe { Region::consume(ctx_ptr as *mut Region) };
+    let msg_bytes = unsafe { Region::consume(msg_ptr as *mut Region) };
+
+    let res = _do_sudo(sudo_fn, &ctx_bytes, &msg_bytes);
+    let res_bytes = to_json_vec(&res).unwrap();
+
+    Region::release_buffer(res_bytes) as usize
+}
+
+fn _do_sudo<M, E>(
+    sudo_fn: &dyn Fn(SudoCtx, M) -> Result<Response, E>,
+    ctx_bytes: &[u8],
+    msg_bytes: &[u8],
+) -> GenericResult<Response>
+where
+    M: DeserializeOwned,
+    E: ToString,
+{
+    let ctx: Context = unwrap_into_generic_result!(from_borsh_slice(ctx_bytes));
+    let sudo_ctx = make_sudo_ctx!(ctx, &mut ExternalStorage, &ExternalApi, &ExternalQuerier);
+    let msg = unwrap_into_generic_result!(from_json_slice(msg_bytes));
+
+    sudo_fn(sudo_ctx, msg).into()
+}
+
 // ---------------------------------- receive ----------------------------------
 
 pub fn do_receive<E>(
@@ -1,8 +1,8 @@
 use {
     crate::Region,
     grug_types::{
-        encode_sections, from_json_slice, to_json_vec, Addr, Api, GenericResult, Order, Querier,
-        QueryRequest, QueryResponse, Record, StdError, StdResult, Storage,
+        encode_sections, from_json_slice, to_json_vec, Addr, Api, Order, Querier, QueryRequest,
+        QueryResponse, QueryResult, Record, StdError, StdResult, Storage,
     },
 };
 
@@ -49,9 +49,24 @@ extern "C" {
     fn blake2b_512(data_ptr: usize) -> usize;
     fn blake3(data_ptr: usize) -> usize;
 
+    // Encodings
+    // For the decode methods, a zero pointer means the input is invalid.
+    fn encode_base64(data_ptr: usize) -> usize;
+    fn decode_base64(data_ptr: usize) -> usize;
+    fn encode_hex(data_ptr: usize) -> usize;
+    fn decode_hex(data_ptr: usize) -> usize;
+
+    // Re-encode JSON in the canonical form. A zero pointer means the input
+    // isn't valid JSON.
+    fn to_json_canonical(json_ptr: usize) -> usize;
+
     // Print a debug message to the client's CLI output.
     fn debug(addr_ptr: usize, msg_ptr: usize);
 
+    // Read the gas meter. Only used in debug builds.
+    #[cfg(debug_assertions)]
+    fn gas_remaining() -> u64;
+
     // Send a query request to the chain.
     // Not to be confused with the `query` export.
     fn query_chain(req: usize) -> usize;
@@ -275,6 +290,39 @@ macro_rules! impl_hash_method {
     };
 }
 
+macro_rules! impl_encode_method {
+    ($name:ident) => {
+        fn $name(&self, data: &[u8]) -> String {
+            let data_region = Region::build(data);
+            let data_ptr = &*data_region as *const Region;
+
+            let encoded_ptr = unsafe { $name(data_ptr as usize) };
+            let encoded = unsafe { Region::consume(encoded_ptr as *mut Region) };
+
+            // We trust the host returns a valid UTF-8 string, therefore
+            // unwrapping it here safely.
+            String::from_utf8(encoded).unwrap()
+        }
+    };
+}
+
+macro_rules! impl_decode_method {
+    ($name:ident, $encoding:literal) => {
+        fn $name(&self, data: &str) -> StdResult<Vec<u8>> {
+            let data_region = Region::build(data.as_bytes());
+            let data_ptr = &*data_region as *const Region;
+
+            let decoded_ptr = unsafe { $name(data_ptr as usize) };
+            if decoded_ptr == 0 {
+                // we interpret a zero pointer as meaning the input is invalid
+                return Err(StdError::decode($encoding));
+            }
+
+            unsafe { Ok(Region::consume(decoded_ptr as *mut Region)) }
+        }
+    };
+}
+
 impl Api for ExternalApi {
     impl_hash_method!(sha2_256, 32);
 
@@ -296,6 +344,27 @@ impl Api for ExternalApi {
 
     impl_hash_method!(blake3, 32);
 
+    impl_encode_method!(encode_base64);
+
+    impl_decode_method!(decode_base64, "base64");
+
+    impl_encode_method!(encode_hex);
+
+    impl_decode_method!(decode_hex, "hex");
+
+    fn to_json_canonical(&self, json: &[u8]) -> StdResult<Vec<u8>> {
+        let json_region = Region::build(json);
+        let json_ptr = &*json_region as *const Region;
+
+        let canonical_ptr = unsafe { to_json_canonical(json_ptr as usize) };
+        if canonical_ptr == 0 {
+            // we interpret a zero pointer as meaning the input is invalid
+            return Err(StdError::decode("json"));
+        }
+
+        unsafe { Ok(Region::consume(canonical_ptr as *mut Region)) }
+    }
+
     fn debug(&self, addr: &Addr, msg: &str) {
         let addr_region = Region::build(addr);
         let addr_ptr = &*addr_region as *const Region;
@@ -305,6 +374,18 @@ impl Api for ExternalApi {
         unsafe { debug(addr_ptr as usize, msg_ptr as usize) }
     }
 
+    #[cfg(debug_assertions)]
+    fn gas_remaining(&self) -> StdResult<u64> {
+        unsafe { Ok(gas_remaining()) }
+    }
+
+    #[cfg(not(debug_assertions))]
+    fn gas_remaining(&self) -> StdResult<u64> {
+        Err(StdError::generic_err(
+            "`gas_remaining` is only available in debug builds",
+        ))
+    }
+
     fn secp256r1_verify(&self, msg_hash: &[u8], sig: &[u8], pk: &[u8]) -> StdResult<()> {
         let msg_hash_region = Region::build(msg_hash);
         let msg_hash_ptr = &*msg_hash_region as *const Region;
@@ -440,7 +521,7 @@ impl Querier for ExternalQuerier {
 
         let res_ptr = unsafe { query_chain(req_ptr as usize) };
         let res_bytes = unsafe { Region::consume(res_ptr as *mut Region) };
-        let res: GenericResult<QueryResponse> = from_json_slice(res_bytes)?;
+        let res: QueryResult = from_json_slice(res_bytes)?;
 
         res.into_std_result()
     }
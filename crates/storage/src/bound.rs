@@ -1,4 +1,4 @@
-use crate::Key;
+use {crate::Key, grug_types::Order};
 
 /// Like Bound but only with the raw binary variants.
 pub enum RawBound {
@@ -44,3 +44,18 @@ where
         }
     }
 }
+
+/// Turn the `start_after` bound of a paginated query into the `(min, max)`
+/// bounds of the range to iterate in the given order.
+///
+/// In ascending order, `start_after` is the lower bound of the range; in
+/// descending order, it's the upper bound.
+pub fn start_after_bounds<K>(
+    start_after: Option<Bound<K>>,
+    order: Order,
+) -> (Option<Bound<K>>, Option<Bound<K>>) {
+    match order {
+        Order::Ascending => (start_after, None),
+        Order::Descending => (None, start_after),
+    }
+}
@@ -34,6 +34,17 @@ impl TestAccount {
         gas_limit: u64,
         chain_id: &str,
         sequence: u32,
+    ) -> anyhow::Result<Tx> {
+        self.sign_transaction_with_memo(msgs, gas_limit, chain_id, sequence, None)
+    }
+
+    pub fn sign_transaction_with_memo(
+        &self,
+        msgs: Vec<Message>,
+        gas_limit: u64,
+        chain_id: &str,
+        sequence: u32,
+        memo: Option<String>,
     ) -> anyhow::Result<Tx> {
         let sign_bytes = Identity256::from(grug_account::make_sign_bytes(
             sha2_256,
@@ -41,6 +52,7 @@ impl TestAccount {
             &self.address,
             chain_id,
             sequence,
+            memo.as_deref(),
         )?);
 
         let signature: Signature = self.sk.sign_digest(sign_bytes);
@@ -50,6 +62,7 @@ impl TestAccount {
             msgs,
             gas_limit,
             credential: signature.to_vec().into(),
+            memo,
         })
     }
 }
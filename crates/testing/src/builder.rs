@@ -1,11 +1,15 @@
 use {
-    crate::{setup_tracing_subscriber, TestAccount, TestAccounts, TestSuite, TestVm},
-    anyhow::ensure,
+    crate::{
+        default_config, setup_tracing_subscriber, TestAccount, TestAccounts, TestSuite, TestVm,
+    },
+    anyhow::{bail, ensure},
     grug_account::PublicKey,
     grug_app::AppError,
     grug_types::{
-        hash, Addr, Binary, BlockInfo, Coins, Config, GenesisState, Hash, Message, NumberConst,
-        Permission, Permissions, Timestamp, Uint64, GENESIS_BLOCK_HASH, GENESIS_SENDER,
+        hash, Addr, Binary, BlockInfo, Coins, Config, GasConfig, GenesisState, Hash, Message,
+        NumberConst, Timestamp, Udec128, Uint128, Uint64, DEFAULT_FEE_DENOM, DEFAULT_MAX_MSG_DEPTH,
+        DEFAULT_MAX_MSG_SIZE, DEFAULT_MAX_SUBMSGS, DEFAULT_TX_RESULT_RETENTION, GENESIS_BLOCK_HASH,
+        GENESIS_SENDER,
     },
     grug_vm_rust::RustVm,
     std::{
@@ -26,10 +30,22 @@ pub struct TestBuilder<VM: TestVm = RustVm> {
     chain_id: Option<String>,
     genesis_time: Option<SystemTime>,
     block_time: Option<Duration>,
+    gas_adjustment: Option<Udec128>,
+    max_msg_depth: Option<u32>,
+    max_submsgs: Option<u32>,
+    max_msg_size: Option<u32>,
+    transfer_caps: BTreeMap<String, Uint128>,
+    fee_denom: Option<String>,
+    registered_denoms: Option<BTreeSet<String>>,
+    gas_refund_ratio: Option<Udec128>,
+    gas_config: Option<GasConfig>,
+    tx_result_retention: Option<u64>,
+    query_gas_limit: Option<u64>,
     // TODO: let user customize the codes and instantiate messages of bank and account
     account_code: Binary,
     account_code_hash: Hash,
     accounts: TestAccounts,
+    owner: Option<&'static str>,
     bank_code: Binary,
     bank_code_hash: Hash,
     balances: BTreeMap<Addr, Coins>,
@@ -61,9 +77,21 @@ where
             chain_id: None,
             genesis_time: None,
             block_time: None,
+            gas_adjustment: None,
+            max_msg_depth: None,
+            max_submsgs: None,
+            max_msg_size: None,
+            transfer_caps: BTreeMap::new(),
+            fee_denom: None,
+            registered_denoms: None,
+            gas_refund_ratio: None,
+            gas_config: None,
+            tx_result_retention: None,
+            query_gas_limit: None,
             account_code,
             account_code_hash,
             accounts: TestAccounts::new(),
+            owner: None,
             bank_code,
             bank_code_hash,
             balances: BTreeMap::new(),
@@ -85,6 +113,98 @@ where
         self
     }
 
+    /// Set the multiplier applied to the gas consumed by simulated
+    /// transactions. Defaults to one.
+    pub fn set_gas_adjustment(mut self, gas_adjustment: Udec128) -> Self {
+        self.gas_adjustment = Some(gas_adjustment);
+        self
+    }
+
+    pub fn set_max_msg_depth(mut self, max_msg_depth: u32) -> Self {
+        self.max_msg_depth = Some(max_msg_depth);
+        self
+    }
+
+    /// Set the maximum number of submessages a single contract response may
+    /// contain.
+    pub fn set_max_submsgs(mut self, max_submsgs: u32) -> Self {
+        self.max_submsgs = Some(max_submsgs);
+        self
+    }
+
+    /// Set the maximum size, in bytes, of a message serialized as JSON.
+    pub fn set_max_msg_size(mut self, max_msg_size: u32) -> Self {
+        self.max_msg_size = Some(max_msg_size);
+        self
+    }
+
+    /// Limit the total amount of the given denom that can be transferred
+    /// within a single block.
+    pub fn set_transfer_cap(mut self, denom: impl ToString, cap: Uint128) -> Self {
+        self.transfer_caps.insert(denom.to_string(), cap);
+        self
+    }
+
+    /// Set the denom in which fees are paid. Defaults to `ugrug`.
+    pub fn set_fee_denom(mut self, fee_denom: impl ToString) -> Self {
+        self.fee_denom = Some(fee_denom.to_string());
+        self
+    }
+
+    /// Only allow coins of the given denoms to be transferred. Defaults to
+    /// allowing any denom.
+    pub fn set_registered_denoms<I, D>(mut self, denoms: I) -> Self
+    where
+        I: IntoIterator<Item = D>,
+        D: ToString,
+    {
+        self.registered_denoms = Some(denoms.into_iter().map(|d| d.to_string()).collect());
+        self
+    }
+
+    /// Set the share of a transaction's unused gas to be refunded to the
+    /// sender. Defaults to zero.
+    pub fn set_gas_refund_ratio(mut self, gas_refund_ratio: Udec128) -> Self {
+        self.gas_refund_ratio = Some(gas_refund_ratio);
+        self
+    }
+
+    /// Set the gas costs of operations performed by the host. Defaults to
+    /// `GasConfig::default()`.
+    pub fn set_gas_config(mut self, gas_config: GasConfig) -> Self {
+        self.gas_config = Some(gas_config);
+        self
+    }
+
+    /// Set the number of most recent blocks whose transaction results are kept.
+    /// Defaults to `DEFAULT_TX_RESULT_RETENTION`.
+    pub fn set_tx_result_retention(mut self, tx_result_retention: u64) -> Self {
+        self.tx_result_retention = Some(tx_result_retention);
+        self
+    }
+
+    /// Set the gas limit for queries made through the test suite. Defaults to
+    /// no limit.
+    pub fn set_query_gas_limit(mut self, query_gas_limit: u64) -> Self {
+        self.query_gas_limit = Some(query_gas_limit);
+        self
+    }
+
+    /// Use the given code for the bank contract, instead of the VM's default.
+    pub fn set_bank_code(mut self, code: Binary) -> Self {
+        self.bank_code_hash = hash(&code);
+        self.bank_code = code;
+        self
+    }
+
+    /// Make the account of the given name the chain's owner.
+    ///
+    /// The account must be added with `add_account` before calling `build`.
+    pub fn set_owner(mut self, name: &'static str) -> Self {
+        self.owner = Some(name);
+        self
+    }
+
     pub fn add_account(mut self, name: &'static str, balances: Coins) -> anyhow::Result<Self> {
         ensure!(
             !self.accounts.contains_key(name),
@@ -109,6 +229,10 @@ where
 
         let block_time = self.block_time.unwrap_or(DEFAULT_BLOCK_TIME);
 
+        let gas_adjustment = self
+            .gas_adjustment
+            .unwrap_or_else(|| Udec128::new(Uint128::ONE));
+
         let chain_id = self
             .chain_id
             .unwrap_or_else(|| DEFAULT_CHAIN_ID.to_string());
@@ -155,26 +279,47 @@ where
             )?);
         }
 
+        // Find the owner's address, if specified
+        let owner = match self.owner {
+            Some(name) => {
+                let Some(account) = self.accounts.get(name) else {
+                    bail!("owner account with name {name} does not exist");
+                };
+                Some(account.address.clone())
+            },
+            None => None,
+        };
+
         // Create the app config
         let bank = Addr::compute(&GENESIS_SENDER, &self.bank_code_hash, DEFAULT_BANK_SALT);
         let config = Config {
-            // TODO: allow user to set owner
-            owner: None,
-            bank,
-            begin_blockers: vec![],
-            end_blockers: vec![],
-            permissions: Permissions {
-                upload: Permission::Everybody,
-                instantiate: Permission::Everybody,
-                create_client: Permission::Everybody,
-                create_connection: Permission::Everybody,
-                create_channel: Permission::Everybody,
-            },
-            allowed_clients: BTreeSet::new(),
+            owner,
+            max_msg_depth: self.max_msg_depth.unwrap_or(DEFAULT_MAX_MSG_DEPTH),
+            max_submsgs: self.max_submsgs.unwrap_or(DEFAULT_MAX_SUBMSGS),
+            max_msg_size: self.max_msg_size.unwrap_or(DEFAULT_MAX_MSG_SIZE),
+            transfer_caps: self.transfer_caps,
+            fee_denom: self
+                .fee_denom
+                .unwrap_or_else(|| DEFAULT_FEE_DENOM.to_string()),
+            registered_denoms: self.registered_denoms,
+            gas_refund_ratio: self.gas_refund_ratio.unwrap_or(Udec128::ZERO),
+            gas_config: self.gas_config.unwrap_or_default(),
+            tx_result_retention: self
+                .tx_result_retention
+                .unwrap_or(DEFAULT_TX_RESULT_RETENTION),
+            ..default_config(bank)
         };
 
         let genesis_state = GenesisState { config, msgs };
-        let suite = TestSuite::create(self.vm, chain_id, block_time, genesis_block, genesis_state)?;
+        let suite = TestSuite::create(
+            self.vm,
+            chain_id,
+            block_time,
+            self.query_gas_limit,
+            gas_adjustment,
+            genesis_block,
+            genesis_state,
+        )?;
 
         Ok((suite, self.accounts))
     }
@@ -0,0 +1,78 @@
+use {
+    crate::{TestVm, DEFAULT_BANK_SALT},
+    grug_types::{
+        hash, Addr, BlockInfo, Coins, Config, GenesisState, Message, NumberConst, Permission,
+        Permissions, Timestamp, Udec128, Uint64, DEFAULT_FEE_DENOM, DEFAULT_MAX_MSG_DEPTH,
+        DEFAULT_MAX_MSG_SIZE, DEFAULT_MAX_SUBMSGS, DEFAULT_TX_RESULT_RETENTION, GENESIS_BLOCK_HASH,
+        GENESIS_SENDER,
+    },
+    std::collections::{BTreeMap, BTreeSet},
+};
+
+/// Create a chain config that uses the given bank contract, with every other
+/// parameter set to its default value.
+pub fn default_config(bank: Addr) -> Config {
+    Config {
+        owner: None,
+        bank,
+        begin_blockers: vec![],
+        end_blockers: vec![],
+        permissions: Permissions {
+            upload: Permission::Everybody,
+            instantiate: Permission::Everybody,
+            create_client: Permission::Everybody,
+            create_connection: Permission::Everybody,
+            create_channel: Permission::Everybody,
+        },
+        allowed_clients: BTreeSet::new(),
+        max_msg_depth: DEFAULT_MAX_MSG_DEPTH,
+        max_submsgs: DEFAULT_MAX_SUBMSGS,
+        max_msg_size: DEFAULT_MAX_MSG_SIZE,
+        transfer_caps: BTreeMap::new(),
+        fee_denom: DEFAULT_FEE_DENOM.to_string(),
+        registered_denoms: None,
+        gas_refund_ratio: Udec128::ZERO,
+        gas_config: Default::default(),
+        tx_result_retention: DEFAULT_TX_RESULT_RETENTION,
+    }
+}
+
+/// Create a minimal genesis state, which only uploads the VM's default bank
+/// code and instantiates it with the given balances, under the default config.
+///
+/// This is for tests that run genesis on an `App` themselves instead of using
+/// the [`TestBuilder`](crate::TestBuilder). Additional messages can be appended
+/// to `msgs`, and the config adjusted, before running it.
+pub fn mock_genesis_state<VM>(
+    initial_balances: BTreeMap<Addr, Coins>,
+) -> anyhow::Result<GenesisState>
+where
+    VM: TestVm,
+{
+    let bank_code = VM::default_bank_code();
+    let bank_code_hash = hash(&bank_code);
+    let bank = Addr::compute(&GENESIS_SENDER, &bank_code_hash, DEFAULT_BANK_SALT);
+
+    Ok(GenesisState {
+        config: default_config(bank),
+        msgs: vec![
+            Message::upload(bank_code),
+            Message::instantiate(
+                bank_code_hash,
+                &grug_bank::InstantiateMsg { initial_balances },
+                DEFAULT_BANK_SALT,
+                Coins::new_empty(),
+                None,
+            )?,
+        ],
+    })
+}
+
+/// The genesis block to go with [`mock_genesis_state`].
+pub fn mock_genesis_block() -> BlockInfo {
+    BlockInfo {
+        height: Uint64::ZERO,
+        timestamp: Timestamp::from_seconds(0),
+        hash: GENESIS_BLOCK_HASH,
+    }
+}
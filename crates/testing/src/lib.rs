@@ -1,8 +1,9 @@
 mod account;
 mod builder;
+mod genesis;
 mod result;
 mod suite;
 mod tracing;
 mod vm;
 
-pub use {account::*, builder::*, result::*, suite::*, tracing::*, vm::*};
+pub use {account::*, builder::*, genesis::*, result::*, suite::*, tracing::*, vm::*};
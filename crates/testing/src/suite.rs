@@ -1,12 +1,14 @@
 use {
     crate::{TestAccount, TestResult},
     anyhow::ensure,
-    grug_app::{App, AppError, AppResult, Vm},
+    grug_app::{App, AppError, AppResult, SimulateOutcome, TxDiff, Vm},
+    grug_bank::DirectBankStore,
     grug_crypto::sha2_256,
     grug_db_memory::MemDb,
     grug_types::{
-        from_json_value, to_json_value, Addr, Binary, BlockInfo, Coins, Event, GenesisState, Hash,
-        Message, NumberConst, QueryRequest, Uint128, Uint64,
+        from_json_value, to_json_value, to_json_vec, Addr, Binary, BlockInfo, Coins, Event,
+        GenesisState, Hash, InfoResponse, Message, NumberConst, Order, QueryRequest,
+        SuppliesResponse, Tx, TxResult, Udec128, Uint128, Uint64,
     },
     grug_vm_rust::RustVm,
     serde::{de::DeserializeOwned, ser::Serialize},
@@ -37,10 +39,15 @@ where
         vm: VM,
         chain_id: String,
         block_time: Duration,
+        query_gas_limit: Option<u64>,
+        gas_adjustment: Udec128,
         genesis_block: BlockInfo,
         genesis_state: GenesisState,
     ) -> anyhow::Result<Self> {
-        let app = App::new(MemDb::new(), vm, None);
+        // The bank contract is always instantiated with `grug_bank`'s message,
+        // so its storage has `grug_bank`'s layout.
+        let app = App::new(MemDb::new(), vm, query_gas_limit, gas_adjustment)
+            .with_bank_store(DirectBankStore);
 
         app.do_init_chain(chain_id.clone(), genesis_block.clone(), genesis_state)?;
 
@@ -77,26 +84,68 @@ where
         // Increment the sequence
         *sequence += 1;
 
+        self.deliver_tx(tx)
+    }
+
+    /// Deliver an already signed transaction in a new block.
+    ///
+    /// Unlike `execute_messages_with_gas`, this doesn't keep track of the
+    /// sender's sequence; the caller is responsible for signing with the right
+    /// one.
+    pub fn deliver_tx(&mut self, tx: Tx) -> anyhow::Result<TestResult<Vec<Event>>> {
+        let mut results = self.deliver_txs(vec![tx])?;
+
+        Ok(results.pop().unwrap())
+    }
+
+    /// Deliver already signed transactions together in a new block, in the
+    /// given order. Return the result of each.
+    ///
+    /// As with `deliver_tx`, the caller is responsible for signing each with
+    /// the right sequence.
+    pub fn deliver_txs(&mut self, txs: Vec<Tx>) -> anyhow::Result<Vec<TestResult<Vec<Event>>>> {
+        let txs = hash_txs(txs)?;
+        let num_txs = txs.len();
+
         // Make a new block
         self.block.height += Uint64::ONE;
         self.block.timestamp = self.block.timestamp.plus_nanos(self.block_time.as_nanos());
 
         // Finalize the block
-        let (_, _, mut results) = self
-            .app
-            .do_finalize_block(self.block.clone(), vec![(Hash::ZERO, tx)])?;
+        let (_, _, results) = self.app.do_finalize_block(self.block.clone(), txs)?;
 
-        // We only sent 1 transaction, so there should be exactly one tx result
+        // There should be exactly one result per transaction
         ensure!(
-            results.len() == 1,
-            "received {} tx results; something is wrong",
-            results.len()
+            results.len() == num_txs,
+            "received {} tx results for {} txs; something is wrong",
+            results.len(),
+            num_txs
         );
 
         // Commit state changes
         self.app.do_commit()?;
 
-        Ok(results.pop().unwrap().into())
+        Ok(results.into_iter().map(Into::into).collect())
+    }
+
+    /// Simulate a transaction consisting of the given messages on top of the
+    /// latest state. The transaction doesn't need to be signed, and the state
+    /// isn't changed.
+    pub fn simulate_messages(
+        &self,
+        signer: &TestAccount,
+        msgs: Vec<Message>,
+    ) -> TestResult<SimulateOutcome> {
+        let tx = Tx {
+            sender: signer.address.clone(),
+            msgs,
+            credential: Binary::empty(),
+            // The gas limit is ignored in simulation mode.
+            gas_limit: 0,
+            memo: None,
+        };
+
+        self.app.simulate_tx(tx).into()
     }
 
     /// Upload a code under the given gas limit. Return the code's hash.
@@ -192,6 +241,44 @@ where
         .into()
     }
 
+    pub fn query_wasm_raw(&self, contract: Addr, key: Binary) -> TestResult<Option<Binary>> {
+        self.app
+            .do_query_app(
+                QueryRequest::WasmRaw { contract, key },
+                0, // zero means to use the latest height
+                false,
+            )
+            .map(|res| res.as_wasm_raw().value)
+            .into()
+    }
+
+    /// Perform the checks that a transaction must pass before it's admitted
+    /// into the mempool.
+    pub fn check_tx(&self, tx: &Tx) -> TestResult<()> {
+        self.app.do_check_tx(tx).into()
+    }
+
+    /// Replay the given transactions on top of the latest state, as if they
+    /// were included in the next block. The state isn't changed.
+    pub fn replay_block(&self, txs: Vec<Tx>) -> anyhow::Result<Vec<TxDiff>> {
+        let mut block = self.block.clone();
+        block.height += Uint64::ONE;
+        block.timestamp = block.timestamp.plus_nanos(self.block_time.as_nanos());
+
+        Ok(self.app.replay_block(None, block, hash_txs(txs)?))
+    }
+
+    pub fn query_info(&self) -> TestResult<InfoResponse> {
+        self.app
+            .do_query_app(
+                QueryRequest::Info {},
+                0, // zero means to use the latest height
+                false,
+            )
+            .map(|res| res.as_info())
+            .into()
+    }
+
     pub fn query_balance(&self, account: &TestAccount, denom: &str) -> TestResult<Uint128> {
         self.app
             .do_query_app(
@@ -205,10 +292,93 @@ where
             .map(|res| res.as_balance().amount)
             .into()
     }
+
+    /// Query an account's balance in a single denom, returning `None` if the
+    /// account has never held the denom.
+    pub fn query_balance_exact(
+        &self,
+        account: &TestAccount,
+        denom: &str,
+    ) -> TestResult<Option<Uint128>> {
+        self.app
+            .do_query_app(
+                QueryRequest::BalanceExact {
+                    address: account.address.clone(),
+                    denom: denom.to_string(),
+                },
+                0, // zero means to use the latest height
+                false,
+            )
+            .map(|res| res.as_balance_exact().map(|coin| coin.amount))
+            .into()
+    }
+
+    /// Query a page of an account's balances.
+    pub fn query_balances(
+        &self,
+        account: &TestAccount,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        order: Option<Order>,
+    ) -> TestResult<Coins> {
+        self.app
+            .do_query_app(
+                QueryRequest::Balances {
+                    address: account.address.clone(),
+                    start_after,
+                    limit,
+                    order,
+                },
+                0, // zero means to use the latest height
+                false,
+            )
+            .map(|res| res.as_balances())
+            .into()
+    }
+
+    /// Query a page of tokens' total supplies.
+    pub fn query_supplies(
+        &self,
+        start_after: Option<String>,
+        limit: Option<u32>,
+        order: Option<Order>,
+    ) -> TestResult<SuppliesResponse> {
+        self.app
+            .do_query_app(
+                QueryRequest::Supplies {
+                    start_after,
+                    limit,
+                    order,
+                },
+                0, // zero means to use the latest height
+                false,
+            )
+            .map(|res| res.as_supplies())
+            .into()
+    }
+
+    /// Query the result of a transaction delivered in a previous block.
+    pub fn query_tx_result(&self, hash: Hash) -> TestResult<TxResult> {
+        self.app
+            .do_query_app(
+                QueryRequest::TxResult { hash },
+                0, // zero means to use the latest height
+                false,
+            )
+            .map(|res| res.as_tx_result())
+            .into()
+    }
+
+    /// Query the total gas consumed by a contract's entry points, according to
+    /// the node's metrics.
+    pub fn query_contract_gas_used(&self, contract: Addr) -> u64 {
+        self.app.do_query_contract_gas_used(&contract)
+    }
 }
 
-// Rust VM doesn't support gas, so we introduce these convenience methods that
-// don't take a `gas_limit` parameter.
+// Rust VM doesn't meter gas inside contracts, so we introduce these convenience
+// methods that don't take a `gas_limit` parameter. The host still charges gas
+// for some operations (e.g. JSON encoding), so we use the maximum gas limit.
 impl TestSuite<RustVm> {
     /// Execute a single message.
     pub fn execute_message(
@@ -216,7 +386,7 @@ impl TestSuite<RustVm> {
         signer: &TestAccount,
         msg: Message,
     ) -> anyhow::Result<TestResult<Vec<Event>>> {
-        self.execute_message_with_gas(signer, 0, msg)
+        self.execute_message_with_gas(signer, u64::MAX, msg)
     }
 
     /// Execute one or more messages.
@@ -225,12 +395,12 @@ impl TestSuite<RustVm> {
         signer: &TestAccount,
         msgs: Vec<Message>,
     ) -> anyhow::Result<TestResult<Vec<Event>>> {
-        self.execute_messages_with_gas(signer, 0, msgs)
+        self.execute_messages_with_gas(signer, u64::MAX, msgs)
     }
 
     /// Upload a code. Return the code's hash.
     pub fn upload(&mut self, signer: &TestAccount, code: Binary) -> anyhow::Result<Hash> {
-        self.upload_with_gas(signer, 0, code)
+        self.upload_with_gas(signer, u64::MAX, code)
     }
 
     /// Instantiate a contract. Return the contract's address.
@@ -245,7 +415,7 @@ impl TestSuite<RustVm> {
         M: Serialize,
         S: Into<Binary>,
     {
-        self.instantiate_with_gas(signer, 0, code_hash, salt, msg)
+        self.instantiate_with_gas(signer, u64::MAX, code_hash, salt, msg)
     }
 
     /// Upload a code and instantiate a contract with it in one go. Return the
@@ -261,6 +431,17 @@ impl TestSuite<RustVm> {
         M: Serialize,
         S: Into<Binary>,
     {
-        self.upload_and_instantiate_with_gas(signer, 0, code, salt, msg)
+        self.upload_and_instantiate_with_gas(signer, u64::MAX, code, salt, msg)
     }
 }
+
+/// Pair each transaction with its hash, computed the same way as in
+/// `App::do_finalize_block_raw`.
+fn hash_txs(txs: Vec<Tx>) -> anyhow::Result<Vec<(Hash, Tx)>> {
+    txs.into_iter()
+        .map(|tx| {
+            let tx_hash = Hash::from_slice(sha2_256(&to_json_vec(&tx)?));
+            Ok((tx_hash, tx))
+        })
+        .collect()
+}
@@ -33,6 +33,7 @@ impl TestVm for RustVm {
             .with_query(Box::new(grug_bank::query))
             .with_bank_execute(Box::new(grug_bank::bank_execute))
             .with_bank_query(Box::new(grug_bank::bank_query))
+            .with_sudo(Box::new(grug_bank::sudo))
             .build()
             .into_bytes()
             .into()
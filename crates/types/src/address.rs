@@ -37,6 +37,8 @@ forward_ref_partial_eq!(Addr, Addr);
 impl Addr {
     /// Addresses are encoded as lowercase hex strings, with the 0x prefix.
     pub const PREFIX: &'static str = "0x";
+    /// Prefix of the preimage of sequential contract addresses.
+    const SEQUENTIAL_PREFIX: &'static [u8] = b"sequential";
 
     /// Create a new address from a 32-byte byte slice.
     pub const fn from_slice(slice: [u8; Hash::LENGTH]) -> Self {
@@ -48,6 +50,11 @@ impl Addr {
     /// sha256(deployer_addr | code_hash | salt)
     ///
     /// where | means byte concatenation.
+    ///
+    /// The concatenation doesn't need to be length-prefixed to be unambiguous:
+    /// the deployer address and code hash are both of fixed length, and the
+    /// salt, the only input of variable length, comes last. Thus no two
+    /// different sets of inputs produce the same preimage.
     pub fn compute(deployer: &Addr, code_hash: &Hash, salt: &[u8]) -> Self {
         let mut hasher = Sha256::new();
         hasher.update(deployer);
@@ -56,6 +63,23 @@ impl Addr {
         Self(Hash(hasher.finalize().into()))
     }
 
+    /// Compute the address of a contract instantiated with a sequential
+    /// address, as:
+    ///
+    /// sha256("sequential" | counter)
+    ///
+    /// where `counter` is the big endian encoding of the global instantiation
+    /// counter, and | means byte concatenation.
+    ///
+    /// The preimage is shorter than that of `compute`, so the two schemes never
+    /// produce the same address.
+    pub fn compute_sequential(counter: u64) -> Self {
+        let mut hasher = Sha256::new();
+        hasher.update(Self::SEQUENTIAL_PREFIX);
+        hasher.update(counter.to_be_bytes());
+        Self(Hash(hasher.finalize().into()))
+    }
+
     /// Generate a mock address from use in testing.
     pub const fn mock(index: u8) -> Self {
         let mut bytes = [0u8; Hash::LENGTH];
@@ -191,7 +215,7 @@ impl<'de> de::Visitor<'de> for AddrVisitor {
 mod tests {
     use {
         super::*,
-        crate::{from_json_value, to_json_value},
+        crate::{from_json_value, hash, to_json_value},
         hex_literal::hex,
         serde_json::json,
     };
@@ -213,4 +237,29 @@ mod tests {
         assert_eq!(MOCK_ADDR, Addr::from_str(MOCK_STR).unwrap());
         assert_eq!(MOCK_ADDR, from_json_value::<Addr>(json!(MOCK_STR)).unwrap());
     }
+
+    #[test]
+    fn computing_contract_addresses() {
+        let deployer = Addr::mock(1);
+        let code_hash = Hash::from_slice([2; Hash::LENGTH]);
+
+        // The preimage is exactly the deployer, code hash, and salt, in order.
+        let mut preimage = deployer.to_vec();
+        preimage.extend_from_slice(&code_hash);
+        preimage.extend_from_slice(b"abc");
+        assert_eq!(
+            Addr::compute(&deployer, &code_hash, b"abc"),
+            Addr(hash(preimage))
+        );
+
+        // Moving bytes between the code hash and the salt changes the address.
+        let mut shifted_hash = code_hash.clone();
+        shifted_hash[Hash::LENGTH - 1] = b'a';
+        let mut shifted_salt = vec![2];
+        shifted_salt.extend_from_slice(b"bc");
+        assert_ne!(
+            Addr::compute(&deployer, &code_hash, b"abc"),
+            Addr::compute(&deployer, &shifted_hash, &shifted_salt),
+        );
+    }
 }
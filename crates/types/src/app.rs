@@ -1,10 +1,13 @@
 use {
-    crate::{Addr, Hash, Message, Timestamp, Uint64},
+    crate::{Addr, Hash, Message, Timestamp, Udec128, Uint128, Uint64},
     borsh::{BorshDeserialize, BorshSerialize},
     hex_literal::hex,
     serde::{Deserialize, Serialize},
     serde_with::skip_serializing_none,
-    std::collections::BTreeSet,
+    std::{
+        collections::{BTreeMap, BTreeSet},
+        io,
+    },
 };
 
 /// Genesis messages don't have senders, so we use this mock up hash as the
@@ -19,6 +22,21 @@ pub const GENESIS_BLOCK_HASH: Hash = Hash(hex!(
     "d04b98f48e8f8bcc15c6ae5ac050801cd6dcfd428fb5f9e65c4e16e7807340fa"
 ));
 
+/// The default of `Config::max_msg_depth`, if it's left out of the JSON.
+pub const DEFAULT_MAX_MSG_DEPTH: u32 = 30;
+
+/// The default of `Config::max_submsgs`, if it's left out of the JSON.
+pub const DEFAULT_MAX_SUBMSGS: u32 = 100;
+
+/// The default of `Config::max_msg_size`, if it's left out of the JSON.
+pub const DEFAULT_MAX_MSG_SIZE: u32 = 1024 * 1024;
+
+/// The default of `Config::fee_denom`, if it's left out of the JSON.
+pub const DEFAULT_FEE_DENOM: &str = "ugrug";
+
+/// The default of `Config::tx_result_retention`, if it's left out of the JSON.
+pub const DEFAULT_TX_RESULT_RETENTION: u64 = 100_000;
+
 /// The chain's genesis state. To be included in the `app_state` field of
 /// CometBFT's `genesis.json`.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
@@ -58,6 +76,129 @@ pub struct Config {
     pub permissions: Permissions,
     /// Code hashes that are allowed as IBC light clients.
     pub allowed_clients: BTreeSet<Hash>,
+    /// The maximum depth of nested messages. A message sent by a transaction
+    /// is of depth zero; a submessage emitted while handling a message of depth
+    /// `n` is of depth `n + 1`.
+    ///
+    /// Prevents deeply nested contract calls (e.g. a contract that executes
+    /// itself) from exhausting the host's stack.
+    #[serde(default = "default_max_msg_depth")]
+    pub max_msg_depth: u32,
+    /// The maximum number of submessages a single contract response may
+    /// contain.
+    ///
+    /// The depth limit alone doesn't stop a contract from emitting thousands
+    /// of submessages at the same depth, which could blow up block processing
+    /// time.
+    #[serde(default = "default_max_submsgs")]
+    pub max_submsgs: u32,
+    /// The maximum size, in bytes, of a message serialized as JSON.
+    ///
+    /// Checked before a message is processed, so that a message carrying a
+    /// giant payload (e.g. a huge instantiate message) is rejected before any
+    /// VM work is done on it.
+    #[serde(default = "default_max_msg_size")]
+    pub max_msg_size: u32,
+    /// The maximum total amount of a denom that may be transferred chain-wide
+    /// within a single block, indexed by denoms. Denoms not listed here can be
+    /// transferred without limit.
+    #[serde(default)]
+    pub transfer_caps: BTreeMap<String, Uint128>,
+    /// The denom in which fees are paid, and the default denom for fee-related
+    /// operations. Must be a valid denom; this is checked at genesis and
+    /// whenever the config is updated.
+    #[serde(default = "default_fee_denom")]
+    pub fee_denom: String,
+    /// If set, only coins of these denoms may be transferred. Adding a denom
+    /// to this set registers it; as with the rest of the config, only the
+    /// owner can do so. `None` means coins of any denom may be transferred.
+    #[serde(default)]
+    pub registered_denoms: Option<BTreeSet<String>>,
+    /// The share of a transaction's unused gas (its gas limit minus the gas it
+    /// has consumed) to be refunded to the sender. Must be between zero and
+    /// one; zero means no refund.
+    ///
+    /// The host computes the refund and passes it to the sender account's
+    /// `after_tx` entry point, which is responsible for settling it, as it is
+    /// for charging the fee in the first place.
+    #[serde(default)]
+    pub gas_refund_ratio: Udec128,
+    /// Gas costs of operations performed by the host, as opposed to those
+    /// performed inside the VM, which are metered by the VM itself.
+    ///
+    /// These are part of the config, rather than set by each node, because
+    /// every node must charge the same amount of gas for the same transaction,
+    /// or they'd disagree on its outcome.
+    #[serde(default)]
+    pub gas_config: GasConfig,
+    /// The number of most recent blocks whose transaction results are kept,
+    /// for clients to look up by transaction hash. Results of older blocks are
+    /// pruned, so that they don't grow the state without bound. Zero means no
+    /// results are kept.
+    #[serde(default = "default_tx_result_retention")]
+    pub tx_result_retention: u64,
+}
+
+// The fields added to `Config` after its initial release default to these
+// values, so that configs written before them, e.g. in an existing genesis
+// file, still deserialize.
+
+fn default_max_msg_depth() -> u32 {
+    DEFAULT_MAX_MSG_DEPTH
+}
+
+fn default_max_submsgs() -> u32 {
+    DEFAULT_MAX_SUBMSGS
+}
+
+fn default_max_msg_size() -> u32 {
+    DEFAULT_MAX_MSG_SIZE
+}
+
+fn default_fee_denom() -> String {
+    DEFAULT_FEE_DENOM.to_string()
+}
+
+fn default_tx_result_retention() -> u64 {
+    DEFAULT_TX_RESULT_RETENTION
+}
+
+/// Gas costs of operations performed by the host. See `Config::gas_config`.
+#[derive(
+    Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, Copy, PartialEq, Eq,
+)]
+#[serde(deny_unknown_fields)]
+pub struct GasConfig {
+    /// Gas charged per byte of JSON that the host serializes as input to, or
+    /// deserializes from output of, a contract call.
+    pub json_codec_per_byte: u64,
+    /// Gas charged each time a contract reads the gas meter with the
+    /// `gas_remaining` import.
+    pub gas_remaining: u64,
+    /// Gas charged per byte of code uploaded, for the VM to compile it.
+    ///
+    /// Related config in CosmWasm:
+    /// <https://github.com/CosmWasm/wasmd/blob/v0.51.0/x/wasm/keeper/gas_register.go#L20-L21>
+    pub upload_per_byte: u64,
+    /// Gas charged per byte of input to the imports that encode or decode data
+    /// for a contract: base64, hex, and canonical JSON.
+    pub encoding_per_byte: u64,
+    /// Gas set aside for the sender account's `after_tx` entry point, out of
+    /// the gas a transaction has left after its messages. This is never
+    /// refunded, so `after_tx` can always run, whatever the refund ratio.
+    pub after_tx_reserve: u64,
+}
+
+impl Default for GasConfig {
+    fn default() -> Self {
+        Self {
+            json_codec_per_byte: 1,
+            gas_remaining: 10,
+            upload_per_byte: 3,
+            encoding_per_byte: 1,
+            after_tx_reserve: 100_000,
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
@@ -90,10 +231,166 @@ pub struct BlockInfo {
     pub hash: Hash,
 }
 
-#[skip_serializing_none]
+/// A program code that has been uploaded to the chain.
 #[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(deny_unknown_fields)]
+pub struct Code {
+    pub byte_code: Vec<u8>,
+    /// The version of the host-guest interface that the code is built against.
+    /// Zero for code uploaded before the version was recorded.
+    pub interface_version: u32,
+}
+
+/// An account, i.e. a contract, as stored by the host.
+///
+/// The Borsh encoding, which is how accounts are stored, is versioned by
+/// `ACCOUNT_LAYOUT_VERSION`; see its doc for details.
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
 pub struct Account {
     pub code_hash: Hash,
     pub admin: Option<Addr>,
+    /// Whether the account's `receive` entry point is to be called when it's
+    /// sent coins with a `Message::Transfer`. Opted into at instantiation.
+    #[serde(default)]
+    pub receive_hook: bool,
+    /// Whether the account rejects being executed with funds attached. Opted
+    /// into at instantiation by contracts that don't handle funds, so that
+    /// they can't be lost to it by accident.
+    #[serde(default)]
+    pub strict_funds: bool,
+    /// The entry points the host is allowed to call on the account. `None`
+    /// means all of them. Recorded at instantiation, and kept across
+    /// migrations.
+    #[serde(default)]
+    pub entry_points: Option<BTreeSet<String>>,
+    /// Whether other contracts may read the account's storage directly with
+    /// `WasmRaw` queries. Opted into at instantiation; otherwise a contract's
+    /// storage can only be read by others through its `query` entry point.
+    #[serde(default)]
+    pub raw_queries: bool,
+}
+
+// --------------------------- account storage layout --------------------------
+
+/// The version of `Account`'s Borsh encoding, written as its first byte.
+///
+/// Borsh isn't self-describing, so a record written before a field was added
+/// can't be decoded into the new struct without knowing its layout. Whenever a
+/// field is added to `Account`, bump this version, and teach the decoder to
+/// upgrade records of the previous layout, filling in the new fields with
+/// their defaults. Upgraded records are written in the latest layout the next
+/// time they're saved.
+///
+/// - v1: `code_hash`, `admin`. The original layout, which has no version byte.
+///   As such, its records can't be told apart from those of later layouts by
+///   their bytes, so the decoder doesn't accept them; instead, the app's store
+///   migration rewrites them in the v2 layout.
+/// - v2: adds the version byte, `receive_hook`, `strict_funds`,
+///   `entry_points`, and `raw_queries`
+pub const ACCOUNT_LAYOUT_VERSION: u8 = 2;
+
+impl BorshSerialize for Account {
+    fn serialize<W>(&self, writer: &mut W) -> io::Result<()>
+    where
+        W: io::Write,
+    {
+        // Serde's `Serialize` is in scope too, so we can't use method syntax.
+        BorshSerialize::serialize(&ACCOUNT_LAYOUT_VERSION, writer)?;
+        BorshSerialize::serialize(&self.code_hash, writer)?;
+        BorshSerialize::serialize(&self.admin, writer)?;
+        BorshSerialize::serialize(&self.receive_hook, writer)?;
+        BorshSerialize::serialize(&self.strict_funds, writer)?;
+        BorshSerialize::serialize(&self.entry_points, writer)?;
+        BorshSerialize::serialize(&self.raw_queries, writer)
+    }
+}
+
+impl BorshDeserialize for Account {
+    fn deserialize_reader<R>(reader: &mut R) -> io::Result<Self>
+    where
+        R: io::Read,
+    {
+        let version = u8::deserialize_reader(reader)?;
+        if version != ACCOUNT_LAYOUT_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unknown account layout version: {version}"),
+            ));
+        }
+
+        Ok(Self {
+            code_hash: Hash::deserialize_reader(reader)?,
+            admin: Option::<Addr>::deserialize_reader(reader)?,
+            receive_hook: bool::deserialize_reader(reader)?,
+            strict_funds: bool::deserialize_reader(reader)?,
+            entry_points: Option::<BTreeSet<String>>::deserialize_reader(reader)?,
+            raw_queries: bool::deserialize_reader(reader)?,
+        })
+    }
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{from_borsh_slice, from_json_value, to_borsh_vec, NumberConst},
+        serde_json::json,
+    };
+
+    #[test]
+    fn config_defaults_for_later_fields() {
+        // A config with only the fields it was first released with.
+        let cfg: Config = from_json_value(json!({
+            "owner": null,
+            "bank": Addr::mock(1),
+            "begin_blockers": [],
+            "end_blockers": [],
+            "permissions": {
+                "upload": "everybody",
+                "instantiate": "everybody",
+                "create_client": "everybody",
+                "create_connection": "everybody",
+                "create_channel": "everybody",
+            },
+            "allowed_clients": [],
+        }))
+        .unwrap();
+
+        assert_eq!(cfg.max_msg_depth, DEFAULT_MAX_MSG_DEPTH);
+        assert_eq!(cfg.max_submsgs, DEFAULT_MAX_SUBMSGS);
+        assert_eq!(cfg.max_msg_size, DEFAULT_MAX_MSG_SIZE);
+        assert!(cfg.transfer_caps.is_empty());
+        assert_eq!(cfg.fee_denom, DEFAULT_FEE_DENOM);
+        assert_eq!(cfg.registered_denoms, None);
+        assert_eq!(cfg.gas_refund_ratio, Udec128::ZERO);
+        assert_eq!(cfg.gas_config, GasConfig::default());
+        assert_eq!(cfg.tx_result_retention, DEFAULT_TX_RESULT_RETENTION);
+    }
+
+    #[test]
+    fn account_round_trip() {
+        let account = Account {
+            code_hash: Hash::from_slice([1; Hash::LENGTH]),
+            admin: None,
+            receive_hook: true,
+            strict_funds: false,
+            entry_points: Some(["execute".to_string()].into()),
+            raw_queries: true,
+        };
+
+        let bytes = to_borsh_vec(&account).unwrap();
+        assert_eq!(bytes[0], ACCOUNT_LAYOUT_VERSION);
+        assert_eq!(from_borsh_slice::<Account>(&bytes).unwrap(), account);
+
+        // Unknown versions are rejected.
+        let mut bytes = bytes;
+        for version in [0, 1, ACCOUNT_LAYOUT_VERSION + 1] {
+            bytes[0] = version;
+            assert!(from_borsh_slice::<Account>(&bytes).is_err());
+        }
+    }
 }
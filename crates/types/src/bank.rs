@@ -28,18 +28,66 @@
 //! - Forward the funds to another account.
 
 use {
-    crate::{Addr, Coin, Coins},
+    crate::{Addr, Coin, Coins, Order, StdResult, Storage, Uint128},
+    borsh::{BorshDeserialize, BorshSerialize},
     serde::{Deserialize, Serialize},
     serde_with::skip_serializing_none,
+    std::collections::BTreeMap,
 };
 
 /// The execute message that the host provides the bank contract during the
 /// `bank_execute` function call.
+///
+/// The variants are untagged, so that `Transfer` is encoded the same way as
+/// before `TransferMulti` was added, and bank contracts built back then can
+/// still handle single transfers.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum BankMsg {
+    /// Send coins from one account to another.
+    Transfer { from: Addr, to: Addr, coins: Coins },
+    /// Send coins from one account to multiple others, all or nothing. Each
+    /// recipient appears once, with the sum of the coins it's sent.
+    TransferMulti {
+        from: Addr,
+        outputs: BTreeMap<Addr, Coins>,
+    },
+}
+
+/// An admin operation on a denom, sent with `Message::BankAdmin`.
+///
+/// The host only routes it to the bank contract's `sudo` entry point after
+/// verifying that the sender is the denom's admin, so the bank contract doesn't
+/// need to authenticate it.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
-pub struct BankMsg {
-    pub from: Addr,
-    pub to: Addr,
-    pub coins: Coins,
+#[serde(rename_all = "snake_case")]
+pub enum BankAdminMsg {
+    /// Set the metadata of a denom, replacing the existing one, if any.
+    SetMetadata {
+        denom: String,
+        metadata: DenomMetadata,
+    },
+}
+
+impl BankAdminMsg {
+    /// Return the denom that the operation is performed on.
+    pub fn denom(&self) -> &str {
+        match self {
+            BankAdminMsg::SetMetadata { denom, .. } => denom,
+        }
+    }
+}
+
+/// Descriptive information of a denom, for display purposes.
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DenomMetadata {
+    pub name: String,
+    pub symbol: String,
+    /// The number of decimal places between the denom's base unit and its
+    /// display unit, e.g. 6 if one display unit is 1,000,000 base units.
+    pub decimals: u8,
+    pub description: Option<String>,
 }
 
 /// The query message that the host provides the bank contract during the
@@ -52,17 +100,37 @@ pub enum BankQuery {
         address: Addr,
         denom: String,
     },
+    /// Similar to `Balance`, but distinguishes an address that has never held
+    /// the denom (returns `None`) from one that has held it but whose balance
+    /// is now zero (returns a zero coin).
+    BalanceExact {
+        address: Addr,
+        denom: String,
+    },
+    /// Enumerate an account's balances in all denoms.
+    ///
+    /// The balances are returned as `Coins`, which are always sorted by
+    /// denom, so `order` only decides which page of denoms is returned, not
+    /// the order of the coins within it.
     Balances {
         address: Addr,
         start_after: Option<String>,
         limit: Option<u32>,
+        order: Option<Order>,
     },
     Supply {
         denom: String,
     },
+    /// Enumerate all tokens' total supplies, in the given order of denoms
+    /// (ascending by default), starting from the denom right after
+    /// `start_after` (exclusive).
+    ///
+    /// As with `Balances`, the supplies within a page are always sorted by
+    /// denom in ascending order. A `limit` of zero is treated as one.
     Supplies {
         start_after: Option<String>,
         limit: Option<u32>,
+        order: Option<Order>,
     },
 }
 
@@ -80,9 +148,21 @@ pub enum BankQuery {
 #[serde(rename_all = "snake_case")]
 pub enum BankQueryResponse {
     Balance(Coin),
+    BalanceExact(Option<Coin>),
     Balances(Coins),
     Supply(Coin),
-    Supplies(Coins),
+    Supplies(SuppliesResponse),
+}
+
+/// A page of tokens' total supplies.
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct SuppliesResponse {
+    pub supplies: Coins,
+    /// The last denom visited in this page, if there are more denoms after it.
+    /// Use it as `start_after` to query the next page. `None` if this is the
+    /// last page.
+    pub next_key: Option<String>,
 }
 
 impl BankQueryResponse {
@@ -93,6 +173,13 @@ impl BankQueryResponse {
         coin
     }
 
+    pub fn as_balance_exact(self) -> Option<Coin> {
+        let BankQueryResponse::BalanceExact(maybe_coin) = self else {
+            panic!("BankQueryResponse is not BalanceExact");
+        };
+        maybe_coin
+    }
+
     pub fn as_balances(self) -> Coins {
         let BankQueryResponse::Balances(coins) = self else {
             panic!("BankQueryResponse is not Balances");
@@ -107,10 +194,29 @@ impl BankQueryResponse {
         coin
     }
 
-    pub fn as_supplies(self) -> Coins {
-        let BankQueryResponse::Supplies(coins) = self else {
+    pub fn as_supplies(self) -> SuppliesResponse {
+        let BankQueryResponse::Supplies(resp) = self else {
             panic!("BankQueryResponse is not Supplies");
         };
-        coins
+        resp
     }
 }
+
+/// Direct access to the balances in a bank contract's storage, bypassing the
+/// VM.
+///
+/// The host uses this for trusted operations of its own, such as minting the
+/// genesis balances, where calling the bank contract once per account would be
+/// slow. It's implemented by the library of a bank contract, so that the host
+/// doesn't need to know the contract's storage layout. It must keep the same
+/// invariants (e.g. total supplies) as the contract itself does.
+///
+/// No message or import leads to this, so contracts can't use it.
+pub trait BankStore {
+    /// Return an account's balance of the given denom, zero if it has none.
+    fn balance(&self, storage: &dyn Storage, address: &Addr, denom: &str) -> StdResult<Uint128>;
+
+    /// Increase an account's balances, and the total supplies, by the given
+    /// coins.
+    fn mint(&self, storage: &mut dyn Storage, address: &Addr, coins: &Coins) -> StdResult<()>;
+}
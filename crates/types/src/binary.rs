@@ -1,4 +1,5 @@
 use {
+    crate::{StdError, StdResult},
     borsh::{BorshDeserialize, BorshSerialize},
     data_encoding::BASE64,
     serde::{de, ser},
@@ -8,6 +9,16 @@ use {
     },
 };
 
+/// The maximum length, in bytes, of a `Binary` deserialized from JSON.
+///
+/// `Binary`s in messages, such as the code in `Message::Upload`, come from
+/// untrusted input. Without a bound, a single huge field could make the node
+/// allocate an arbitrary amount of memory while decoding it.
+///
+/// Whether a transaction decodes must not differ between nodes, so this is a
+/// constant rather than a setting.
+pub const MAX_BINARY_LENGTH: usize = 8 * 1024 * 1024;
+
 #[derive(
     BorshSerialize, BorshDeserialize, Default, Clone, PartialEq, Eq, Hash, PartialOrd, Ord,
 )]
@@ -17,6 +28,24 @@ impl Binary {
     pub fn empty() -> Self {
         Self(vec![])
     }
+
+    /// Decode a base64 string, rejecting it if it would decode to more than
+    /// `MAX_BINARY_LENGTH` bytes. The length is checked before anything is
+    /// allocated.
+    pub fn from_base64(encoded: &str) -> StdResult<Self> {
+        // The decoded length is computed from the string's length alone.
+        let length = BASE64
+            .decode_len(encoded.len())
+            .map_err(|_| StdError::decode("base64"))?;
+        if length > MAX_BINARY_LENGTH {
+            return Err(StdError::binary_too_long(length, MAX_BINARY_LENGTH));
+        }
+
+        BASE64
+            .decode(encoded.as_bytes())
+            .map(Self)
+            .map_err(|_| StdError::decode("base64"))
+    }
 }
 
 impl AsRef<[u8]> for Binary {
@@ -124,9 +153,55 @@ impl<'de> de::Visitor<'de> for BinaryVisitor {
     where
         E: de::Error,
     {
-        BASE64
-            .decode(v.as_bytes())
-            .map(Binary)
-            .map_err(|err| E::custom(format!("Invalid base64: {err}")))
+        Binary::from_base64(v).map_err(E::custom)
+    }
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {super::*, crate::from_json_value, serde_json::json};
+
+    #[test]
+    fn deserializing_binary_within_bound() {
+        let binary = from_json_value::<Binary>(json!("aGVsbG8=")).unwrap();
+        assert_eq!(binary, Binary::from("hello"));
+    }
+
+    #[test]
+    fn deserializing_binary_at_bound() {
+        // The longest base64 string that decodes to within the maximum. Adding
+        // one more group of four characters would exceed it, which is covered
+        // by the test below.
+        let encoded = "A".repeat(MAX_BINARY_LENGTH / 3 * 4);
+        let binary = from_json_value::<Binary>(json!(encoded)).unwrap();
+        assert_eq!(binary.len(), MAX_BINARY_LENGTH / 3 * 3);
+    }
+
+    #[test]
+    fn deserializing_oversized_binary() {
+        // A base64 string that decodes to slightly more than the maximum. It's
+        // rejected based on its length, before being decoded.
+        let length = (MAX_BINARY_LENGTH / 3 + 1) * 3;
+        let encoded = "A".repeat(length / 3 * 4);
+
+        // Decoding directly gives a typed error.
+        assert!(matches!(
+            Binary::from_base64(&encoded),
+            Err(StdError::BinaryTooLong { length: l, max: MAX_BINARY_LENGTH }) if l == length
+        ));
+
+        // The same error is reported when decoding it as part of JSON.
+        let err = from_json_value::<Binary>(json!(encoded)).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains(&StdError::binary_too_long(length, MAX_BINARY_LENGTH).to_string()));
+
+        // Invalid base64 is still rejected as such.
+        assert!(matches!(
+            Binary::from_base64("aGVsbG8"),
+            Err(StdError::Decode { encoding: "base64" })
+        ));
     }
 }
@@ -1,10 +1,20 @@
+#[cfg(any(test, feature = "proptest"))]
+use proptest::{
+    arbitrary::Arbitrary,
+    collection,
+    strategy::{BoxedStrategy, Strategy},
+};
 use {
-    crate::{NonZero, Number, NumberConst, StdError, StdResult, Uint128},
+    crate::{
+        from_json_slice, NonZero, Number, NumberConst, StdError, StdResult, Udec128, Uint128,
+        Uint256,
+    },
     borsh::{BorshDeserialize, BorshSerialize},
-    serde::{Deserialize, Serialize},
+    serde::{de, Deserialize, Serialize},
     std::{
         collections::{btree_map, BTreeMap},
         fmt,
+        ops::{Add, AddAssign, Sub, SubAssign},
         str::FromStr,
     },
 };
@@ -28,6 +38,29 @@ impl Coin {
 
         Self { denom, amount }
     }
+
+    /// Classify the coin's denom. See [`DenomKind`] for the rules.
+    pub fn denom_kind(&self) -> DenomKind {
+        DenomKind::of(&self.denom)
+    }
+
+    /// Round the amount down to a multiple of `10^decimals`, i.e. zero out its
+    /// lowest `decimals` digits. E.g. `123456` rounded down by 3 is `123000`.
+    ///
+    /// The amount becomes zero if it has no more than `decimals` digits.
+    pub fn round_down_to(&self, decimals: u8) -> Coin {
+        // If the power overflows, it's greater than any amount, so all digits
+        // are zeroed out.
+        let amount = match Uint128::TEN.checked_pow(decimals as u32) {
+            Ok(unit) => self.amount / unit * unit,
+            Err(_) => Uint128::ZERO,
+        };
+
+        Coin {
+            denom: self.denom.clone(),
+            amount,
+        }
+    }
 }
 
 impl fmt::Display for Coin {
@@ -42,6 +75,97 @@ impl fmt::Debug for Coin {
     }
 }
 
+// -------------------------------- denom kind ---------------------------------
+
+/// The prefix of denoms of tokens created by contracts.
+pub const FACTORY_DENOM_PREFIX: &str = "factory/";
+
+/// The minimum length of a denom, in bytes.
+pub const MIN_DENOM_LEN: usize = 3;
+
+/// The maximum length of a denom, in bytes.
+pub const MAX_DENOM_LEN: usize = 128;
+
+/// Classification of a denom by its namespace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DenomKind {
+    /// A token native to the chain, e.g. `ugrug` or `ibc/{hash}`.
+    ///
+    /// The denom must start with an ASCII letter, and consist of one or more
+    /// non-empty parts separated by `/`, each consisting only of ASCII
+    /// alphanumerics and `.`, `_`, `-`, `:`.
+    Native,
+    /// A token created by a contract, of the format
+    /// `factory/{creator}/{subdenom}`.
+    ///
+    /// The creator and subdenom must both be non-empty and consist only of
+    /// ASCII alphanumerics and `.`, `_`, `-`, `:`.
+    Factory,
+    /// Neither of the above.
+    Invalid,
+}
+
+impl DenomKind {
+    /// Classify the given denom.
+    pub fn of(denom: &str) -> Self {
+        if let Some(rest) = denom.strip_prefix(FACTORY_DENOM_PREFIX) {
+            let mut parts = rest.split('/');
+            return match (parts.next(), parts.next(), parts.next()) {
+                (Some(creator), Some(subdenom), None)
+                    if is_valid_denom_part(creator) && is_valid_denom_part(subdenom) =>
+                {
+                    Self::Factory
+                },
+                _ => Self::Invalid,
+            };
+        }
+
+        let starts_with_letter = denom.starts_with(|c: char| c.is_ascii_alphabetic());
+        if starts_with_letter && denom.split('/').all(is_valid_denom_part) {
+            Self::Native
+        } else {
+            Self::Invalid
+        }
+    }
+}
+
+/// Ensure the denom is of a valid length, and is either a native or a factory
+/// denom. See [`DenomKind`] for the rules.
+///
+/// Notably, this rejects `,`, and `:` at the end of the denom, which are used
+/// as separators when coins are stringified, so that valid coins always
+/// round-trip through `Display` and `FromStr`. A `:` elsewhere in the denom is
+/// fine, as `FromStr` splits each coin at its last `:`.
+pub fn validate_denom(denom: &str) -> StdResult<()> {
+    if denom.len() < MIN_DENOM_LEN || denom.len() > MAX_DENOM_LEN {
+        return Err(StdError::invalid_denom(
+            denom,
+            format!("length must be between {MIN_DENOM_LEN} and {MAX_DENOM_LEN}"),
+        ));
+    }
+
+    if DenomKind::of(denom) == DenomKind::Invalid {
+        return Err(StdError::invalid_denom(
+            denom,
+            "must be a native or factory denom",
+        ));
+    }
+
+    if denom.ends_with(':') {
+        return Err(StdError::invalid_denom(denom, "must not end with `:`"));
+    }
+
+    Ok(())
+}
+
+fn is_valid_denom_part(part: &str) -> bool {
+    !part.is_empty() && part.chars().all(is_valid_denom_char)
+}
+
+fn is_valid_denom_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-' | ':')
+}
+
 // ----------------------------------- coins -----------------------------------
 
 /// A record in the `Coins` map.
@@ -66,9 +190,12 @@ pub struct CoinRef<'a> {
 }
 
 /// A sorted list of coins or tokens.
-#[derive(
-    Serialize, Deserialize, BorshSerialize, BorshDeserialize, Default, Clone, PartialEq, Eq,
-)]
+///
+/// Coins are kept in a map ordered by denom, so iteration and serialization
+/// always follow the same order, regardless of the order in which the coins
+/// were inserted or appeared in the deserialized input. Two equal `Coins`
+/// therefore always have the same encoding, which consensus depends on.
+#[derive(Serialize, BorshSerialize, BorshDeserialize, Default, Clone, PartialEq, Eq)]
 pub struct Coins(BTreeMap<String, Uint128>);
 
 impl Coins {
@@ -86,6 +213,11 @@ impl Coins {
     // This can sometimes be confusing. Therefore we make this a special case
     // and stringifies it to a set of empty square brackets instead.
     pub const EMPTY_COINS_STR: &'static str = "[]";
+    /// The maximum number of coins that can be deserialized from JSON.
+    ///
+    /// Without a cap, a payload with millions of coins could exhaust the
+    /// memory while being decoded.
+    pub const MAX_LEN: usize = 1024;
 
     /// Create a new `Coins` without any coin.
     pub fn new_empty() -> Self {
@@ -100,6 +232,44 @@ impl Coins {
         Self([(denom, amount)].into())
     }
 
+    /// Parse coins from a JSON array of coins, e.g.
+    /// `[{"denom":"uatom","amount":"123"}]`. Denoms may be out of order, but
+    /// duplicates and zero amounts are rejected.
+    ///
+    /// This is intended for CLIs that accept this form in addition to the
+    /// compact `denom:amount,...` form parsed by `FromStr`.
+    pub fn from_json_str(s: &str) -> StdResult<Self> {
+        from_json_slice::<Vec<Coin>>(s)?.try_into()
+    }
+
+    /// Create a `Coins` from denoms and amounts that come from a trusted
+    /// source, e.g. the contract's own storage, and are known to contain no
+    /// duplicate denoms or zero amounts.
+    ///
+    /// This is only checked in debug builds, where a violation panics. In
+    /// release builds, no check is done: a duplicate denom overwrites the
+    /// earlier one, and zero amounts are kept. Use `TryFrom` for untrusted
+    /// data instead.
+    pub fn from_trusted<I>(iter: I) -> Self
+    where
+        I: IntoIterator<Item = (String, Uint128)>,
+    {
+        let mut map = BTreeMap::new();
+        for (denom, amount) in iter {
+            debug_assert!(
+                !amount.is_zero(),
+                "trusted coins have zero amount of `{denom}`"
+            );
+            debug_assert!(
+                !map.contains_key(&denom),
+                "trusted coins have duplicate denom `{denom}`"
+            );
+            map.insert(denom, amount);
+        }
+
+        Self(map)
+    }
+
     /// Return whether the `Coins` contains any coin at all.
     pub fn is_empty(&self) -> bool {
         self.0.is_empty()
@@ -111,8 +281,30 @@ impl Coins {
     }
 
     /// Return whether there is a non-zero amount of the given denom.
+    ///
+    /// A zero record, which a `Coins` shouldn't contain but may if it was
+    /// decoded from untrusted input without being normalized, is treated as
+    /// absent.
     pub fn has(&self, denom: &str) -> bool {
-        self.0.contains_key(denom)
+        self.0.get(denom).is_some_and(|amount| !amount.is_zero())
+    }
+
+    /// Remove coins of zero amount.
+    ///
+    /// The constructors of `Coins` and JSON deserialization never produce
+    /// these, but Borsh deserialization reads the map as is and doesn't reject
+    /// them.
+    pub fn normalize(&mut self) {
+        self.0.retain(|_, amount| !amount.is_zero());
+    }
+
+    /// Remove denoms whose amount is below the threshold of the same denom in
+    /// `thresholds`, e.g. to sweep dust balances.
+    ///
+    /// Denoms not found in `thresholds` have no threshold, and are kept.
+    pub fn remove_dust(&mut self, thresholds: &Coins) {
+        self.0
+            .retain(|denom, amount| !thresholds.0.get(denom).is_some_and(|min| amount < min));
     }
 
     /// Get the amount of the given denom.
@@ -121,6 +313,38 @@ impl Coins {
         self.0.get(denom).copied().unwrap_or(Uint128::ZERO)
     }
 
+    /// Get the amount of the given denom, or `None` if the denom doesn't exist.
+    ///
+    /// Unlike `amount_of`, this distinguishes an absent denom from one that is
+    /// present with a zero amount, which a normalized `Coins` never contains.
+    pub fn amount_of_checked(&self, denom: &str) -> Option<Uint128> {
+        self.0.get(denom).copied()
+    }
+
+    /// Return the coin with the largest amount, or `None` if the `Coins` is
+    /// empty. If multiple coins tie, the one whose denom comes first wins.
+    pub fn max_by_amount(&self) -> Option<CoinRef> {
+        self.into_iter().reduce(|max, coin| {
+            if coin.amount > max.amount {
+                coin
+            } else {
+                max
+            }
+        })
+    }
+
+    /// Return the coin with the smallest amount, or `None` if the `Coins` is
+    /// empty. If multiple coins tie, the one whose denom comes first wins.
+    pub fn min_by_amount(&self) -> Option<CoinRef> {
+        self.into_iter().reduce(|min, coin| {
+            if coin.amount < min.amount {
+                coin
+            } else {
+                min
+            }
+        })
+    }
+
     /// Do nothing if the `Coins` is empty; throw an error if not empty.
     pub fn assert_empty(&self) -> StdResult<()> {
         if !self.is_empty() {
@@ -144,9 +368,31 @@ impl Coins {
         Ok(CoinRef { denom, amount })
     }
 
+    /// If the `Coins` is exactly one coin, consume the `Coins` and return this
+    /// coin; otherwise throw error.
+    ///
+    /// Same as `one_coin`, but returns an owned `Coin`.
+    pub fn into_single(self) -> StdResult<Coin> {
+        let len = self.len();
+        let mut coins = self.into_iter();
+
+        let (Some(coin), None) = (coins.next(), coins.next()) else {
+            return Err(StdError::invalid_payment(1, len));
+        };
+
+        Ok(coin)
+    }
+
     /// Increase the amount of a denom by the given amount. If the denom doesn't
     /// exist, a new record is created.
+    ///
+    /// Increasing by zero is a no-op, so that no zero-amount record is created.
+    /// On overflow, an error is returned and the amount is left unchanged.
     pub fn increase_amount(&mut self, denom: &str, by: Uint128) -> StdResult<()> {
+        if by.is_zero() {
+            return Ok(());
+        }
+
         let Some(amount) = self.0.get_mut(denom) else {
             // if the denom doesn't exist, we just create a new record, and we
             // are done.
@@ -159,6 +405,23 @@ impl Coins {
         Ok(())
     }
 
+    /// Add another set of coins into this one, in place.
+    ///
+    /// On overflow, an error is returned and `self` is left unchanged, even if
+    /// some denoms could have been added before the overflowing one.
+    pub fn checked_add_assign(&mut self, other: &Coins) -> StdResult<()> {
+        // Check every sum before touching `self`, so that the merge is atomic.
+        for (denom, amount) in &other.0 {
+            self.amount_of(denom).checked_add(*amount)?;
+        }
+
+        for (denom, amount) in &other.0 {
+            self.increase_amount(denom, *amount)?;
+        }
+
+        Ok(())
+    }
+
     /// Decrease the amount of a denom by the given amount. Amount can't be
     /// reduced below zero. If the amount is reduced to exactly zero, the record
     /// is purged, so that only non-zero amount coins remain.
@@ -179,6 +442,300 @@ impl Coins {
         Ok(())
     }
 
+    /// Add another set of coins to this one, returning the sum.
+    ///
+    /// Error if the sum of any denom overflows.
+    pub fn checked_add(&self, other: &Coins) -> StdResult<Coins> {
+        let mut sum = self.clone();
+        sum.checked_add_assign(other)?;
+        Ok(sum)
+    }
+
+    /// Subtract another set of coins from this one, returning the difference.
+    /// Denoms whose amounts are reduced to exactly zero are purged, same as in
+    /// `decrease_amount`.
+    ///
+    /// Error if `other` contains a denom not found in `self`, or if the amount
+    /// of any denom would go below zero.
+    pub fn checked_sub(&self, other: &Coins) -> StdResult<Coins> {
+        let mut difference = self.clone();
+        for (denom, amount) in &other.0 {
+            difference.decrease_amount(denom, *amount)?;
+        }
+        Ok(difference)
+    }
+
+    /// Add up an iterator of coins into `Coins`.
+    ///
+    /// Unlike `TryFrom<Vec<Coin>>`, duplicate denoms are allowed; their amounts
+    /// are summed. Error if any coin has a zero amount, or if the sum of a denom
+    /// overflows.
+    ///
+    /// We can't implement `std::iter::Sum` for this, because `Sum` doesn't
+    /// allow returning a `Result`.
+    pub fn try_sum<I>(iter: I) -> StdResult<Self>
+    where
+        I: IntoIterator<Item = Coin>,
+    {
+        let mut coins = Self::new_empty();
+        for coin in iter {
+            if coin.amount.is_zero() {
+                return Err(StdError::invalid_coins(format!(
+                    "denom `{}` has zero amount",
+                    coin.denom
+                )));
+            }
+
+            coins.increase_amount(&coin.denom, coin.amount)?;
+        }
+
+        Ok(coins)
+    }
+
+    /// Split the coins into `n` equal shares. Return the shares, as well as the
+    /// remainder that can't be evenly divided.
+    ///
+    /// Error if `n` is zero.
+    pub fn split_shares(&self, n: u32) -> StdResult<(Vec<Coins>, Coins)> {
+        let divisor = Uint128::new(n.into());
+        let mut share = Self::new_empty();
+        let mut remainder = Self::new_empty();
+
+        for (denom, amount) in &self.0 {
+            let quotient = amount.checked_div(divisor)?;
+            if !quotient.is_zero() {
+                share.0.insert(denom.clone(), quotient);
+            }
+
+            let rem = amount.checked_rem(divisor)?;
+            if !rem.is_zero() {
+                remainder.0.insert(denom.clone(), rem);
+            }
+        }
+
+        Ok((vec![share; n as usize], remainder))
+    }
+
+    /// Subtract `other` from the coins, clamping each denom's amount at zero
+    /// instead of erroring. Return the result, as well as the shortfall, i.e.
+    /// the amounts that couldn't be subtracted.
+    ///
+    /// Useful for refunds, where the refundable amount may exceed what's owed.
+    pub fn sub_clamped(&self, other: &Coins) -> (Coins, Coins) {
+        let mut result = self.clone();
+        let mut shortfall = Self::new_empty();
+
+        for (denom, amount) in &other.0 {
+            let available = result.amount_of(denom);
+            if available > *amount {
+                // Can't underflow because `available > amount`.
+                result.0.insert(denom.clone(), available - *amount);
+            } else {
+                result.0.remove(denom);
+                if available < *amount {
+                    shortfall.0.insert(denom.clone(), *amount - available);
+                }
+            }
+        }
+
+        (result, shortfall)
+    }
+
+    /// Return a new `Coins` where each denom's amount is capped at the amount
+    /// of the same denom in `caps`.
+    ///
+    /// Denoms not found in `caps` are considered uncapped, and pass through
+    /// unchanged.
+    pub fn cap_by(&self, caps: &Coins) -> Coins {
+        // Caps are never zero, so neither are the capped amounts.
+        let inner = self
+            .0
+            .iter()
+            .map(|(denom, amount)| {
+                let amount = match caps.0.get(denom) {
+                    Some(cap) => *amount.min(cap),
+                    None => *amount,
+                };
+                (denom.clone(), amount)
+            })
+            .collect();
+
+        Self(inner)
+    }
+
+    /// Ensure each denom's amount doesn't exceed the amount of the same denom
+    /// in `caps`.
+    ///
+    /// Same as in `cap_by`, denoms not found in `caps` are considered uncapped.
+    /// Error on the first denom that exceeds its cap.
+    pub fn validate_max(&self, caps: &Coins) -> StdResult<()> {
+        for (denom, amount) in &self.0 {
+            if let Some(cap) = caps.0.get(denom) {
+                if amount > cap {
+                    return Err(StdError::ExceedsCap {
+                        denom: denom.clone(),
+                        amount: *amount,
+                        cap: *cap,
+                    });
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Return the portion of the coins that isn't locked, i.e., for each
+    /// denom, the amount minus the locked amount, or zero if more than the
+    /// amount is locked.
+    ///
+    /// Useful for vesting, where `locked` is the amount yet to be vested.
+    pub fn spendable(&self, locked: &Coins) -> Coins {
+        self.sub_clamped(locked).0
+    }
+
+    /// Compute the change to be returned for a payment, i.e. `paid` minus
+    /// `price`.
+    ///
+    /// Error if `paid` doesn't cover `price` in every denom.
+    pub fn make_change(paid: &Coins, price: &Coins) -> StdResult<Coins> {
+        let (change, shortfall) = paid.sub_clamped(price);
+
+        if let Some((denom, _)) = shortfall.0.into_iter().next() {
+            return Err(StdError::InsufficientPayment {
+                paid: paid.amount_of(&denom),
+                price: price.amount_of(&denom),
+                denom,
+            });
+        }
+
+        Ok(change)
+    }
+
+    /// Compare against another set of coins, returning the denoms only found
+    /// in one of them, and those found in both but with different amounts.
+    pub fn diff(&self, other: &Coins) -> CoinsDiff {
+        let mut diff = CoinsDiff::default();
+
+        for (denom, amount) in &self.0 {
+            match other.0.get(denom) {
+                None => {
+                    diff.only_in_left.0.insert(denom.clone(), *amount);
+                },
+                Some(other_amount) if other_amount != amount => {
+                    diff.differing
+                        .insert(denom.clone(), (*amount, *other_amount));
+                },
+                Some(_) => {},
+            }
+        }
+
+        for (denom, amount) in &other.0 {
+            if !self.0.contains_key(denom) {
+                diff.only_in_right.0.insert(denom.clone(), *amount);
+            }
+        }
+
+        diff
+    }
+
+    /// Compute the symmetric difference against another set of coins, as two
+    /// `Coins`: what `self` has in excess of `other`, and what `other` has in
+    /// excess of `self`.
+    ///
+    /// A denom found in only one of them goes entirely to that side; a denom
+    /// found in both but with different amounts contributes the difference to
+    /// the side with the larger amount. Adding the first to `other` and the
+    /// second to `self` makes the two equal.
+    pub fn symmetric_difference(&self, other: &Coins) -> (Coins, Coins) {
+        let CoinsDiff {
+            mut only_in_left,
+            mut only_in_right,
+            differing,
+        } = self.diff(other);
+
+        for (denom, (left, right)) in differing {
+            if left > right {
+                only_in_left.0.insert(denom, left - right);
+            } else {
+                only_in_right.0.insert(denom, right - left);
+            }
+        }
+
+        (only_in_left, only_in_right)
+    }
+
+    /// Compute the total value of the coins in terms of the `base` denom,
+    /// given the price of each denom in the base denom.
+    ///
+    /// The base denom itself is valued at 1 unless a price is explicitly given.
+    /// Products and the running sum are computed in `Uint256`, so only the
+    /// final result can overflow `Uint128`. Error if a denom has no price.
+    ///
+    /// Note that the returned coin has a zero amount if the coins are empty.
+    pub fn total_in(&self, prices: &BTreeMap<String, Uint128>, base: &str) -> StdResult<Coin> {
+        let mut total = Uint256::ZERO;
+        for (denom, amount) in &self.0 {
+            let price = match prices.get(denom) {
+                Some(price) => *price,
+                None if denom == base => Uint128::ONE,
+                None => {
+                    return Err(StdError::PriceNotFound {
+                        denom: denom.clone(),
+                    });
+                },
+            };
+
+            let value = Uint256::from(*amount).checked_mul(Uint256::from(price))?;
+            total = total.checked_add(value)?;
+        }
+
+        Ok(Coin {
+            denom: base.to_string(),
+            amount: total.try_into()?,
+        })
+    }
+
+    /// Compute, for each denom, the fraction of the given totals (e.g. the
+    /// total supplies) that the coins make up, rounded down.
+    ///
+    /// Denoms that don't appear in the totals, i.e. whose totals are zero, are
+    /// skipped. Error if a fraction is too large to be represented.
+    pub fn as_fraction_of(&self, totals: &Coins) -> StdResult<BTreeMap<String, Udec128>> {
+        let mut fractions = BTreeMap::new();
+        for (denom, amount) in &self.0 {
+            let Some(total) = totals.0.get(denom) else {
+                continue;
+            };
+
+            fractions.insert(denom.clone(), Udec128::checked_from_ratio(*amount, *total)?);
+        }
+
+        Ok(fractions)
+    }
+
+    /// Format the coins as a two-column table for human-facing output, with a
+    /// header row. Denoms are left-aligned and amounts right-aligned.
+    pub fn to_table(&self) -> String {
+        let rows = self
+            .0
+            .iter()
+            .map(|(denom, amount)| (denom.clone(), amount.to_string()))
+            .collect::<Vec<_>>();
+
+        let denom_width = rows.iter().map(|(d, _)| d.len()).max().unwrap_or(0).max(5);
+        let amount_width = rows.iter().map(|(_, a)| a.len()).max().unwrap_or(0).max(6);
+
+        let mut lines = vec![format!(
+            "{:<denom_width$}  {:>amount_width$}",
+            "denom", "amount"
+        )];
+        for (denom, amount) in rows {
+            lines.push(format!("{denom:<denom_width$}  {amount:>amount_width$}"));
+        }
+
+        lines.join("\n")
+    }
+
     /// Convert an iterator over denoms and amounts to `Coins`.
     ///
     /// Used internally for implementing `TryFrom<[Coin; N]>`,
@@ -191,9 +748,11 @@ impl Coins {
     {
         let mut map = BTreeMap::new();
         for (denom, amount) in iter {
+            validate_denom(&denom)?;
+
             if amount.is_zero() {
                 return Err(StdError::invalid_coins(format!(
-                    "denom `{}` as zero amount",
+                    "denom `{}` has zero amount",
                     denom
                 )));
             }
@@ -220,19 +779,33 @@ impl FromStr for Coins {
     type Err = StdError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        // handle special case: empty string
-        if s == Self::EMPTY_COINS_STR {
+        // handle special case: empty coins, which stringify to `[]`. also
+        // accept an empty or whitespace-only string, which is what a CLI
+        // typically passes when the user gives no coins.
+        if s == Self::EMPTY_COINS_STR || s.trim().is_empty() {
             return Ok(Coins::new_empty());
         }
 
         let mut map = BTreeMap::new();
         for coin_str in s.split(',') {
-            let Some((denom, amount_str)) = coin_str.split_once(':') else {
+            // catch stray commas, e.g. `uatom:1,` or `uatom:1,,uosmo:2`, which
+            // would otherwise be reported as a malformed coin with no content.
+            if coin_str.is_empty() {
+                return Err(StdError::invalid_coins(format!(
+                    "empty coin in `{s}`: leading, trailing, or consecutive commas are not allowed"
+                )));
+            }
+
+            // Split at the last colon, as the denom may contain colons but the
+            // amount may not.
+            let Some((denom, amount_str)) = coin_str.rsplit_once(':') else {
                 return Err(StdError::invalid_coins(format!(
                     "invalid coin `{coin_str}`: must be in the format {{denom}}:{{amount}}"
                 )));
             };
 
+            validate_denom(denom)?;
+
             let Ok(amount) = Uint128::from_str(amount_str) else {
                 return Err(StdError::invalid_coins(format!(
                     "invalid amount `{amount_str}`"
@@ -241,7 +814,7 @@ impl FromStr for Coins {
 
             if amount.is_zero() {
                 return Err(StdError::invalid_coins(format!(
-                    "denom `{denom}` as zero amount"
+                    "denom `{denom}` has zero amount"
                 )));
             }
 
@@ -286,6 +859,14 @@ impl From<Coin> for Coins {
     }
 }
 
+impl TryFrom<Coins> for Coin {
+    type Error = StdError;
+
+    fn try_from(coins: Coins) -> StdResult<Self> {
+        coins.into_single()
+    }
+}
+
 impl From<Coins> for Vec<Coin> {
     fn from(coins: Coins) -> Self {
         coins.into_iter().collect()
@@ -310,6 +891,27 @@ impl IntoIterator for Coins {
     }
 }
 
+/// Add each coin to the collection, summing the amounts of duplicate denoms.
+/// Coins of zero amount are ignored.
+///
+/// Panics if the sum of a denom overflows. Use `increase_amount` or `try_sum`
+/// instead if this is a concern.
+impl Extend<Coin> for Coins {
+    fn extend<T>(&mut self, iter: T)
+    where
+        T: IntoIterator<Item = Coin>,
+    {
+        for coin in iter {
+            if coin.amount.is_zero() {
+                continue;
+            }
+
+            self.increase_amount(&coin.denom, coin.amount)
+                .unwrap_or_else(|err| panic!("failed to extend coins: {err}"));
+        }
+    }
+}
+
 pub struct CoinsIter<'a>(btree_map::Iter<'a, String, Uint128>);
 
 impl<'a> Iterator for CoinsIter<'a> {
@@ -355,60 +957,347 @@ impl fmt::Debug for Coins {
     }
 }
 
-// ----------------------------------- tests -----------------------------------
+impl<'de> de::Deserialize<'de> for Coins {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        deserializer.deserialize_map(CoinsVisitor)
+    }
+}
 
-#[cfg(test)]
-mod tests {
-    use {
-        super::*,
-        crate::{from_json_value, to_json_value, Json},
-        serde_json::json,
-    };
+struct CoinsVisitor;
 
-    fn mock_coins() -> Coins {
-        Coins(
-            [
-                (String::from("uatom"), Uint128::new(123)),
-                (String::from("umars"), Uint128::new(456)),
-                (String::from("uosmo"), Uint128::new(789)),
-            ]
-            .into(),
+impl<'de> de::Visitor<'de> for CoinsVisitor {
+    type Value = Coins;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "a map from denoms to amounts, of no more than {} entries",
+            Coins::MAX_LEN
         )
     }
 
-    fn mock_coins_json() -> Json {
-        json!({
-            "uatom": "123",
-            "umars": "456",
-            "uosmo": "789",
-        })
-    }
+    fn visit_map<A>(self, mut access: A) -> Result<Self::Value, A::Error>
+    where
+        A: de::MapAccess<'de>,
+    {
+        let mut map = BTreeMap::new();
+        let mut len = 0;
+
+        // Count the entries read rather than the map's length, so that the cap
+        // also applies to payloads with repeated denoms. Bail out as soon as
+        // the cap is exceeded, without reading the rest of the input.
+        while let Some((denom, amount)) = access.next_entry::<String, Uint128>()? {
+            len += 1;
+            if len > Coins::MAX_LEN {
+                return Err(de::Error::custom(format!(
+                    "too many coins! max: {}",
+                    Coins::MAX_LEN
+                )));
+            }
 
-    #[test]
-    fn serializing_coins() {
-        assert_eq!(to_json_value(&mock_coins()).unwrap(), mock_coins_json());
+            validate_denom(&denom).map_err(de::Error::custom)?;
+
+            if amount.is_zero() {
+                return Err(de::Error::custom(format!(
+                    "denom `{denom}` has zero amount"
+                )));
+            }
+
+            map.insert(denom, amount);
+        }
+
+        Ok(Coins(map))
     }
+}
 
-    #[test]
-    fn deserializing_coins() {
-        // valid string
-        assert_eq!(
-            from_json_value::<Coins>(mock_coins_json()).unwrap(),
-            mock_coins()
-        );
+// The operators panic on error, same as those of the number types. Use
+// `checked_add` and `checked_sub` to handle errors instead.
 
-        // invalid json: contains zero amount
-        let illegal_json = json!([
-            {
-                "denom": "uatom",
-                "amount": "0",
-            },
-        ]);
-        assert!(from_json_value::<Coins>(illegal_json).is_err());
+impl Add<&Coins> for &Coins {
+    type Output = Coins;
 
-        // invalid json: contains duplicate
-        let illegal_json = json!([
-            {
+    fn add(self, rhs: &Coins) -> Coins {
+        self.checked_add(rhs).unwrap_or_else(|err| panic!("{err}"))
+    }
+}
+
+impl Sub<&Coins> for &Coins {
+    type Output = Coins;
+
+    fn sub(self, rhs: &Coins) -> Coins {
+        self.checked_sub(rhs).unwrap_or_else(|err| panic!("{err}"))
+    }
+}
+
+impl AddAssign<&Coins> for Coins {
+    fn add_assign(&mut self, rhs: &Coins) {
+        self.checked_add_assign(rhs)
+            .unwrap_or_else(|err| panic!("{err}"))
+    }
+}
+
+impl SubAssign<&Coins> for Coins {
+    fn sub_assign(&mut self, rhs: &Coins) {
+        *self = self.checked_sub(rhs).unwrap_or_else(|err| panic!("{err}"))
+    }
+}
+
+// ------------------------------- ordered coins -------------------------------
+
+/// A `Coins` that remembers the order in which its denoms were given, for
+/// display purposes only.
+///
+/// `Coins` always sorts its denoms, which can be surprising when, e.g., a CLI
+/// echoes back coins that the user typed in a different order. This view
+/// keeps the input order for `Display`, while equality and the underlying
+/// `Coins` (which is what should be stored or sent to the chain) ignore it.
+#[derive(Debug, Clone)]
+pub struct OrderedCoins {
+    coins: Coins,
+    denoms: Vec<String>,
+}
+
+impl OrderedCoins {
+    /// Return the sorted coins.
+    pub fn as_coins(&self) -> &Coins {
+        &self.coins
+    }
+
+    /// Consume self, returning the sorted coins.
+    pub fn into_coins(self) -> Coins {
+        self.coins
+    }
+}
+
+// Parses the same `denom1:amount1,...,denomN:amountN` format as `Coins`.
+impl FromStr for OrderedCoins {
+    type Err = StdError;
+
+    fn from_str(s: &str) -> StdResult<Self> {
+        let coins = Coins::from_str(s)?;
+
+        // `Coins::from_str` has already validated the input, so every coin is
+        // in the `denom:amount` format, without duplicates.
+        let denoms = if coins.is_empty() {
+            vec![]
+        } else {
+            s.split(',')
+                .filter_map(|coin_str| coin_str.rsplit_once(':'))
+                .map(|(denom, _)| denom.to_string())
+                .collect()
+        };
+
+        Ok(Self { coins, denoms })
+    }
+}
+
+impl From<Coins> for OrderedCoins {
+    fn from(coins: Coins) -> Self {
+        let denoms = coins.0.keys().cloned().collect();
+        Self { coins, denoms }
+    }
+}
+
+impl From<OrderedCoins> for Coins {
+    fn from(ordered: OrderedCoins) -> Self {
+        ordered.coins
+    }
+}
+
+impl PartialEq for OrderedCoins {
+    fn eq(&self, other: &Self) -> bool {
+        self.coins == other.coins
+    }
+}
+
+impl Eq for OrderedCoins {}
+
+impl fmt::Display for OrderedCoins {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // special case: empty string
+        if self.coins.is_empty() {
+            return f.write_str(Coins::EMPTY_COINS_STR);
+        }
+
+        let s = self
+            .denoms
+            .iter()
+            .map(|denom| format!("{denom}:{}", self.coins.amount_of(denom)))
+            .collect::<Vec<_>>()
+            .join(",");
+
+        f.write_str(&s)
+    }
+}
+
+// -------------------------------- coins diff ---------------------------------
+
+/// The difference between two `Coins`, as returned by `Coins::diff`.
+#[derive(Default, Debug, Clone, PartialEq, Eq)]
+pub struct CoinsDiff {
+    /// Coins whose denoms are only found in the left-hand side.
+    pub only_in_left: Coins,
+    /// Coins whose denoms are only found in the right-hand side.
+    pub only_in_right: Coins,
+    /// Denoms found in both sides but with different amounts:
+    /// denom => (left amount, right amount)
+    pub differing: BTreeMap<String, (Uint128, Uint128)>,
+}
+
+impl CoinsDiff {
+    /// Whether the two sides are equal.
+    pub fn is_empty(&self) -> bool {
+        self.only_in_left.is_empty() && self.only_in_right.is_empty() && self.differing.is_empty()
+    }
+}
+
+impl fmt::Display for CoinsDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "only in left: {}", self.only_in_left)?;
+        writeln!(f, "only in right: {}", self.only_in_right)?;
+        write!(f, "differing:")?;
+
+        if self.differing.is_empty() {
+            return write!(f, " {}", Coins::EMPTY_COINS_STR);
+        }
+
+        for (denom, (left, right)) in &self.differing {
+            write!(f, "\n  {denom}: {left} != {right}")?;
+        }
+
+        Ok(())
+    }
+}
+
+// --------------------------------- proptest ----------------------------------
+
+// Generators of valid coins, for property tests. Denoms are native denoms, and
+// amounts are non-zero.
+
+#[cfg(any(test, feature = "proptest"))]
+fn arbitrary_denom() -> impl Strategy<Value = String> {
+    "[a-z][a-z0-9._-]{2,15}"
+}
+
+#[cfg(any(test, feature = "proptest"))]
+impl Arbitrary for Coin {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        (arbitrary_denom(), 1..=u128::MAX)
+            .prop_map(|(denom, amount)| Coin {
+                denom,
+                amount: Uint128::new(amount),
+            })
+            .boxed()
+    }
+}
+
+#[cfg(any(test, feature = "proptest"))]
+impl Arbitrary for Coins {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: Self::Parameters) -> Self::Strategy {
+        // Generating a map, instead of a list of coins, ensures there are no
+        // duplicate denoms.
+        collection::btree_map(arbitrary_denom(), 1..=u128::MAX, 0..8)
+            .prop_map(|map| {
+                Coins(
+                    map.into_iter()
+                        .map(|(denom, amount)| (denom, Uint128::new(amount)))
+                        .collect(),
+                )
+            })
+            .boxed()
+    }
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{
+            from_borsh_slice, from_json_slice, from_json_value, to_borsh_vec, to_json_value,
+            to_json_vec, Json,
+        },
+        proptest::prelude::*,
+        serde_json::json,
+    };
+
+    fn mock_coins() -> Coins {
+        Coins(
+            [
+                (String::from("uatom"), Uint128::new(123)),
+                (String::from("umars"), Uint128::new(456)),
+                (String::from("uosmo"), Uint128::new(789)),
+            ]
+            .into(),
+        )
+    }
+
+    /// Assert two `Coins` are equal. On mismatch, panic with their structured
+    /// diff, which is easier to read than the flat `Debug` output.
+    #[track_caller]
+    fn assert_coins_eq(a: &Coins, b: &Coins) {
+        let diff = a.diff(b);
+        if !diff.is_empty() {
+            panic!("coins are not equal:\n{diff}");
+        }
+    }
+
+    fn mock_coins_json() -> Json {
+        json!({
+            "uatom": "123",
+            "umars": "456",
+            "uosmo": "789",
+        })
+    }
+
+    proptest! {
+        /// Ensure coins survive a round trip through JSON and Borsh.
+        #[test]
+        fn coins_round_trip(coins in any::<Coins>()) {
+            let json = to_json_vec(&coins).unwrap();
+            prop_assert_eq!(&from_json_slice::<Coins>(json).unwrap(), &coins);
+
+            let borsh = to_borsh_vec(&coins).unwrap();
+            prop_assert_eq!(&from_borsh_slice::<Coins>(borsh).unwrap(), &coins);
+
+            // The compact string form round-trips as well.
+            prop_assert_eq!(Coins::from_str(&coins.to_string()).unwrap(), coins);
+        }
+    }
+
+    #[test]
+    fn serializing_coins() {
+        assert_eq!(to_json_value(&mock_coins()).unwrap(), mock_coins_json());
+    }
+
+    #[test]
+    fn deserializing_coins() {
+        // valid string
+        assert_eq!(
+            from_json_value::<Coins>(mock_coins_json()).unwrap(),
+            mock_coins()
+        );
+
+        // invalid json: contains zero amount
+        let illegal_json = json!({
+            "uatom": "123",
+            "umars": "0",
+        });
+        assert!(from_json_value::<Coins>(illegal_json).is_err());
+
+        // invalid json: contains duplicate
+        let illegal_json = json!([
+            {
                 "denom": "uatom",
                 "amount": "123",
             },
@@ -420,6 +1309,194 @@ mod tests {
         assert!(from_json_value::<Coins>(illegal_json).is_err());
     }
 
+    #[test]
+    fn deserializing_coins_is_canonical() {
+        let a = br#"{"uatom":"123","umars":"456","uosmo":"789"}"#;
+        let b = br#"{"uosmo":"789","uatom":"123","umars":"456"}"#;
+
+        let coins_a = from_json_slice::<Coins>(a).unwrap();
+        let coins_b = from_json_slice::<Coins>(b).unwrap();
+        assert_eq!(coins_a, coins_b);
+        assert_eq!(coins_a, mock_coins());
+
+        // Both re-serialize to the sorted form.
+        assert_eq!(to_json_vec(&coins_a).unwrap(), a);
+        assert_eq!(to_json_vec(&coins_b).unwrap(), a);
+        assert_eq!(
+            to_borsh_vec(&coins_a).unwrap(),
+            to_borsh_vec(&coins_b).unwrap()
+        );
+
+        // Same for the array form: the input order is free, but the coins are
+        // sorted once parsed.
+        let a = r#"[
+            {"denom":"uatom","amount":"123"},
+            {"denom":"umars","amount":"456"},
+            {"denom":"uosmo","amount":"789"}
+        ]"#;
+        let b = r#"[
+            {"denom":"uosmo","amount":"789"},
+            {"denom":"uatom","amount":"123"},
+            {"denom":"umars","amount":"456"}
+        ]"#;
+
+        let coins_a = Coins::from_json_str(a).unwrap();
+        let coins_b = Coins::from_json_str(b).unwrap();
+        assert_eq!(coins_a, coins_b);
+        assert_eq!(coins_a, mock_coins());
+        assert_eq!(
+            to_json_vec(&Vec::<Coin>::from(coins_a)).unwrap(),
+            to_json_vec(&Vec::<Coin>::from(coins_b)).unwrap()
+        );
+    }
+
+    // The encoding of `Coins` is consensus-relevant: it goes into contract
+    // messages, events, and storage. The following bytes are frozen; if this
+    // test breaks, the wire format has changed.
+    #[test]
+    fn coins_wire_format() {
+        // single coin
+        let coins = Coins::new_one("uatom", NonZero::new(123_u128));
+        let json = br#"{"uatom":"123"}"#;
+        assert_eq!(to_json_vec(&coins).unwrap(), json);
+        assert_eq!(from_json_slice::<Coins>(json).unwrap(), coins);
+
+        let borsh = [
+            1, 0, 0, 0, // number of coins
+            5, 0, 0, 0, b'u', b'a', b't', b'o', b'm', // denom
+            123, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, // amount
+        ];
+        assert_eq!(to_borsh_vec(&coins).unwrap(), borsh);
+        assert_eq!(from_borsh_slice::<Coins>(borsh).unwrap(), coins);
+
+        // the array form, as accepted by `from_json_str`
+        let array = r#"[{"denom":"uatom","amount":"123"}]"#;
+        assert_eq!(Coins::from_json_str(array).unwrap(), coins);
+        assert_eq!(
+            to_json_vec(&Vec::<Coin>::from(coins)).unwrap(),
+            array.as_bytes()
+        );
+
+        // empty coins
+        let coins = Coins::new_empty();
+        assert_eq!(to_json_vec(&coins).unwrap(), b"{}");
+        assert_eq!(from_json_slice::<Coins>(b"{}").unwrap(), coins);
+        assert_eq!(to_borsh_vec(&coins).unwrap(), [0, 0, 0, 0]);
+
+        // the empty array
+        assert_eq!(Coins::from_json_str("[]").unwrap(), Coins::new_empty());
+        assert_eq!(
+            to_json_vec(&Vec::<Coin>::from(coins.clone())).unwrap(),
+            b"[]"
+        );
+
+        // the string form of empty coins is `[]`
+        assert_eq!(coins.to_string(), Coins::EMPTY_COINS_STR);
+        assert_eq!(Coins::from_str("[]").unwrap(), Coins::new_empty());
+
+        // denoms with special characters are kept verbatim, and ordered by bytes
+        let coins = Coins::from_str("uosmo:1,ibc/27394FB0:2,factory/osmo1/ugrug:3").unwrap();
+        let json = br#"{"factory/osmo1/ugrug":"3","ibc/27394FB0":"2","uosmo":"1"}"#;
+        assert_eq!(to_json_vec(&coins).unwrap(), json);
+        assert_eq!(from_json_slice::<Coins>(json).unwrap(), coins);
+        assert_eq!(
+            coins.to_string(),
+            "factory/osmo1/ugrug:3,ibc/27394FB0:2,uosmo:1"
+        );
+
+        let array = concat!(
+            r#"[{"denom":"factory/osmo1/ugrug","amount":"3"},"#,
+            r#"{"denom":"ibc/27394FB0","amount":"2"},"#,
+            r#"{"denom":"uosmo","amount":"1"}]"#,
+        );
+        assert_eq!(Coins::from_json_str(array).unwrap(), coins);
+        assert_eq!(
+            to_json_vec(&Vec::<Coin>::from(coins)).unwrap(),
+            array.as_bytes()
+        );
+    }
+
+    #[test]
+    fn summing_coins() {
+        // duplicate denoms are summed
+        let coins = Coins::try_sum([
+            Coin::new("uosmo", NonZero::new(789_u128)),
+            Coin::new("uatom", NonZero::new(100_u128)),
+            Coin::new("umars", NonZero::new(456_u128)),
+            Coin::new("uatom", NonZero::new(23_u128)),
+        ])
+        .unwrap();
+        assert_eq!(coins, mock_coins());
+
+        // zero amount
+        let coins = vec![Coin {
+            denom: "uatom".to_string(),
+            amount: Uint128::ZERO,
+        }];
+        assert!(Coins::try_sum(coins).is_err());
+
+        // overflow
+        let coins = [
+            Coin::new("uatom", NonZero::new(Uint128::MAX)),
+            Coin::new("uatom", NonZero::new(1_u128)),
+        ];
+        assert!(matches!(
+            Coins::try_sum(coins),
+            Err(StdError::OverflowAdd { .. })
+        ));
+    }
+
+    #[test]
+    fn max_and_min_by_amount() {
+        let coins = mock_coins();
+        let max = coins.max_by_amount().unwrap();
+        assert_eq!(
+            (max.denom.as_str(), *max.amount),
+            ("uosmo", Uint128::new(789))
+        );
+        let min = coins.min_by_amount().unwrap();
+        assert_eq!(
+            (min.denom.as_str(), *min.amount),
+            ("uatom", Uint128::new(123))
+        );
+
+        // ties are broken by denom order
+        let coins = Coins::from_str("uosmo:5,uatom:5,umars:1").unwrap();
+        assert_eq!(coins.max_by_amount().unwrap().denom, "uatom");
+        let coins = Coins::from_str("uosmo:1,uatom:5,umars:1").unwrap();
+        assert_eq!(coins.min_by_amount().unwrap().denom, "umars");
+
+        // empty
+        assert!(Coins::new_empty().max_by_amount().is_none());
+        assert!(Coins::new_empty().min_by_amount().is_none());
+    }
+
+    #[test]
+    fn splitting_coins() {
+        let coins = Coins::new_one("uatom", NonZero::new(100_u128));
+        let (shares, remainder) = coins.split_shares(3).unwrap();
+        assert_eq!(shares, vec![
+            Coins::new_one("uatom", NonZero::new(33_u128));
+            3
+        ]);
+        assert_eq!(remainder, Coins::new_one("uatom", NonZero::new(1_u128)));
+
+        // the shares plus the remainder add up to the original coins
+        let total = Coins::try_sum(shares.into_iter().chain([remainder]).flatten()).unwrap();
+        assert_eq!(total, coins);
+
+        // a denom smaller than the number of shares goes to the remainder
+        let (shares, remainder) = mock_coins().split_shares(200).unwrap();
+        assert_eq!(shares[0], Coins::from_str("umars:2,uosmo:3").unwrap());
+        assert_eq!(
+            remainder,
+            Coins::from_str("uatom:123,umars:56,uosmo:189").unwrap()
+        );
+
+        // zero shares
+        assert!(coins.split_shares(0).is_err());
+    }
+
     #[test]
     fn coins_from_str() {
         // valid string. note: out of order is allowed
@@ -432,6 +1509,852 @@ mod tests {
 
         // invalid string: contains duplicate
         let s = "uatom:123,uatom:456";
-        assert!(Coins::from_str(s).is_err())
+        assert!(Coins::from_str(s).is_err());
+
+        // invalid string: stray trailing comma
+        let s = "uatom:123,";
+        assert!(matches!(
+            Coins::from_str(s),
+            Err(StdError::InvalidCoins { reason }) if reason.contains("trailing")
+        ));
+
+        // invalid string: lone or consecutive commas
+        for s in [",", ",,"] {
+            assert!(matches!(
+                Coins::from_str(s),
+                Err(StdError::InvalidCoins { reason }) if reason.contains("consecutive commas")
+            ));
+        }
+
+        // invalid string: missing amount
+        let s = "uatom:";
+        assert!(matches!(
+            Coins::from_str(s),
+            Err(StdError::InvalidCoins { reason }) if reason == "invalid amount ``"
+        ));
+    }
+
+    #[test]
+    fn empty_coins_round_trip() {
+        let empty = Coins::new_empty();
+        assert_eq!(Coins::from_str(&empty.to_string()).unwrap(), empty);
+
+        // an empty or whitespace-only string is also parsed as empty coins
+        assert_eq!(Coins::from_str("").unwrap(), empty);
+        assert_eq!(Coins::from_str("  ").unwrap(), empty);
+    }
+
+    #[test]
+    fn coins_from_json_str() {
+        // valid string. note: out of order is allowed
+        let s = r#"[
+            {"denom":"uosmo","amount":"789"},
+            {"denom":"uatom","amount":"123"},
+            {"denom":"umars","amount":"456"}
+        ]"#;
+        let compact = "uosmo:789,uatom:123,umars:456";
+        assert_eq!(
+            Coins::from_json_str(s).unwrap(),
+            Coins::from_str(compact).unwrap()
+        );
+
+        // empty array
+        assert_eq!(Coins::from_json_str("[]").unwrap(), Coins::new_empty());
+
+        // invalid string: contains zero amount
+        let s = r#"[{"denom":"uatom","amount":"0"}]"#;
+        assert!(Coins::from_json_str(s).is_err());
+
+        // invalid string: contains duplicate
+        let s = r#"[{"denom":"uatom","amount":"123"},{"denom":"uatom","amount":"456"}]"#;
+        assert!(Coins::from_json_str(s).is_err());
+
+        // invalid string: compact form
+        assert!(Coins::from_json_str(compact).is_err());
+    }
+
+    #[test]
+    fn fractions_of_supply() {
+        let supplies = Coins::try_from([
+            Coin::new("uatom", NonZero::new(400_u128)),
+            Coin::new("umars", NonZero::new(3_u128)),
+            Coin::new("uosmo", NonZero::new(1000_u128)),
+        ])
+        .unwrap();
+        let holdings = Coins::try_from([
+            Coin::new("uatom", NonZero::new(100_u128)),
+            Coin::new("umars", NonZero::new(1_u128)),
+            Coin::new("untrn", NonZero::new(50_u128)),
+        ])
+        .unwrap();
+
+        // untrn has no supply, and is skipped.
+        assert_eq!(
+            holdings.as_fraction_of(&supplies).unwrap(),
+            BTreeMap::from([
+                (String::from("uatom"), Udec128::new_percent(25_u128)),
+                (
+                    String::from("umars"),
+                    Udec128::from_str("0.333333333333333333").unwrap()
+                ),
+            ])
+        );
+    }
+
+    #[test]
+    fn rounding_coin_down() {
+        let coin = Coin::new("uatom", NonZero::new(123456_u128));
+
+        assert_eq!(
+            coin.round_down_to(3),
+            Coin::new("uatom", NonZero::new(123000_u128))
+        );
+        assert_eq!(coin.round_down_to(0), coin);
+
+        // more digits than the amount has
+        assert_eq!(coin.round_down_to(6).amount, Uint128::ZERO);
+        assert_eq!(coin.round_down_to(u8::MAX).amount, Uint128::ZERO);
+    }
+
+    #[test]
+    fn normalizing_coins() {
+        // Bypass the constructors, which reject zero amounts.
+        let mut coins = Coins(
+            [
+                (String::from("uatom"), Uint128::new(123)),
+                (String::from("umars"), Uint128::ZERO),
+            ]
+            .into(),
+        );
+        assert!(coins.has("uatom"));
+        assert!(!coins.has("umars"));
+        assert_eq!(coins.len(), 2);
+
+        coins.normalize();
+        assert!(!coins.has("umars"));
+        assert_eq!(coins, Coins::new_one("uatom", NonZero::new(123_u128)));
+
+        // JSON deserialization rejects zero amounts, but Borsh doesn't.
+        assert!(from_json_value::<Coins>(json!({ "uatom": "0" })).is_err());
+
+        let bytes = to_borsh_vec(&Coins([(String::from("uatom"), Uint128::ZERO)].into())).unwrap();
+        let mut coins: Coins = from_borsh_slice(bytes).unwrap();
+        assert!(!coins.has("uatom"));
+
+        coins.normalize();
+        assert!(coins.is_empty());
+    }
+
+    #[test]
+    fn getting_amount_checked() {
+        let mut coins = mock_coins();
+        coins.normalize();
+
+        assert_eq!(coins.amount_of_checked("uatom"), Some(Uint128::new(123)));
+        assert_eq!(coins.amount_of_checked("uusdc"), None);
+
+        // A zero record, as may be decoded from untrusted Borsh input, is
+        // present.
+        let bytes = to_borsh_vec(&Coins([(String::from("uatom"), Uint128::ZERO)].into())).unwrap();
+        let coins: Coins = from_borsh_slice(bytes).unwrap();
+        assert_eq!(coins.amount_of_checked("uatom"), Some(Uint128::ZERO));
+        assert_eq!(coins.amount_of("uatom"), Uint128::ZERO);
+    }
+
+    #[test]
+    fn coins_from_trusted_source() {
+        let coins = Coins::from_trusted([
+            (String::from("uosmo"), Uint128::new(789)),
+            (String::from("uatom"), Uint128::new(123)),
+            (String::from("umars"), Uint128::new(456)),
+        ]);
+        assert_eq!(coins, mock_coins());
+    }
+
+    // The invariants are only checked in debug builds. In release builds, this
+    // returns a `Coins` containing a zero amount, and this test doesn't run.
+    #[cfg(debug_assertions)]
+    #[test]
+    #[should_panic(expected = "trusted coins have zero amount of `uatom`")]
+    fn coins_from_trusted_source_with_zero_amount() {
+        Coins::from_trusted([(String::from("uatom"), Uint128::ZERO)]);
+    }
+
+    #[test]
+    fn total_value_in_base_denom() {
+        let prices = BTreeMap::from([
+            (String::from("uatom"), Uint128::new(10)),
+            (String::from("umars"), Uint128::new(2)),
+            (String::from("uosmo"), Uint128::new(1)),
+        ]);
+
+        // 123 * 10 + 456 * 2 + 789 * 1 = 2931
+        assert_eq!(mock_coins().total_in(&prices, "uusdc").unwrap(), Coin {
+            denom: String::from("uusdc"),
+            amount: Uint128::new(2931),
+        });
+
+        // The base denom doesn't need a price.
+        let mut coins = mock_coins();
+        coins.increase_amount("uusdc", Uint128::new(69)).unwrap();
+        assert_eq!(
+            coins.total_in(&prices, "uusdc").unwrap().amount,
+            Uint128::new(3000)
+        );
+
+        // The product is computed in `Uint256` without overflowing; only the
+        // final conversion back to `Uint128` fails.
+        let coins = Coins::new_one("uatom", NonZero::new(u128::MAX));
+        assert!(matches!(
+            coins.total_in(&prices, "uusdc"),
+            Err(StdError::OverflowConversion { .. })
+        ));
+    }
+
+    #[test]
+    fn total_value_with_missing_price() {
+        let prices = BTreeMap::from([(String::from("uatom"), Uint128::new(10))]);
+
+        assert!(matches!(
+            mock_coins().total_in(&prices, "uusdc"),
+            Err(StdError::PriceNotFound { denom }) if denom == "umars"
+        ));
+    }
+
+    #[test]
+    fn classifying_denoms() {
+        for (denom, kind) in [
+            ("ugrug", DenomKind::Native),
+            ("ibc/27394FB092D2ECCD56123C74F36E4C1F", DenomKind::Native),
+            ("gravity0x-usdc.e_1", DenomKind::Native),
+            ("factory/0x1234abcd/ufoo", DenomKind::Factory),
+            // empty
+            ("", DenomKind::Invalid),
+            // doesn't start with a letter
+            ("1grug", DenomKind::Invalid),
+            ("/ugrug", DenomKind::Invalid),
+            // contains empty parts
+            ("ibc//abcd", DenomKind::Invalid),
+            ("ugrug/", DenomKind::Invalid),
+            ("gamm:pool:1", DenomKind::Native),
+            // contains illegal characters
+            ("u grug", DenomKind::Invalid),
+            ("ugrug,123", DenomKind::Invalid),
+            // factory denom with missing or extra parts
+            ("factory/", DenomKind::Invalid),
+            ("factory/0x1234abcd", DenomKind::Invalid),
+            ("factory/0x1234abcd/", DenomKind::Invalid),
+            ("factory//ufoo", DenomKind::Invalid),
+            ("factory/0x1234abcd/ufoo/bar", DenomKind::Invalid),
+        ] {
+            assert_eq!(DenomKind::of(denom), kind, "denom: `{denom}`");
+        }
+
+        let coin = Coin::new("factory/0x1234abcd/ufoo", NonZero::new(1_u128));
+        assert_eq!(coin.denom_kind(), DenomKind::Factory);
+    }
+
+    #[test]
+    fn validating_denoms() {
+        for denom in [
+            "uatom",
+            "ibc/ABC123",
+            "factory/0x1234abcd/ufoo",
+            "gamm:pool:1",
+        ] {
+            assert!(validate_denom(denom).is_ok(), "denom: `{denom}`");
+        }
+
+        let too_long = "a".repeat(MAX_DENOM_LEN + 1);
+        for denom in ["ab", too_long.as_str(), "uatom,uosmo", "uatom:"] {
+            assert!(
+                matches!(validate_denom(denom), Err(StdError::InvalidDenom { .. })),
+                "denom: `{denom}`"
+            );
+        }
+
+        // invalid denoms are rejected on ingestion
+        let err = from_json_slice::<Coins>(br#"{"uatom,uosmo":"1"}"#).unwrap_err();
+        assert!(err.to_string().contains("invalid denom `uatom,uosmo`"));
+
+        assert!(matches!(
+            Coins::try_from(vec![Coin::new("u grug", NonZero::new(1_u128))]),
+            Err(StdError::InvalidDenom { denom, .. }) if denom == "u grug"
+        ));
+
+        assert!(matches!(
+            Coins::from_str("ab:1"),
+            Err(StdError::InvalidDenom { denom, .. }) if denom == "ab"
+        ));
+
+        // a denom with internal colons round-trips through the string form
+        let coins = Coins::from_str("gamm:pool:1:123,uatom:456").unwrap();
+        assert_eq!(coins.amount_of("gamm:pool:1"), Uint128::new(123));
+        assert_eq!(Coins::from_str(&coins.to_string()).unwrap(), coins);
+    }
+
+    #[test]
+    fn extracting_single_coin() {
+        let coins = Coins::new_one("uatom", NonZero::new(123_u128));
+        assert_eq!(coins.into_single().unwrap(), Coin {
+            denom: String::from("uatom"),
+            amount: Uint128::new(123),
+        });
+
+        assert!(matches!(
+            Coins::new_empty().into_single(),
+            Err(StdError::InvalidPayment {
+                expect: 1,
+                actual: 0
+            })
+        ));
+
+        assert!(matches!(
+            mock_coins().into_single(),
+            Err(StdError::InvalidPayment {
+                expect: 1,
+                actual: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn converting_coins_into_coin() {
+        let coin: Coin = Coins::new_one("uatom", NonZero::new(123_u128))
+            .try_into()
+            .unwrap();
+        assert_eq!(coin, Coin {
+            denom: String::from("uatom"),
+            amount: Uint128::new(123),
+        });
+
+        assert!(matches!(
+            Coin::try_from(mock_coins()),
+            Err(StdError::InvalidPayment {
+                expect: 1,
+                actual: 3
+            })
+        ));
+    }
+
+    #[test]
+    fn capping_coins() {
+        let caps = Coins(
+            [
+                // above the amount
+                (String::from("uatom"), Uint128::new(200)),
+                // below the amount
+                (String::from("umars"), Uint128::new(400)),
+                // not in the coins
+                (String::from("uusdc"), Uint128::new(1)),
+            ]
+            .into(),
+        );
+
+        // uosmo isn't capped, so it passes through.
+        assert_eq!(
+            mock_coins().cap_by(&caps),
+            Coins(
+                [
+                    (String::from("uatom"), Uint128::new(123)),
+                    (String::from("umars"), Uint128::new(400)),
+                    (String::from("uosmo"), Uint128::new(789)),
+                ]
+                .into(),
+            )
+        );
+    }
+
+    #[test]
+    fn spendable_coins() {
+        let locked = Coins(
+            [
+                // more than the balance
+                (String::from("uatom"), Uint128::new(200)),
+                // part of the balance
+                (String::from("umars"), Uint128::new(400)),
+                // not in the balance
+                (String::from("uusdc"), Uint128::new(1)),
+            ]
+            .into(),
+        );
+
+        // uatom is fully locked, so it's not spendable at all; uosmo isn't
+        // locked, so it's fully spendable.
+        assert_eq!(
+            mock_coins().spendable(&locked),
+            Coins(
+                [
+                    (String::from("umars"), Uint128::new(56)),
+                    (String::from("uosmo"), Uint128::new(789)),
+                ]
+                .into(),
+            )
+        );
+    }
+
+    #[test]
+    fn deserializing_too_many_coins() {
+        let entries = (0..Coins::MAX_LEN)
+            .map(|i| format!(r#""denom{i}":"1""#))
+            .collect::<Vec<_>>();
+
+        // exactly at the cap is fine
+        let json = format!("{{{}}}", entries.join(","));
+        let coins = from_json_slice::<Coins>(json.as_bytes()).unwrap();
+        assert_eq!(coins.len(), Coins::MAX_LEN);
+
+        // one more coin past the cap, followed by a malformed remainder. the
+        // error is about the cap, not the syntax, meaning decoding stopped
+        // before the rest of the input was read.
+        let json = format!(
+            r#"{{{},"denom_extra":"1","this is not valid json"#,
+            entries.join(",")
+        );
+        let err = from_json_slice::<Coins>(json.as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("too many coins! max: 1024"));
+    }
+
+    #[test]
+    fn adding_coins() {
+        // disjoint denoms
+        let a = Coins::from_str("uatom:1,umars:2").unwrap();
+        let b = Coins::from_str("uosmo:3").unwrap();
+        let expect = Coins::from_str("uatom:1,umars:2,uosmo:3").unwrap();
+        assert_eq!(a.checked_add(&b).unwrap(), expect);
+        assert_eq!(&a + &b, expect);
+
+        // overlapping denoms
+        let b = Coins::from_str("umars:5,uosmo:3").unwrap();
+        let expect = Coins::from_str("uatom:1,umars:7,uosmo:3").unwrap();
+        assert_eq!(a.checked_add(&b).unwrap(), expect);
+
+        let mut c = a.clone();
+        c += &b;
+        assert_eq!(c, expect);
+
+        // adding empty coins is a no-op
+        assert_eq!(a.checked_add(&Coins::new_empty()).unwrap(), a);
+
+        // overflow
+        let max = Coins([(String::from("uatom"), Uint128::MAX)].into());
+        assert!(matches!(
+            max.checked_add(&a),
+            Err(StdError::OverflowAdd { .. })
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "addition overflow")]
+    fn adding_coins_panics_on_overflow() {
+        let max = Coins([(String::from("uatom"), Uint128::MAX)].into());
+        let _ = &max + &Coins::from_str("uatom:1").unwrap();
+    }
+
+    #[test]
+    fn subtracting_coins() {
+        let a = Coins::from_str("uatom:1,umars:7,uosmo:3").unwrap();
+
+        // overlapping denoms; a denom reduced to zero is purged
+        let b = Coins::from_str("uatom:1,umars:5").unwrap();
+        let expect = Coins::from_str("umars:2,uosmo:3").unwrap();
+        assert_eq!(a.checked_sub(&b).unwrap(), expect);
+        assert_eq!(&a - &b, expect);
+        assert!(!(&a - &b).has("uatom"));
+
+        let mut c = a.clone();
+        c -= &b;
+        assert_eq!(c, expect);
+
+        // subtracting everything leaves nothing
+        assert!(a.checked_sub(&a).unwrap().is_empty());
+
+        // disjoint denoms
+        let b = Coins::from_str("uusdc:1").unwrap();
+        assert!(matches!(
+            a.checked_sub(&b),
+            Err(StdError::DenomNotFound { denom }) if denom == "uusdc"
+        ));
+
+        // underflow
+        let b = Coins::from_str("umars:8").unwrap();
+        assert!(matches!(
+            a.checked_sub(&b),
+            Err(StdError::OverflowSub { .. })
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "subtraction overflow")]
+    fn subtracting_coins_panics_on_underflow() {
+        let mut a = Coins::from_str("uatom:1").unwrap();
+        a -= &Coins::from_str("uatom:2").unwrap();
+    }
+
+    #[test]
+    fn validating_max() {
+        // exactly at the cap, below the cap, and uncapped
+        let caps = Coins(
+            [
+                (String::from("uatom"), Uint128::new(123)),
+                (String::from("umars"), Uint128::new(1000)),
+            ]
+            .into(),
+        );
+        assert!(mock_coins().validate_max(&caps).is_ok());
+
+        // exceeding the cap
+        let caps = Coins([(String::from("umars"), Uint128::new(455))].into());
+        assert!(matches!(
+            mock_coins().validate_max(&caps),
+            Err(StdError::ExceedsCap { denom, amount, cap })
+                if denom == "umars" && amount == Uint128::new(456) && cap == Uint128::new(455)
+        ));
+    }
+
+    #[test]
+    fn making_change() {
+        // exact payment
+        let change = Coins::make_change(&mock_coins(), &mock_coins()).unwrap();
+        assert!(change.is_empty());
+
+        // overpayment
+        let price = Coins(
+            [
+                (String::from("uatom"), Uint128::new(100)),
+                (String::from("umars"), Uint128::new(456)),
+            ]
+            .into(),
+        );
+        let change = Coins::make_change(&mock_coins(), &price).unwrap();
+        assert_eq!(
+            change,
+            Coins(
+                [
+                    (String::from("uatom"), Uint128::new(23)),
+                    (String::from("uosmo"), Uint128::new(789)),
+                ]
+                .into(),
+            )
+        );
+
+        // underpayment
+        let price = Coins([(String::from("umars"), Uint128::new(457))].into());
+        assert!(matches!(
+            Coins::make_change(&mock_coins(), &price),
+            Err(StdError::InsufficientPayment { denom, paid, price })
+                if denom == "umars" && paid == Uint128::new(456) && price == Uint128::new(457)
+        ));
+
+        // paying nothing of a denom in the price
+        let price = Coins([(String::from("uusdc"), Uint128::new(1))].into());
+        assert!(matches!(
+            Coins::make_change(&mock_coins(), &price),
+            Err(StdError::InsufficientPayment { denom, .. }) if denom == "uusdc"
+        ));
+    }
+
+    #[test]
+    fn removing_dust() {
+        let thresholds = Coins(
+            [
+                // below the threshold
+                (String::from("uatom"), Uint128::new(124)),
+                // exactly the threshold
+                (String::from("umars"), Uint128::new(456)),
+                // not in the coins
+                (String::from("uusdc"), Uint128::new(1)),
+            ]
+            .into(),
+        );
+
+        // uosmo has no threshold, so it's kept.
+        let mut coins = mock_coins();
+        coins.remove_dust(&thresholds);
+        assert_eq!(
+            coins,
+            Coins(
+                [
+                    (String::from("umars"), Uint128::new(456)),
+                    (String::from("uosmo"), Uint128::new(789)),
+                ]
+                .into(),
+            )
+        );
+    }
+
+    #[test]
+    fn formatting_coins_as_table() {
+        let mut coins = mock_coins();
+        coins
+            .increase_amount(
+                "ibc/27394FB092D2ECCD56123C74F36E4C1F",
+                Uint128::new(1_000_000),
+            )
+            .unwrap();
+
+        assert_eq!(
+            coins.to_table(),
+            [
+                "denom                                  amount",
+                "ibc/27394FB092D2ECCD56123C74F36E4C1F  1000000",
+                "uatom                                     123",
+                "umars                                     456",
+                "uosmo                                     789",
+            ]
+            .join("\n")
+        );
+
+        // An empty table still has the header.
+        assert_eq!(Coins::new_empty().to_table(), "denom  amount");
+    }
+
+    #[test]
+    fn diffing_coins() {
+        let other = Coins(
+            [
+                (String::from("uatom"), Uint128::new(123)),
+                (String::from("umars"), Uint128::new(400)),
+                (String::from("uusdc"), Uint128::new(1)),
+            ]
+            .into(),
+        );
+
+        let diff = mock_coins().diff(&other);
+        assert_eq!(
+            diff.only_in_left,
+            Coins::new_one("uosmo", NonZero::new(789_u128))
+        );
+        assert_eq!(
+            diff.only_in_right,
+            Coins::new_one("uusdc", NonZero::new(1_u128))
+        );
+        assert_eq!(
+            diff.differing,
+            BTreeMap::from([(
+                String::from("umars"),
+                (Uint128::new(456), Uint128::new(400))
+            )])
+        );
+        assert_eq!(
+            diff.to_string(),
+            "only in left: uosmo:789\nonly in right: uusdc:1\ndiffering:\n  umars: 456 != 400"
+        );
+
+        assert!(mock_coins().diff(&mock_coins()).is_empty());
+        assert_coins_eq(&mock_coins(), &mock_coins());
+    }
+
+    #[test]
+    fn symmetric_difference_of_coins() {
+        let other = Coins(
+            [
+                (String::from("uatom"), Uint128::new(123)),
+                (String::from("umars"), Uint128::new(500)),
+                (String::from("uosmo"), Uint128::new(700)),
+                (String::from("uusdc"), Uint128::new(1)),
+            ]
+            .into(),
+        );
+
+        let (in_self, in_other) = mock_coins().symmetric_difference(&other);
+        assert_eq!(in_self, Coins::new_one("uosmo", NonZero::new(89_u128)));
+        assert_eq!(
+            in_other,
+            Coins::try_from([
+                Coin::new("umars", NonZero::new(44_u128)),
+                Coin::new("uusdc", NonZero::new(1_u128)),
+            ])
+            .unwrap()
+        );
+
+        // Applying the difference reconciles the two.
+        let mut left = mock_coins();
+        let mut right = other;
+        for coin in in_other {
+            left.increase_amount(&coin.denom, coin.amount).unwrap();
+        }
+        for coin in in_self {
+            right.increase_amount(&coin.denom, coin.amount).unwrap();
+        }
+        assert_coins_eq(&left, &right);
+
+        let (in_self, in_other) = mock_coins().symmetric_difference(&mock_coins());
+        assert!(in_self.is_empty() && in_other.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "coins are not equal:\nonly in left: uosmo:789\nonly in right: []")]
+    fn asserting_coins_eq_prints_diff() {
+        let mut other = mock_coins();
+        other.0.remove("uosmo");
+
+        assert_coins_eq(&mock_coins(), &other);
+    }
+
+    #[test]
+    fn subtracting_coins_clamped() {
+        let other = Coins(
+            [
+                // less than available
+                (String::from("uatom"), Uint128::new(100)),
+                // exactly available
+                (String::from("umars"), Uint128::new(456)),
+                // more than available
+                (String::from("uosmo"), Uint128::new(1000)),
+                // not available at all
+                (String::from("uusdc"), Uint128::new(50)),
+            ]
+            .into(),
+        );
+
+        let (result, shortfall) = mock_coins().sub_clamped(&other);
+        assert_eq!(
+            result,
+            Coins([(String::from("uatom"), Uint128::new(23))].into())
+        );
+        assert_eq!(
+            shortfall,
+            Coins(
+                [
+                    (String::from("uosmo"), Uint128::new(211)),
+                    (String::from("uusdc"), Uint128::new(50)),
+                ]
+                .into()
+            )
+        );
+
+        // Subtracting nothing has no shortfall.
+        let (result, shortfall) = mock_coins().sub_clamped(&Coins::new_empty());
+        assert_eq!(result, mock_coins());
+        assert!(shortfall.is_empty());
+    }
+
+    #[test]
+    fn extending_coins() {
+        let mut coins = mock_coins();
+        coins.extend([
+            Coin::new("uatom", NonZero::new(7_u128)),
+            Coin::new("uusdc", NonZero::new(100_u128)),
+            Coin::new("uatom", NonZero::new(10_u128)),
+            Coin {
+                denom: String::from("umars"),
+                amount: Uint128::ZERO,
+            },
+        ]);
+
+        assert_eq!(
+            coins,
+            Coins(
+                [
+                    (String::from("uatom"), Uint128::new(140)),
+                    (String::from("umars"), Uint128::new(456)),
+                    (String::from("uosmo"), Uint128::new(789)),
+                    (String::from("uusdc"), Uint128::new(100)),
+                ]
+                .into()
+            )
+        );
+    }
+
+    #[test]
+    fn increasing_amount() {
+        let mut coins = mock_coins();
+
+        // Increasing by zero doesn't create a zero-amount record.
+        coins.increase_amount("uusdc", Uint128::ZERO).unwrap();
+        assert!(!coins.has("uusdc"));
+
+        coins.increase_amount("uusdc", Uint128::new(1)).unwrap();
+        coins.increase_amount("uatom", Uint128::new(77)).unwrap();
+        assert_eq!(coins.amount_of("uusdc"), Uint128::new(1));
+        assert_eq!(coins.amount_of("uatom"), Uint128::new(200));
+    }
+
+    #[test]
+    fn increasing_amount_overflow() {
+        let mut coins = Coins::new_one("uatom", NonZero::new(Uint128::MAX - Uint128::new(1)));
+
+        // Exactly reaching the max is fine.
+        coins.increase_amount("uatom", Uint128::new(1)).unwrap();
+        assert_eq!(coins.amount_of("uatom"), Uint128::MAX);
+
+        assert!(matches!(
+            coins.increase_amount("uatom", Uint128::new(1)),
+            Err(StdError::OverflowAdd { .. })
+        ));
+
+        // The amount is left unchanged on error.
+        assert_eq!(coins.amount_of("uatom"), Uint128::MAX);
+    }
+
+    #[test]
+    fn adding_coins_in_place() {
+        let mut coins = mock_coins();
+        coins
+            .checked_add_assign(&Coins(
+                [
+                    (String::from("uatom"), Uint128::new(1)),
+                    (String::from("uusdc"), Uint128::new(2)),
+                ]
+                .into(),
+            ))
+            .unwrap();
+        assert_eq!(
+            coins,
+            Coins(
+                [
+                    (String::from("uatom"), Uint128::new(124)),
+                    (String::from("umars"), Uint128::new(456)),
+                    (String::from("uosmo"), Uint128::new(789)),
+                    (String::from("uusdc"), Uint128::new(2)),
+                ]
+                .into(),
+            )
+        );
+
+        // uatom could be added, but umars overflows, so nothing is added.
+        let before = coins.clone();
+        let other = Coins(
+            [
+                (String::from("uatom"), Uint128::new(1)),
+                (String::from("umars"), Uint128::MAX),
+            ]
+            .into(),
+        );
+        assert!(matches!(
+            coins.checked_add_assign(&other),
+            Err(StdError::OverflowAdd { .. })
+        ));
+        assert_eq!(coins, before);
+    }
+
+    #[test]
+    #[should_panic(expected = "failed to extend coins")]
+    fn extending_coins_overflow_panics() {
+        let mut coins = Coins::new_one("uatom", NonZero::new(u128::MAX));
+        coins.extend([Coin::new("uatom", NonZero::new(1_u128))]);
+    }
+
+    #[test]
+    fn ordered_coins_display_in_input_order() {
+        let ordered = OrderedCoins::from_str("uosmo:1,uatom:2,umars:3").unwrap();
+        assert_eq!(ordered.to_string(), "uosmo:1,uatom:2,umars:3");
+
+        // The underlying coins are still sorted.
+        assert_eq!(ordered.as_coins().to_string(), "uatom:2,umars:3,uosmo:1");
+
+        // Equality ignores the input order.
+        let other = OrderedCoins::from_str("umars:3,uosmo:1,uatom:2").unwrap();
+        assert_eq!(ordered, other);
+        assert_ne!(ordered.to_string(), other.to_string());
+        assert_eq!(ordered, OrderedCoins::from(ordered.clone().into_coins()));
+
+        // Invalid input is rejected the same way as for `Coins`.
+        assert!(OrderedCoins::from_str("uosmo:1,uosmo:2").is_err());
+        assert_eq!(
+            OrderedCoins::from_str("[]").unwrap().to_string(),
+            Coins::EMPTY_COINS_STR
+        );
     }
 }
@@ -3,7 +3,7 @@
 #![cfg_attr(rustfmt, rustfmt::skip)]
 
 use {
-    crate::{Addr, Api, BlockInfo, Coins, QuerierWrapper, Storage},
+    crate::{Addr, Api, BlockInfo, Coins, QuerierWrapper, Storage, TxOutcome},
     borsh::{BorshDeserialize, BorshSerialize},
 };
 
@@ -11,12 +11,13 @@ use {
 /// host constructs this, serialize to bytes, and pass it to the Wasm module.
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub struct Context {
-    pub chain_id: String,
-    pub block:    BlockInfo,
-    pub contract: Addr,
-    pub sender:   Option<Addr>,
-    pub funds:    Option<Coins>,
-    pub simulate: Option<bool>,
+    pub chain_id:   String,
+    pub block:      BlockInfo,
+    pub contract:   Addr,
+    pub sender:     Option<Addr>,
+    pub funds:      Option<Coins>,
+    pub simulate:   Option<bool>,
+    pub tx_outcome: Option<TxOutcome>,
 }
 
 /// A context that contians an immutable store. The contract is allowed to read
@@ -68,13 +69,18 @@ pub struct SudoCtx<'a> {
 ///
 /// The typical use of the `simulate` parameter is to skip certain authentication
 /// steps (e.g. verifying a cryptographic signature) if it's in simulation mode.
+///
+/// In `after_tx`, the `tx_outcome` parameter summarizes the outcome of the
+/// transaction's messages, so that the account can react to it. It's `None` in
+/// `before_tx`, as the messages haven't been executed yet.
 #[rustfmt::skip]
 pub struct AuthCtx<'a> {
-    pub storage:  &'a mut dyn Storage,
-    pub api:      &'a dyn Api,
-    pub querier:  QuerierWrapper<'a>,
-    pub chain_id: String,
-    pub block:    BlockInfo,
-    pub contract: Addr,
-    pub simulate: bool,
+    pub storage:    &'a mut dyn Storage,
+    pub api:        &'a dyn Api,
+    pub querier:    QuerierWrapper<'a>,
+    pub chain_id:   String,
+    pub block:      BlockInfo,
+    pub contract:   Addr,
+    pub simulate:   bool,
+    pub tx_outcome: Option<TxOutcome>,
 }
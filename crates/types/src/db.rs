@@ -45,7 +45,12 @@ impl<V> Op<V> {
 }
 
 /// Describing iteration order.
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+///
+/// Used both by the storage iterator host functions, where it's passed over
+/// FFI as an `i32`, and by paginated queries, where it's serialized as a
+/// snake_case string (`"ascending"` or `"descending"`).
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
 pub enum Order {
     Ascending = 1,
     Descending = 2,
@@ -1,6 +1,8 @@
 use {
+    crate::{Addr, Uint128},
     data_encoding::BASE64,
     hex::FromHexError,
+    serde::{Deserialize, Serialize},
     std::{any::type_name, array::TryFromSliceError, convert::Infallible},
     thiserror::Error,
 };
@@ -24,6 +26,16 @@ pub enum StdError {
     #[error("generic error: {0}")]
     Generic(String),
 
+    /// A contract queried another contract, which failed. `msg` is the error
+    /// as reported by the host.
+    #[error("query to contract `{contract}` failed: {msg}")]
+    QueryFailed { contract: Addr, msg: String },
+
+    /// A contract attempted to read another contract's storage with a
+    /// `WasmRaw` query, which the latter hasn't opted into.
+    #[error("contract `{contract}` does not allow its storage to be queried raw")]
+    RawQueryNotAllowed { contract: Addr },
+
     // TODO: add more details to this
     #[error("signature verification failed")]
     VerificationFailed,
@@ -38,12 +50,32 @@ pub enum StdError {
     #[error("invalid coins: {reason}")]
     InvalidCoins { reason: String },
 
+    #[error("invalid denom `{denom}`: {reason}")]
+    InvalidDenom { denom: String, reason: String },
+
     #[error("invalid payment: expecting {expect} coins, found {actual}")]
     InvalidPayment { expect: usize, actual: usize },
 
+    #[error("insufficient payment for denom `{denom}`: paid {paid}, price {price}")]
+    InsufficientPayment {
+        denom: String,
+        paid: Uint128,
+        price: Uint128,
+    },
+
+    #[error("amount of denom `{denom}` exceeds cap: amount {amount}, cap {cap}")]
+    ExceedsCap {
+        denom: String,
+        amount: Uint128,
+        cap: Uint128,
+    },
+
     #[error("cannot find denom `{denom}` in coins")]
     DenomNotFound { denom: String },
 
+    #[error("no price found for denom `{denom}`")]
+    PriceNotFound { denom: String },
+
     #[error("data not found! type: {ty}, storage key: {key}")]
     DataNotFound { ty: &'static str, key: String },
 
@@ -117,6 +149,12 @@ pub enum StdError {
     #[error("logarithm of zero")]
     ZeroLog,
 
+    #[error("binary data too long! length: {length}, max: {max}")]
+    BinaryTooLong { length: usize, max: usize },
+
+    #[error("failed to decode {encoding} string")]
+    Decode { encoding: &'static str },
+
     #[error("failed to serialize into json! type: {ty}, reason: {reason}")]
     Serialize { ty: &'static str, reason: String },
 
@@ -133,12 +171,27 @@ impl StdError {
         }
     }
 
+    pub fn binary_too_long(length: usize, max: usize) -> Self {
+        Self::BinaryTooLong { length, max }
+    }
+
+    pub fn decode(encoding: &'static str) -> Self {
+        Self::Decode { encoding }
+    }
+
     pub fn invalid_coins(reason: impl Into<String>) -> Self {
         Self::InvalidCoins {
             reason: reason.into(),
         }
     }
 
+    pub fn invalid_denom(denom: impl Into<String>, reason: impl Into<String>) -> Self {
+        Self::InvalidDenom {
+            denom: denom.into(),
+            reason: reason.into(),
+        }
+    }
+
     pub fn invalid_payment(expect: usize, actual: usize) -> Self {
         Self::InvalidPayment { expect, actual }
     }
@@ -236,14 +289,20 @@ impl StdError {
         Self::NegativeSqrt { a: a.to_string() }
     }
 
-    pub fn serialize<T>(reason: impl ToString) -> Self {
+    pub fn serialize<T>(reason: impl ToString) -> Self
+    where
+        T: ?Sized,
+    {
         Self::Serialize {
             ty: type_name::<T>(),
             reason: reason.to_string(),
         }
     }
 
-    pub fn deserialize<T>(reason: impl ToString) -> Self {
+    pub fn deserialize<T>(reason: impl ToString) -> Self
+    where
+        T: ?Sized,
+    {
         Self::Deserialize {
             ty: type_name::<T>(),
             reason: reason.to_string(),
@@ -256,3 +315,37 @@ impl StdError {
 }
 
 pub type StdResult<T> = core::result::Result<T, StdError>;
+
+/// An error from a query that a contract makes to the chain, in a form that
+/// can be passed over the FFI boundary.
+///
+/// The errors that a contract may want to handle are kept structured; the rest
+/// are passed as strings.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryError {
+    QueryFailed { contract: Addr, msg: String },
+    RawQueryNotAllowed { contract: Addr },
+    Generic(String),
+}
+
+impl From<StdError> for QueryError {
+    fn from(err: StdError) -> Self {
+        match err {
+            StdError::QueryFailed { contract, msg } => Self::QueryFailed { contract, msg },
+            StdError::RawQueryNotAllowed { contract } => Self::RawQueryNotAllowed { contract },
+            StdError::Generic(msg) => Self::Generic(msg),
+            err => Self::Generic(err.to_string()),
+        }
+    }
+}
+
+impl From<QueryError> for StdError {
+    fn from(err: QueryError) -> Self {
+        match err {
+            QueryError::QueryFailed { contract, msg } => Self::QueryFailed { contract, msg },
+            QueryError::RawQueryNotAllowed { contract } => Self::RawQueryNotAllowed { contract },
+            QueryError::Generic(msg) => Self::Generic(msg),
+        }
+    }
+}
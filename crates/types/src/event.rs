@@ -1,6 +1,12 @@
-use serde::{Deserialize, Serialize};
+use {
+    crate::{Addr, Hash, Json},
+    borsh::{BorshDeserialize, BorshSerialize},
+    serde::{Deserialize, Serialize},
+};
 
-#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+#[derive(
+    Serialize, Deserialize, BorshSerialize, BorshDeserialize, Default, Debug, Clone, PartialEq, Eq,
+)]
 pub struct Event {
     pub r#type: String,
     pub attributes: Vec<Attribute>,
@@ -23,9 +29,52 @@ impl Event {
         self.attributes.extend(attrs);
         self
     }
+
+    /// The event emitted when the chain config is updated.
+    pub fn configure(sender: &Addr) -> Self {
+        Self::new("configure").add_attribute("sender", sender)
+    }
+
+    /// The event emitted when a code is uploaded.
+    ///
+    /// Includes the size of the code in bytes, so that indexers can track the
+    /// growth of code storage.
+    pub fn upload(code_hash: &Hash, code_size: usize) -> Self {
+        Self::new("upload")
+            .add_attribute("code_hash", code_hash)
+            .add_attribute("code_size", code_size)
+    }
+
+    /// The event emitted when a code is removed.
+    pub fn remove_code(code_hash: &Hash) -> Self {
+        Self::new("remove_code").add_attribute("code_hash", code_hash)
+    }
+
+    /// The event emitted when a transaction carrying a memo is processed.
+    pub fn memo(memo: &str) -> Self {
+        Self::new("memo").add_attribute("memo", memo)
+    }
+
+    /// The event emitted when a submessage fails, but the contract that sent
+    /// it has requested a reply on error, so the failure doesn't abort the
+    /// parent message.
+    ///
+    /// Records the failed message and the error, which would otherwise only be
+    /// seen by the contract's `reply` entry point, and the gas the message
+    /// consumed before failing, which is charged even though its state changes
+    /// are discarded.
+    pub fn submessage_failed(sender: &Addr, msg: &Json, error: &str, gas_used: u64) -> Self {
+        Self::new("submessage_failed")
+            .add_attribute("sender", sender)
+            .add_attribute("msg", msg)
+            .add_attribute("error", error)
+            .add_attribute("gas_used", gas_used)
+    }
 }
 
-#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq, Eq)]
+#[derive(
+    Serialize, Deserialize, BorshSerialize, BorshDeserialize, Default, Debug, Clone, PartialEq, Eq,
+)]
 pub struct Attribute {
     pub key: String,
     pub value: String,
@@ -39,3 +88,40 @@ impl Attribute {
         }
     }
 }
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn building_events() {
+        let event = Event::new("transfer")
+            .add_attribute("from", "alice")
+            .add_attribute("to", "bob")
+            .add_attributes([
+                Attribute::new("amount", 123),
+                Attribute::new("denom", "ugrug"),
+            ]);
+
+        assert_eq!(event, Event {
+            r#type: "transfer".to_string(),
+            attributes: vec![
+                Attribute::new("from", "alice"),
+                Attribute::new("to", "bob"),
+                Attribute::new("amount", "123"),
+                Attribute::new("denom", "ugrug"),
+            ],
+        });
+
+        let code_hash = Hash::from_slice([1; Hash::LENGTH]);
+        assert_eq!(Event::upload(&code_hash, 420), Event {
+            r#type: "upload".to_string(),
+            attributes: vec![
+                Attribute::new("code_hash", code_hash.to_string()),
+                Attribute::new("code_size", "420"),
+            ],
+        });
+    }
+}
@@ -11,8 +11,9 @@
 
 use {
     crate::{
-        from_json_value, to_json_value, AccountResponse, Addr, Batch, Binary, Coins, Hash,
-        InfoResponse, Op, Order, QueryRequest, QueryResponse, Record, StdResult, Uint128,
+        from_json_value, to_json_value, AccountResponse, Addr, Batch, Binary, BlockInfo, Coins,
+        Hash, InfoResponse, Op, Order, QueryRequest, QueryResponse, Record, StdResult,
+        SuppliesResponse, TxResult, Uint128,
     },
     dyn_clone::DynClone,
     serde::{de::DeserializeOwned, ser::Serialize},
@@ -178,6 +179,18 @@ pub trait Api {
     /// contracts.
     fn debug(&self, addr: &Addr, msg: &str);
 
+    /// Return the amount of gas remaining, i.e. the gas limit minus the gas
+    /// used so far, including that used by the current call. If there isn't a
+    /// gas limit (e.g. when simulating a transaction), return `u64::MAX` minus
+    /// the gas used.
+    ///
+    /// Meant for reading the gas meter mid-execution when optimizing gas usage.
+    /// Only available when simulating a transaction, and only in debug builds
+    /// of the contract; otherwise this errors, so that production contracts
+    /// can't make their logic depend on the gas meter. Incurs a small fixed gas
+    /// cost, which is charged even if the read is refused.
+    fn gas_remaining(&self) -> StdResult<u64>;
+
     /// Verify an Secp256r1 signature with the given hashed message and public
     /// key.
     ///
@@ -249,10 +262,31 @@ pub trait Api {
 
     /// Perform the BLAKE3 hash.
     fn blake3(&self, data: &[u8]) -> [u8; 32];
+
+    /// Encode bytes into a base64 string (standard alphabet, with padding).
+    fn encode_base64(&self, data: &[u8]) -> String;
+
+    /// Decode a base64 string (standard alphabet, with padding) into bytes.
+    fn decode_base64(&self, data: &str) -> StdResult<Vec<u8>>;
+
+    /// Encode bytes into a lowercase hex string.
+    fn encode_hex(&self, data: &[u8]) -> String;
+
+    /// Decode a hex string into bytes. Both lowercase and uppercase letters are
+    /// accepted.
+    fn decode_hex(&self, data: &str) -> StdResult<Vec<u8>>;
+
+    /// Re-encode JSON bytes in the canonical form, with object keys sorted and
+    /// no insignificant whitespace. See [`to_json_canonical`](crate::to_json_canonical).
+    fn to_json_canonical(&self, json: &[u8]) -> StdResult<Vec<u8>>;
 }
 
 // ---------------------------------- querier ----------------------------------
 
+/// The page size used by `QuerierWrapper` methods that enumerate all items by
+/// making multiple paginated queries.
+const QUERY_ALL_PAGE_LIMIT: u32 = 30;
+
 pub trait Querier {
     /// Make a query. This is the only method that the context needs to manually
     /// implement. The other methods will be implemented automatically.
@@ -283,23 +317,43 @@ impl<'a> QuerierWrapper<'a> {
             .map(|res| res.as_info())
     }
 
+    /// Return the ID of the chain, e.g. for a query entry point to include in
+    /// data to be signed.
+    pub fn query_chain_id(&self) -> StdResult<String> {
+        self.query_info().map(|info| info.chain_id)
+    }
+
+    pub fn query_block_info(&self) -> StdResult<BlockInfo> {
+        self.inner
+            .query_chain(QueryRequest::BlockInfo {})
+            .map(|res| res.as_block_info())
+    }
+
     pub fn query_balance(&self, address: Addr, denom: String) -> StdResult<Uint128> {
         self.inner
             .query_chain(QueryRequest::Balance { address, denom })
             .map(|res| res.as_balance().amount)
     }
 
+    pub fn query_balance_exact(&self, address: Addr, denom: String) -> StdResult<Option<Uint128>> {
+        self.inner
+            .query_chain(QueryRequest::BalanceExact { address, denom })
+            .map(|res| res.as_balance_exact().map(|coin| coin.amount))
+    }
+
     pub fn query_balances(
         &self,
         address: Addr,
         start_after: Option<String>,
         limit: Option<u32>,
+        order: Option<Order>,
     ) -> StdResult<Coins> {
         self.inner
             .query_chain(QueryRequest::Balances {
                 address,
                 start_after,
                 limit,
+                order,
             })
             .map(|res| res.as_balances())
     }
@@ -314,12 +368,34 @@ impl<'a> QuerierWrapper<'a> {
         &self,
         start_after: Option<String>,
         limit: Option<u32>,
-    ) -> StdResult<Coins> {
+        order: Option<Order>,
+    ) -> StdResult<SuppliesResponse> {
         self.inner
-            .query_chain(QueryRequest::Supplies { start_after, limit })
+            .query_chain(QueryRequest::Supplies {
+                start_after,
+                limit,
+                order,
+            })
             .map(|res| res.as_supplies())
     }
 
+    /// Enumerate the total supplies of all tokens, by paginating through
+    /// `QueryRequest::Supplies` until all denoms have been collected.
+    pub fn query_all_supplies(&self) -> StdResult<Coins> {
+        let mut supplies = Coins::new_empty();
+        let mut start_after = None;
+
+        loop {
+            let page = self.query_supplies(start_after, Some(QUERY_ALL_PAGE_LIMIT), None)?;
+            supplies.extend(page.supplies);
+
+            let Some(next_key) = page.next_key else {
+                return Ok(supplies);
+            };
+            start_after = Some(next_key);
+        }
+    }
+
     pub fn query_code(&self, hash: Hash) -> StdResult<Binary> {
         self.inner
             .query_chain(QueryRequest::Code { hash })
@@ -330,9 +406,14 @@ impl<'a> QuerierWrapper<'a> {
         &self,
         start_after: Option<Hash>,
         limit: Option<u32>,
+        order: Option<Order>,
     ) -> StdResult<Vec<Hash>> {
         self.inner
-            .query_chain(QueryRequest::Codes { start_after, limit })
+            .query_chain(QueryRequest::Codes {
+                start_after,
+                limit,
+                order,
+            })
             .map(|res| res.as_codes())
     }
 
@@ -342,13 +423,28 @@ impl<'a> QuerierWrapper<'a> {
             .map(|res| res.as_account())
     }
 
+    /// Return whether a contract exists at the given address.
+    ///
+    /// In Grug, all accounts are contracts, so this is `false` only if nothing
+    /// has ever been instantiated at the address.
+    pub fn is_contract(&self, address: Addr) -> StdResult<bool> {
+        self.inner
+            .query_chain(QueryRequest::IsContract { address })
+            .map(|res| res.as_is_contract())
+    }
+
     pub fn query_accounts(
         &self,
         start_after: Option<Addr>,
         limit: Option<u32>,
+        order: Option<Order>,
     ) -> StdResult<Vec<AccountResponse>> {
         self.inner
-            .query_chain(QueryRequest::Accounts { start_after, limit })
+            .query_chain(QueryRequest::Accounts {
+                start_after,
+                limit,
+                order,
+            })
             .map(|res| res.as_accounts())
     }
 
@@ -358,6 +454,14 @@ impl<'a> QuerierWrapper<'a> {
             .map(|res| res.as_wasm_raw().value)
     }
 
+    /// Return the result of a transaction delivered in a previous block, by
+    /// the hash of the transaction.
+    pub fn query_tx_result(&self, hash: Hash) -> StdResult<TxResult> {
+        self.inner
+            .query_chain(QueryRequest::TxResult { hash })
+            .map(|res| res.as_tx_result())
+    }
+
     pub fn query_wasm_smart<M: Serialize, R: DeserializeOwned>(
         &self,
         contract: Addr,
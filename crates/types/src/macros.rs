@@ -97,13 +97,14 @@ macro_rules! make_auth_ctx {
             debug_assert!($ctx.funds.is_none());
 
             AuthCtx {
-                storage:  $storage,
-                api:      $api,
-                querier:  QuerierWrapper::new($querier),
-                chain_id: $ctx.chain_id,
-                block:    $ctx.block,
-                contract: $ctx.contract,
-                simulate: $ctx.simulate.unwrap(),
+                storage:    $storage,
+                api:        $api,
+                querier:    QuerierWrapper::new($querier),
+                chain_id:   $ctx.chain_id,
+                block:      $ctx.block,
+                contract:   $ctx.contract,
+                simulate:   $ctx.simulate.unwrap(),
+                tx_outcome: $ctx.tx_outcome,
             }
         }
     };
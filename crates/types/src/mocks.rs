@@ -1,5 +1,9 @@
 use {
-    crate::{Addr, Api, Order, Record, StdError, StdResult, Storage},
+    crate::{
+        from_json_slice, to_json_canonical, Addr, Api, Json, Order, Record, StdError, StdResult,
+        Storage,
+    },
+    data_encoding::{BASE64, HEXLOWER, HEXLOWER_PERMISSIVE},
     std::{collections::BTreeMap, iter, ops::Bound},
 };
 
@@ -122,6 +126,11 @@ impl Api for MockApi {
         println!("Contract emitted debug message! addr = {addr}, msg = {msg}");
     }
 
+    // The mock API doesn't track gas, so we pretend the meter is always full.
+    fn gas_remaining(&self) -> StdResult<u64> {
+        Ok(u64::MAX)
+    }
+
     fn secp256r1_verify(&self, msg_hash: &[u8], sig: &[u8], pk: &[u8]) -> StdResult<()> {
         grug_crypto::secp256r1_verify(msg_hash, sig, pk).map_err(|_| StdError::VerificationFailed)
     }
@@ -194,4 +203,30 @@ impl Api for MockApi {
     fn blake3(&self, data: &[u8]) -> [u8; 32] {
         grug_crypto::blake3(data)
     }
+
+    fn encode_base64(&self, data: &[u8]) -> String {
+        BASE64.encode(data)
+    }
+
+    fn decode_base64(&self, data: &str) -> StdResult<Vec<u8>> {
+        BASE64
+            .decode(data.as_bytes())
+            .map_err(|_| StdError::decode("base64"))
+    }
+
+    fn encode_hex(&self, data: &[u8]) -> String {
+        HEXLOWER.encode(data)
+    }
+
+    fn decode_hex(&self, data: &str) -> StdResult<Vec<u8>> {
+        HEXLOWER_PERMISSIVE
+            .decode(data.as_bytes())
+            .map_err(|_| StdError::decode("hex"))
+    }
+
+    fn to_json_canonical(&self, json: &[u8]) -> StdResult<Vec<u8>> {
+        from_json_slice::<Json>(json)
+            .and_then(|json| to_json_canonical(&json))
+            .map_err(|_| StdError::decode("json"))
+    }
 }
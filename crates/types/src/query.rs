@@ -1,7 +1,10 @@
 use {
-    crate::{Addr, Binary, BlockInfo, Coin, Coins, Config, Hash, Json},
+    crate::{
+        Addr, Binary, BlockInfo, Coin, Coins, Config, Hash, Json, Order, SuppliesResponse, TxResult,
+    },
     serde::{Deserialize, Serialize},
     serde_with::skip_serializing_none,
+    std::collections::BTreeSet,
 };
 
 #[skip_serializing_none]
@@ -11,24 +14,43 @@ pub enum QueryRequest {
     /// The chain's global information. Corresponding to the ABCI Info method.
     /// Returns: `InfoResponse`
     Info {},
+    /// The block that is currently being processed. For queries that aren't
+    /// part of a block (e.g. ABCI queries), this is the last finalized block.
+    /// Returns: `BlockInfo`
+    BlockInfo {},
     /// An account's balance in a single denom.
     /// Returns: `Coin`
     Balance { address: Addr, denom: String },
+    /// An account's balance in a single denom, or `None` if the account has
+    /// never held the denom.
+    /// Returns: `Option<Coin>`
+    BalanceExact { address: Addr, denom: String },
     /// Enumerate an account's balances in all denoms.
+    ///
+    /// The balances are returned as `Coins`, which are always sorted by
+    /// denom, so `order` only decides which page of denoms is returned, not
+    /// the order of the coins within it.
     /// Returns: `Coins`
     Balances {
         address: Addr,
         start_after: Option<String>,
         limit: Option<u32>,
+        order: Option<Order>,
     },
     /// A token's total supply.
     /// Returns: `Coin`
     Supply { denom: String },
-    /// Enumerate all tokens' total supplies.
-    /// Returns: `Coins`
+    /// Enumerate all tokens' total supplies, in the given order of denoms
+    /// (ascending by default), starting from the denom right after
+    /// `start_after` (exclusive).
+    ///
+    /// As with `Balances`, the supplies within a page are always sorted by
+    /// denom in ascending order. A `limit` of zero is treated as one.
+    /// Returns: `SuppliesResponse`
     Supplies {
         start_after: Option<String>,
         limit: Option<u32>,
+        order: Option<Order>,
     },
     /// A single Wasm byte code.
     /// Returns: `Binary`
@@ -40,15 +62,20 @@ pub enum QueryRequest {
     Codes {
         start_after: Option<Hash>,
         limit: Option<u32>,
+        order: Option<Order>,
     },
     /// Metadata of a single account.
     /// Returns: `AccountResponse`
     Account { address: Addr },
+    /// Whether an account, i.e. a contract, exists at the given address.
+    /// Returns: `bool`
+    IsContract { address: Addr },
     /// Enumerate metadata of all accounts.
     /// Returns: `Vec<AccountResponse>`
     Accounts {
         start_after: Option<Addr>,
         limit: Option<u32>,
+        order: Option<Order>,
     },
     /// A raw key-value pair in a contract's internal state.
     /// Returns: `WasmRawResponse`
@@ -56,6 +83,10 @@ pub enum QueryRequest {
     /// Call the contract's query entry point with the given message.
     /// Returns: `WasmSmartResponse`
     WasmSmart { contract: Addr, msg: Json },
+    /// The result of a transaction delivered in a block, by the hash of the
+    /// transaction.
+    /// Returns: `TxResult`
+    TxResult { hash: Hash },
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
@@ -71,6 +102,10 @@ pub struct AccountResponse {
     pub address: Addr,
     pub code_hash: Hash,
     pub admin: Option<Addr>,
+    pub receive_hook: bool,
+    pub strict_funds: bool,
+    pub entry_points: Option<BTreeSet<String>>,
+    pub raw_queries: bool,
 }
 
 #[skip_serializing_none]
@@ -99,16 +134,20 @@ pub struct ClientResponse {
 #[serde(rename_all = "snake_case")]
 pub enum QueryResponse {
     Info(InfoResponse),
+    BlockInfo(BlockInfo),
     Balance(Coin),
+    BalanceExact(Option<Coin>),
     Balances(Coins),
     Supply(Coin),
-    Supplies(Coins),
+    Supplies(SuppliesResponse),
     Code(Binary),
     Codes(Vec<Hash>),
     Account(AccountResponse),
+    IsContract(bool),
     Accounts(Vec<AccountResponse>),
     WasmRaw(WasmRawResponse),
     WasmSmart(WasmSmartResponse),
+    TxResult(TxResult),
 }
 
 // TODO: can we use a macro to implement these?
@@ -120,6 +159,13 @@ impl QueryResponse {
         resp
     }
 
+    pub fn as_block_info(self) -> BlockInfo {
+        let Self::BlockInfo(block) = self else {
+            panic!("QueryResponse is not BlockInfo");
+        };
+        block
+    }
+
     pub fn as_balance(self) -> Coin {
         let Self::Balance(coin) = self else {
             panic!("BankQueryResponse is not Balance");
@@ -127,6 +173,13 @@ impl QueryResponse {
         coin
     }
 
+    pub fn as_balance_exact(self) -> Option<Coin> {
+        let Self::BalanceExact(maybe_coin) = self else {
+            panic!("QueryResponse is not BalanceExact");
+        };
+        maybe_coin
+    }
+
     pub fn as_balances(self) -> Coins {
         let Self::Balances(coins) = self else {
             panic!("BankQueryResponse is not Balances");
@@ -141,11 +194,11 @@ impl QueryResponse {
         coin
     }
 
-    pub fn as_supplies(self) -> Coins {
-        let Self::Supplies(coins) = self else {
+    pub fn as_supplies(self) -> SuppliesResponse {
+        let Self::Supplies(resp) = self else {
             panic!("BankQueryResponse is not Supplies");
         };
-        coins
+        resp
     }
 
     pub fn as_code(self) -> Binary {
@@ -169,6 +222,13 @@ impl QueryResponse {
         resp
     }
 
+    pub fn as_is_contract(self) -> bool {
+        let Self::IsContract(is_contract) = self else {
+            panic!("QueryResponse is not IsContract");
+        };
+        is_contract
+    }
+
     pub fn as_accounts(self) -> Vec<AccountResponse> {
         let Self::Accounts(resp) = self else {
             panic!("QueryResponse is not Accounts");
@@ -189,4 +249,11 @@ impl QueryResponse {
         };
         resp
     }
+
+    pub fn as_tx_result(self) -> TxResult {
+        let Self::TxResult(result) = self else {
+            panic!("QueryResponse is not TxResult");
+        };
+        result
+    }
 }
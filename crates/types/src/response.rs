@@ -1,5 +1,5 @@
 use {
-    crate::{to_json_value, Attribute, Json, Message, StdResult},
+    crate::{to_json_value, Attribute, Event, Json, Message, StdResult},
     serde::{Deserialize, Serialize},
 };
 
@@ -7,6 +7,12 @@ use {
 pub struct Response {
     pub submsgs: Vec<SubMessage>,
     pub attributes: Vec<Attribute>,
+    /// Custom events emitted by the contract.
+    ///
+    /// The host prefixes their types before emitting them, so that they can't
+    /// be confused with events emitted by the state machine itself.
+    #[serde(default)]
+    pub events: Vec<Event>,
 }
 
 impl Response {
@@ -39,6 +45,11 @@ impl Response {
         self.attributes.push(Attribute::new(key, value));
         self
     }
+
+    pub fn add_event(mut self, event: Event) -> Self {
+        self.events.push(event);
+        self
+    }
 }
 
 /// Indicates that after a submessage has been executed, whether the host should
@@ -1,5 +1,5 @@
 use {
-    crate::{Event, StdError, StdResult},
+    crate::{Event, QueryError, QueryResponse, StdError, StdResult},
     serde::{Deserialize, Serialize},
 };
 
@@ -7,17 +7,25 @@ use {
 /// entry point.
 pub type SubMsgResult = GenericResult<Vec<Event>>;
 
+/// The result of a query that a contract makes to the chain, provided to the
+/// contract by the `query_chain` import. Unlike other results passed over the
+/// FFI, its error is structured, so that the contract can tell why the query
+/// failed.
+pub type QueryResult = GenericResult<QueryResponse, QueryError>;
+
 /// A result type that can be serialized into a string and thus passed over the
 /// FFI boundary.
 ///
 /// This is used in two cases:
 /// - the host calls an export function on the Wasm module
 /// - the Wasm module calls an import function provided by the host
+///
+/// The error is a string, unless another type is specified.
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
-pub enum GenericResult<T> {
+pub enum GenericResult<T, E = String> {
     Ok(T),
-    Err(String),
+    Err(E),
 }
 
 impl<T, E> From<Result<T, E>> for GenericResult<T>
@@ -66,3 +74,22 @@ impl<T> GenericResult<T> {
         }
     }
 }
+
+impl From<StdResult<QueryResponse>> for QueryResult {
+    fn from(res: StdResult<QueryResponse>) -> Self {
+        match res {
+            Result::Ok(res) => Self::Ok(res),
+            Result::Err(err) => Self::Err(err.into()),
+        }
+    }
+}
+
+impl QueryResult {
+    /// Convert the QueryResult to a StdResult, keeping the error structured.
+    pub fn into_std_result(self) -> StdResult<QueryResponse> {
+        match self {
+            GenericResult::Ok(res) => Ok(res),
+            GenericResult::Err(err) => Err(err.into()),
+        }
+    }
+}
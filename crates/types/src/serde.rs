@@ -16,7 +16,7 @@ where
 /// Serialize a Rust value into JSON value.
 pub fn to_json_value<T>(data: &T) -> StdResult<Json>
 where
-    T: Serialize,
+    T: Serialize + ?Sized,
 {
     serde_json::to_value(data)
         .map(Into::into)
@@ -35,11 +35,57 @@ where
 /// Serialize a Rust value into bytes using the JSON encoding scheme.
 pub fn to_json_vec<T>(data: &T) -> StdResult<Vec<u8>>
 where
-    T: Serialize,
+    T: Serialize + ?Sized,
 {
     serde_json::to_vec(data).map_err(StdError::serialize::<T>)
 }
 
+/// Serialize a Rust value into an indented JSON string, for human inspection
+/// (e.g. printing in the CLI).
+///
+/// Never use this for anything that's part of consensus; use `to_json_vec`
+/// instead.
+pub fn to_json_pretty<T>(data: &T) -> StdResult<String>
+where
+    T: Serialize + ?Sized,
+{
+    serde_json::to_string_pretty(data).map_err(StdError::serialize::<T>)
+}
+
+/// Serialize a Rust value into bytes using the JSON encoding scheme, in a
+/// canonical form: object keys are sorted, and there is no insignificant
+/// whitespace.
+///
+/// Use this when the JSON is to be hashed (e.g. for signing), so that
+/// logically equal data always produces the same hash.
+pub fn to_json_canonical<T>(data: &T) -> StdResult<Vec<u8>>
+where
+    T: Serialize + ?Sized,
+{
+    let json = serde_json::to_value(data).map_err(StdError::serialize::<T>)?;
+    serde_json::to_vec(&sort_json_keys(json)).map_err(StdError::serialize::<T>)
+}
+
+// `serde_json::Map` is sorted by default, but not if the `preserve_order`
+// feature is enabled (which may happen by feature unification), so we sort the
+// keys explicitly.
+fn sort_json_keys(json: Json) -> Json {
+    match json {
+        Json::Object(map) => {
+            let mut entries = map.into_iter().collect::<Vec<_>>();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            Json::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, sort_json_keys(value)))
+                    .collect(),
+            )
+        },
+        Json::Array(values) => Json::Array(values.into_iter().map(sort_json_keys).collect()),
+        _ => json,
+    }
+}
+
 /// Deserialize a slice of bytes into Rust value of a given type `T` using the
 /// [Borsh](https://crates.io/crates/borsh) encoding scheme.
 pub fn from_borsh_slice<T>(bytes: impl AsRef<[u8]>) -> StdResult<T>
@@ -74,3 +120,74 @@ where
 {
     data.encode_to_vec()
 }
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{Coin, Coins, NonZero},
+    };
+
+    #[test]
+    fn canonical_json() {
+        let a: Json = from_json_slice(br#"{"b":1,"a":{"d":[{"y":2,"x":1}],"c":null}}"#).unwrap();
+        let b: Json = from_json_slice(
+            br#"{
+                "a": { "c": null, "d": [ { "x": 1, "y": 2 } ] },
+                "b": 1
+            }"#,
+        )
+        .unwrap();
+
+        let expect = br#"{"a":{"c":null,"d":[{"x":1,"y":2}]},"b":1}"#;
+        assert_eq!(to_json_canonical(&a).unwrap(), expect);
+        assert_eq!(to_json_canonical(&b).unwrap(), expect);
+    }
+
+    #[test]
+    fn json_round_trips_borrowed_data() {
+        let coins = Coins::try_from([
+            Coin::new("uatom", NonZero::new(123_u128)),
+            Coin::new("uosmo", NonZero::new(456_u128)),
+        ])
+        .unwrap();
+
+        // Serialize from a reference, deserialize from a slice or a vector.
+        let bytes: Vec<u8> = to_json_vec(&coins).unwrap();
+        assert_eq!(from_json_slice::<Coins>(bytes.as_slice()).unwrap(), coins);
+        assert_eq!(from_json_slice::<Coins>(&bytes).unwrap(), coins);
+        assert_eq!(from_json_slice::<Coins>(bytes).unwrap(), coins);
+
+        // Unsized data, such as a slice of coins or a string slice, can be
+        // serialized without first being copied into an owned value.
+        let list = coins.clone().into_iter().collect::<Vec<_>>();
+        let bytes = to_json_vec(list.as_slice()).unwrap();
+        assert_eq!(from_json_slice::<Vec<Coin>>(&bytes).unwrap(), list);
+        assert_eq!(
+            Coins::try_from(from_json_slice::<Vec<Coin>>(&bytes).unwrap()).unwrap(),
+            coins
+        );
+
+        let json = to_json_value("uatom").unwrap();
+        assert_eq!(from_json_value::<String>(json).unwrap(), "uatom");
+    }
+
+    #[test]
+    fn pretty_json() {
+        let coins = Coins::try_from([
+            Coin::new("uatom", NonZero::new(123_u128)),
+            Coin::new("uosmo", NonZero::new(456_u128)),
+        ])
+        .unwrap();
+
+        let pretty = to_json_pretty(&coins).unwrap();
+        let compact = to_json_vec(&coins).unwrap();
+        assert!(pretty.contains('\n'));
+        assert!(!compact.contains(&b'\n'));
+
+        assert_eq!(from_json_slice::<Coins>(pretty).unwrap(), coins);
+        assert_eq!(from_json_slice::<Coins>(compact).unwrap(), coins);
+    }
+}
@@ -1,17 +1,97 @@
 use {
-    crate::{to_json_value, Addr, Binary, Coins, Config, Hash, Json, StdError, StdResult},
+    crate::{
+        to_json_value, Addr, BankAdminMsg, Binary, Coins, Config, Event, Hash, Json, StdError,
+        StdResult,
+    },
+    borsh::{BorshDeserialize, BorshSerialize},
     serde::{Deserialize, Serialize},
     serde_with::skip_serializing_none,
+    std::collections::BTreeSet,
 };
 
+/// The maximum length of a transaction's memo, in bytes.
+pub const MAX_MEMO_LENGTH: usize = 256;
+
+/// The maximum number of outputs in a `Message::TransferMulti`.
+pub const MAX_TRANSFER_OUTPUTS: usize = 256;
+
+#[skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 pub struct Tx {
     pub sender: Addr,
     pub msgs: Vec<Message>,
     pub credential: Binary,
     pub gas_limit: u64,
+    /// An optional note attached to the transaction, such as a deposit
+    /// reference for an exchange. Emitted in the transaction's events, so that
+    /// it can be indexed. Must be no longer than `MAX_MEMO_LENGTH` bytes.
+    pub memo: Option<String>,
+}
+
+/// A summary of the outcome of a transaction's messages, provided to the sender
+/// account's `after_tx` entry point.
+///
+/// The messages are executed atomically: if one of them fails, the state
+/// changes made by all of them are discarded.
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TxOutcome {
+    /// The error of the message that failed; `None` if all of them succeeded.
+    pub error: Option<String>,
+    /// The number of events emitted by the messages; zero if one failed.
+    pub events: u32,
+    /// The amount of gas consumed by the messages.
+    pub gas_used: u64,
+    /// The amount of unused gas to be refunded to the sender, as determined
+    /// by the chain's `gas_refund_ratio`. Zero if the transaction doesn't
+    /// have a gas limit, e.g. in simulation mode.
+    ///
+    /// This gas is withheld from `after_tx`, so the refund stays accurate
+    /// whatever `after_tx` does.
+    pub gas_refund: u64,
+}
+
+impl TxOutcome {
+    /// Whether all the messages succeeded.
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
 }
 
+/// The result of a transaction delivered in a block, indexed by the hash of
+/// the transaction so that clients can look it up.
+#[skip_serializing_none]
+#[derive(Serialize, Deserialize, BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TxResult {
+    /// The height of the block in which the transaction was delivered.
+    pub height: u64,
+    /// The error that the transaction failed with; `None` if it succeeded.
+    pub error: Option<String>,
+    /// The events emitted by the transaction; empty if it failed.
+    pub events: Vec<Event>,
+    /// The amount of gas consumed by the transaction.
+    pub gas_used: u64,
+}
+
+impl TxResult {
+    /// Whether the transaction succeeded.
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// A message to be processed by the state machine.
+///
+/// In JSON, messages are externally tagged: a message is an object with a
+/// single key, the variant's name in snake case, whose value holds the
+/// variant's fields, e.g.
+///
+/// ```json
+/// {"transfer":{"to":"0x...","coins":{"uatom":"123"}}}
+/// ```
+///
+/// Fields of `None`, and flags that are `false`, are omitted. Clients in other
+/// languages are generated against this format, so it must be kept stable.
 #[skip_serializing_none]
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -23,8 +103,19 @@ pub enum Message {
     Configure { new_cfg: Config },
     /// Send coins to the given recipient address.
     Transfer { to: Addr, coins: Coins },
+    /// Send coins to multiple recipient addresses.
+    ///
+    /// The transfers either all succeed or all fail. A recipient may appear
+    /// more than once, in which case it receives the sum of its outputs. There
+    /// can be at most `MAX_TRANSFER_OUTPUTS` outputs.
+    TransferMulti { outputs: Vec<(Addr, Coins)> },
     /// Upload a Wasm binary code and store it in the chain's state.
     Upload { code: Binary },
+    /// Delete a code from the chain's state, in order to reclaim disk space.
+    ///
+    /// Only the `owner` is authorized to do this. The code must not be used by
+    /// any account.
+    RemoveCode { code_hash: Hash },
     /// Register a new account.
     Instantiate {
         code_hash: Hash,
@@ -32,6 +123,47 @@ pub enum Message {
         salt: Binary,
         funds: Coins,
         admin: Option<Addr>,
+        /// Whether to call the contract's `receive` entry point whenever it's
+        /// sent coins with a `Message::Transfer`. If not, the contract is
+        /// credited without being called, and doesn't need to implement the
+        /// entry point.
+        #[serde(default, skip_serializing_if = "is_false")]
+        receive_hook: bool,
+        /// Whether the contract is to reject being executed with funds
+        /// attached, for contracts that don't handle them.
+        #[serde(default, skip_serializing_if = "is_false")]
+        strict_funds: bool,
+        /// The entry points the host is allowed to call on the contract, even
+        /// if it exports others. `None` means all of them.
+        #[serde(default)]
+        entry_points: Option<BTreeSet<String>>,
+        /// Whether other contracts may read the contract's storage directly
+        /// with `WasmRaw` queries.
+        #[serde(default, skip_serializing_if = "is_false")]
+        raw_queries: bool,
+    },
+    /// Register a new account, with an address derived from a global counter
+    /// that is incremented on each such instantiation, instead of a salt.
+    ///
+    /// The first contract instantiated this way gets counter value 1, the
+    /// second gets 2, and so on. See `Addr::compute_sequential`.
+    InstantiateSequential {
+        code_hash: Hash,
+        msg: Json,
+        funds: Coins,
+        admin: Option<Addr>,
+        /// See `Message::Instantiate`.
+        #[serde(default, skip_serializing_if = "is_false")]
+        receive_hook: bool,
+        /// See `Message::Instantiate`.
+        #[serde(default, skip_serializing_if = "is_false")]
+        strict_funds: bool,
+        /// See `Message::Instantiate`.
+        #[serde(default)]
+        entry_points: Option<BTreeSet<String>>,
+        /// See `Message::Instantiate`.
+        #[serde(default, skip_serializing_if = "is_false")]
+        raw_queries: bool,
     },
     /// Execute a contract.
     Execute {
@@ -48,6 +180,12 @@ pub enum Message {
         new_code_hash: Hash,
         msg: Json,
     },
+    /// Perform an admin operation on a denom, such as setting its metadata.
+    ///
+    /// Only the denom's admin is authorized to do this. The admin of a factory
+    /// denom (`factory/{creator}/{subdenom}`) is its creator; the admin of a
+    /// native denom is the chain's `owner`.
+    BankAdmin { msg: BankAdminMsg },
 }
 
 impl Message {
@@ -66,6 +204,10 @@ impl Message {
         })
     }
 
+    pub fn transfer_multi(outputs: Vec<(Addr, Coins)>) -> Self {
+        Self::TransferMulti { outputs }
+    }
+
     pub fn upload<B>(code: B) -> Self
     where
         B: Into<Binary>,
@@ -73,6 +215,10 @@ impl Message {
         Self::Upload { code: code.into() }
     }
 
+    pub fn remove_code(code_hash: Hash) -> Self {
+        Self::RemoveCode { code_hash }
+    }
+
     pub fn instantiate<M, S, C>(
         code_hash: Hash,
         msg: &M,
@@ -92,9 +238,100 @@ impl Message {
             salt: salt.into(),
             funds: funds.try_into()?,
             admin,
+            receive_hook: false,
+            strict_funds: false,
+            entry_points: None,
+            raw_queries: false,
         })
     }
 
+    pub fn instantiate_sequential<M, C>(
+        code_hash: Hash,
+        msg: &M,
+        funds: C,
+        admin: Option<Addr>,
+    ) -> StdResult<Self>
+    where
+        M: Serialize,
+        C: TryInto<Coins>,
+        StdError: From<C::Error>,
+    {
+        Ok(Self::InstantiateSequential {
+            code_hash,
+            msg: to_json_value(msg)?,
+            funds: funds.try_into()?,
+            admin,
+            receive_hook: false,
+            strict_funds: false,
+            entry_points: None,
+            raw_queries: false,
+        })
+    }
+
+    /// Opt the contract to be instantiated into having its `receive` entry
+    /// point called whenever it's sent coins with a `Message::Transfer`.
+    ///
+    /// Has no effect on messages other than `Instantiate` and
+    /// `InstantiateSequential`.
+    pub fn with_receive_hook(mut self) -> Self {
+        if let Self::Instantiate { receive_hook, .. }
+        | Self::InstantiateSequential { receive_hook, .. } = &mut self
+        {
+            *receive_hook = true;
+        }
+
+        self
+    }
+
+    /// Make the contract to be instantiated reject being executed with funds
+    /// attached.
+    ///
+    /// Has no effect on messages other than `Instantiate` and
+    /// `InstantiateSequential`.
+    pub fn with_strict_funds(mut self) -> Self {
+        if let Self::Instantiate { strict_funds, .. }
+        | Self::InstantiateSequential { strict_funds, .. } = &mut self
+        {
+            *strict_funds = true;
+        }
+
+        self
+    }
+
+    /// Only allow the host to call the given entry points on the contract to
+    /// be instantiated, e.g. to disable `migrate` even if it's exported.
+    ///
+    /// Has no effect on messages other than `Instantiate` and
+    /// `InstantiateSequential`.
+    pub fn with_entry_points<I, S>(mut self, names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: ToString,
+    {
+        if let Self::Instantiate { entry_points, .. }
+        | Self::InstantiateSequential { entry_points, .. } = &mut self
+        {
+            *entry_points = Some(names.into_iter().map(|name| name.to_string()).collect());
+        }
+
+        self
+    }
+
+    /// Allow other contracts to read the storage of the contract to be
+    /// instantiated directly with `WasmRaw` queries.
+    ///
+    /// Has no effect on messages other than `Instantiate` and
+    /// `InstantiateSequential`.
+    pub fn with_raw_queries(mut self) -> Self {
+        if let Self::Instantiate { raw_queries, .. }
+        | Self::InstantiateSequential { raw_queries, .. } = &mut self
+        {
+            *raw_queries = true;
+        }
+
+        self
+    }
+
     pub fn execute<M, C>(contract: Addr, msg: &M, funds: C) -> StdResult<Self>
     where
         M: Serialize,
@@ -118,4 +355,123 @@ impl Message {
             msg: to_json_value(msg)?,
         })
     }
+
+    pub fn bank_admin(msg: BankAdminMsg) -> Self {
+        Self::BankAdmin { msg }
+    }
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+// ----------------------------------- tests -----------------------------------
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        crate::{from_json_value, NonZero},
+        serde_json::json,
+    };
+
+    const MOCK_ADDR_1: &str = "0x0000000000000000000000000000000000000000000000000000000000000001";
+    const MOCK_ADDR_2: &str = "0x0000000000000000000000000000000000000000000000000000000000000002";
+    const MOCK_HASH: &str = "0101010101010101010101010101010101010101010101010101010101010101";
+
+    fn mock_funds() -> Coins {
+        Coins::new_one("uatom", NonZero::new(123_u128))
+    }
+
+    /// Assert that the message serializes to exactly the given JSON, and is
+    /// deserialized back from it.
+    #[track_caller]
+    fn assert_wire_format(msg: Message, json: Json) {
+        assert_eq!(to_json_value(&msg).unwrap(), json);
+        assert_eq!(from_json_value::<Message>(json).unwrap(), msg);
+    }
+
+    #[test]
+    fn transfer_wire_format() {
+        assert_wire_format(
+            Message::transfer(Addr::mock(1), mock_funds()).unwrap(),
+            json!({
+                "transfer": {
+                    "to": MOCK_ADDR_1,
+                    "coins": { "uatom": "123" },
+                },
+            }),
+        );
+    }
+
+    #[test]
+    fn upload_wire_format() {
+        assert_wire_format(
+            Message::upload(b"\0asm".to_vec()),
+            json!({
+                "upload": {
+                    "code": "AGFzbQ==",
+                },
+            }),
+        );
+    }
+
+    #[test]
+    fn instantiate_wire_format() {
+        let msg = Message::instantiate(
+            Hash::from_slice([1; Hash::LENGTH]),
+            &json!({ "foo": "bar" }),
+            b"salt".to_vec(),
+            mock_funds(),
+            Some(Addr::mock(2)),
+        )
+        .unwrap();
+
+        assert_wire_format(
+            msg.clone(),
+            json!({
+                "instantiate": {
+                    "code_hash": MOCK_HASH,
+                    "msg": { "foo": "bar" },
+                    "salt": "c2FsdA==",
+                    "funds": { "uatom": "123" },
+                    "admin": MOCK_ADDR_2,
+                },
+            }),
+        );
+
+        assert_wire_format(
+            msg.with_receive_hook()
+                .with_strict_funds()
+                .with_entry_points(["instantiate", "execute"])
+                .with_raw_queries(),
+            json!({
+                "instantiate": {
+                    "code_hash": MOCK_HASH,
+                    "msg": { "foo": "bar" },
+                    "salt": "c2FsdA==",
+                    "funds": { "uatom": "123" },
+                    "admin": MOCK_ADDR_2,
+                    "receive_hook": true,
+                    "strict_funds": true,
+                    "entry_points": ["execute", "instantiate"],
+                    "raw_queries": true,
+                },
+            }),
+        );
+    }
+
+    #[test]
+    fn execute_wire_format() {
+        assert_wire_format(
+            Message::execute(Addr::mock(1), &json!({ "foo": "bar" }), mock_funds()).unwrap(),
+            json!({
+                "execute": {
+                    "contract": MOCK_ADDR_1,
+                    "msg": { "foo": "bar" },
+                    "funds": { "uatom": "123" },
+                },
+            }),
+        );
+    }
 }
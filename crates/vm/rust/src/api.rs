@@ -0,0 +1,126 @@
+use {
+    grug_app::GasTracker,
+    grug_types::{Addr, Api, MockApi, StdError, StdResult},
+};
+
+/// The `Api` provided to Rust contracts.
+///
+/// Rust contracts run natively, so cryptography and encoding methods are simply
+/// delegated to `MockApi`. The exception is `gas_remaining`, which reads the
+/// gas tracker of the current call.
+pub struct RustApi {
+    gas_tracker: GasTracker,
+}
+
+impl RustApi {
+    pub fn new(gas_tracker: GasTracker) -> Self {
+        Self { gas_tracker }
+    }
+}
+
+impl Api for RustApi {
+    fn debug(&self, addr: &Addr, msg: &str) {
+        MockApi.debug(addr, msg)
+    }
+
+    // Rust contracts are always compiled together with the host, so there is
+    // no distinction between debug and release builds of the contract. Only
+    // the host's restriction to simulation applies.
+    fn gas_remaining(&self) -> StdResult<u64> {
+        self.gas_tracker
+            .read_remaining()
+            .map_err(StdError::generic_err)?
+            .ok_or_else(|| StdError::generic_err("`gas_remaining` is only available in simulation"))
+    }
+
+    fn secp256r1_verify(&self, msg_hash: &[u8], sig: &[u8], pk: &[u8]) -> StdResult<()> {
+        MockApi.secp256r1_verify(msg_hash, sig, pk)
+    }
+
+    fn secp256k1_verify(&self, msg_hash: &[u8], sig: &[u8], pk: &[u8]) -> StdResult<()> {
+        MockApi.secp256k1_verify(msg_hash, sig, pk)
+    }
+
+    fn secp256k1_pubkey_recover(
+        &self,
+        msg_hash: &[u8],
+        sig: &[u8],
+        recovery_id: u8,
+        compressed: bool,
+    ) -> StdResult<Vec<u8>> {
+        MockApi.secp256k1_pubkey_recover(msg_hash, sig, recovery_id, compressed)
+    }
+
+    fn ed25519_verify(&self, msg_hash: &[u8], sig: &[u8], pk: &[u8]) -> StdResult<()> {
+        MockApi.ed25519_verify(msg_hash, sig, pk)
+    }
+
+    fn ed25519_batch_verify(
+        &self,
+        msgs_hash: &[&[u8]],
+        sigs: &[&[u8]],
+        pks: &[&[u8]],
+    ) -> StdResult<()> {
+        MockApi.ed25519_batch_verify(msgs_hash, sigs, pks)
+    }
+
+    fn sha2_256(&self, data: &[u8]) -> [u8; 32] {
+        MockApi.sha2_256(data)
+    }
+
+    fn sha2_512(&self, data: &[u8]) -> [u8; 64] {
+        MockApi.sha2_512(data)
+    }
+
+    fn sha2_512_truncated(&self, data: &[u8]) -> [u8; 32] {
+        MockApi.sha2_512_truncated(data)
+    }
+
+    fn sha3_256(&self, data: &[u8]) -> [u8; 32] {
+        MockApi.sha3_256(data)
+    }
+
+    fn sha3_512(&self, data: &[u8]) -> [u8; 64] {
+        MockApi.sha3_512(data)
+    }
+
+    fn sha3_512_truncated(&self, data: &[u8]) -> [u8; 32] {
+        MockApi.sha3_512_truncated(data)
+    }
+
+    fn keccak256(&self, data: &[u8]) -> [u8; 32] {
+        MockApi.keccak256(data)
+    }
+
+    fn blake2s_256(&self, data: &[u8]) -> [u8; 32] {
+        MockApi.blake2s_256(data)
+    }
+
+    fn blake2b_512(&self, data: &[u8]) -> [u8; 64] {
+        MockApi.blake2b_512(data)
+    }
+
+    fn blake3(&self, data: &[u8]) -> [u8; 32] {
+        MockApi.blake3(data)
+    }
+
+    fn encode_base64(&self, data: &[u8]) -> String {
+        MockApi.encode_base64(data)
+    }
+
+    fn decode_base64(&self, data: &str) -> StdResult<Vec<u8>> {
+        MockApi.decode_base64(data)
+    }
+
+    fn encode_hex(&self, data: &[u8]) -> String {
+        MockApi.encode_hex(data)
+    }
+
+    fn decode_hex(&self, data: &str) -> StdResult<Vec<u8>> {
+        MockApi.decode_hex(data)
+    }
+
+    fn to_json_canonical(&self, json: &[u8]) -> StdResult<Vec<u8>> {
+        MockApi.to_json_canonical(json)
+    }
+}
@@ -1,7 +1,7 @@
 use {
     crate::{
         AfterBlockFn, AfterTxFn, BankExecuteFn, BankQueryFn, BeforeBlockFn, BeforeTxFn, Contract,
-        ExecuteFn, InstantiateFn, MigrateFn, QueryFn, ReceiveFn, ReplyFn,
+        ExecuteFn, InstantiateFn, MigrateFn, QueryFn, ReceiveFn, ReplyFn, SudoFn,
     },
     elsa::sync::FrozenVec,
     grug_types::{
@@ -44,6 +44,7 @@ pub struct ContractBuilder<
     M3 = Empty,
     M5 = Empty,
     M6 = Empty,
+    M7 = Empty,
     E2 = StdError,
     E3 = StdError,
     E4 = StdError,
@@ -55,6 +56,7 @@ pub struct ContractBuilder<
     E10 = StdError,
     E11 = StdError,
     E12 = StdError,
+    E13 = StdError,
 > {
     instantiate_fn: InstantiateFn<M1, E1>,
     execute_fn: Option<ExecuteFn<M2, E2>>,
@@ -68,6 +70,7 @@ pub struct ContractBuilder<
     after_block_fn: Option<AfterBlockFn<E10>>,
     bank_execute_fn: Option<BankExecuteFn<E11>>,
     bank_query_fn: Option<BankQueryFn<E12>>,
+    sudo_fn: Option<SudoFn<M7, E13>>,
 }
 
 impl<M1, E1> ContractBuilder<M1, E1>
@@ -89,18 +92,20 @@ where
             after_block_fn: None,
             bank_execute_fn: None,
             bank_query_fn: None,
+            sudo_fn: None,
         }
     }
 }
 
-impl<M1, E1, M2, M3, M5, M6, E2, E3, E4, E5, E6, E7, E8, E9, E10, E11, E12>
-    ContractBuilder<M1, E1, M2, M3, M5, M6, E2, E3, E4, E5, E6, E7, E8, E9, E10, E11, E12>
+impl<M1, E1, M2, M3, M5, M6, M7, E2, E3, E4, E5, E6, E7, E8, E9, E10, E11, E12, E13>
+    ContractBuilder<M1, E1, M2, M3, M5, M6, M7, E2, E3, E4, E5, E6, E7, E8, E9, E10, E11, E12, E13>
 where
     M1: DeserializeOwned + 'static,
     M2: DeserializeOwned + 'static,
     M3: DeserializeOwned + 'static,
     M5: DeserializeOwned + 'static,
     M6: DeserializeOwned + 'static,
+    M7: DeserializeOwned + 'static,
     E1: ToString + 'static,
     E2: ToString + 'static,
     E3: ToString + 'static,
@@ -113,11 +118,32 @@ where
     E10: ToString + 'static,
     E11: ToString + 'static,
     E12: ToString + 'static,
+    E13: ToString + 'static,
 {
     pub fn with_execute<M2A, E2A>(
         self,
         execute_fn: ExecuteFn<M2A, E2A>,
-    ) -> ContractBuilder<M1, E1, M2A, M3, M5, M6, E2A, E3, E4, E5, E6, E7, E8, E9, E10, E11, E12>
+    ) -> ContractBuilder<
+        M1,
+        E1,
+        M2A,
+        M3,
+        M5,
+        M6,
+        M7,
+        E2A,
+        E3,
+        E4,
+        E5,
+        E6,
+        E7,
+        E8,
+        E9,
+        E10,
+        E11,
+        E12,
+        E13,
+    >
     where
         M2A: DeserializeOwned + 'static,
         E2A: ToString + 'static,
@@ -135,13 +161,34 @@ where
             after_block_fn: self.after_block_fn,
             bank_execute_fn: self.bank_execute_fn,
             bank_query_fn: self.bank_query_fn,
+            sudo_fn: self.sudo_fn,
         }
     }
 
     pub fn with_migrate<M3A, E3A>(
         self,
         migrate_fn: MigrateFn<M3A, E3A>,
-    ) -> ContractBuilder<M1, E1, M2, M3A, M5, M6, E2, E3A, E4, E5, E6, E7, E8, E9, E10, E11, E12>
+    ) -> ContractBuilder<
+        M1,
+        E1,
+        M2,
+        M3A,
+        M5,
+        M6,
+        M7,
+        E2,
+        E3A,
+        E4,
+        E5,
+        E6,
+        E7,
+        E8,
+        E9,
+        E10,
+        E11,
+        E12,
+        E13,
+    >
     where
         M3A: DeserializeOwned + 'static,
         E3A: ToString + 'static,
@@ -159,13 +206,34 @@ where
             after_block_fn: self.after_block_fn,
             bank_execute_fn: self.bank_execute_fn,
             bank_query_fn: self.bank_query_fn,
+            sudo_fn: self.sudo_fn,
         }
     }
 
     pub fn with_receive<E4A>(
         self,
         receive_fn: ReceiveFn<E4A>,
-    ) -> ContractBuilder<M1, E1, M2, M3, M5, M6, E2, E3, E4A, E5, E6, E7, E8, E9, E10, E11, E12>
+    ) -> ContractBuilder<
+        M1,
+        E1,
+        M2,
+        M3,
+        M5,
+        M6,
+        M7,
+        E2,
+        E3,
+        E4A,
+        E5,
+        E6,
+        E7,
+        E8,
+        E9,
+        E10,
+        E11,
+        E12,
+        E13,
+    >
     where
         E4A: ToString + 'static,
     {
@@ -182,13 +250,34 @@ where
             after_block_fn: self.after_block_fn,
             bank_execute_fn: self.bank_execute_fn,
             bank_query_fn: self.bank_query_fn,
+            sudo_fn: self.sudo_fn,
         }
     }
 
     pub fn with_reply<M5A, E5A>(
         self,
         reply_fn: ReplyFn<M5A, E5A>,
-    ) -> ContractBuilder<M1, E1, M2, M3, M5A, M6, E2, E3, E4, E5A, E6, E7, E8, E9, E10, E11, E12>
+    ) -> ContractBuilder<
+        M1,
+        E1,
+        M2,
+        M3,
+        M5A,
+        M6,
+        M7,
+        E2,
+        E3,
+        E4,
+        E5A,
+        E6,
+        E7,
+        E8,
+        E9,
+        E10,
+        E11,
+        E12,
+        E13,
+    >
     where
         M5A: DeserializeOwned + 'static,
         E5A: ToString + 'static,
@@ -206,13 +295,34 @@ where
             after_block_fn: self.after_block_fn,
             bank_execute_fn: self.bank_execute_fn,
             bank_query_fn: self.bank_query_fn,
+            sudo_fn: self.sudo_fn,
         }
     }
 
     pub fn with_query<M6A, E6A>(
         self,
         query_fn: QueryFn<M6A, E6A>,
-    ) -> ContractBuilder<M1, E1, M2, M3, M5, M6A, E2, E3, E4, E5, E6A, E7, E8, E9, E10, E11, E12>
+    ) -> ContractBuilder<
+        M1,
+        E1,
+        M2,
+        M3,
+        M5,
+        M6A,
+        M7,
+        E2,
+        E3,
+        E4,
+        E5,
+        E6A,
+        E7,
+        E8,
+        E9,
+        E10,
+        E11,
+        E12,
+        E13,
+    >
     where
         M6A: DeserializeOwned + 'static,
         E6A: ToString + 'static,
@@ -230,14 +340,34 @@ where
             after_block_fn: self.after_block_fn,
             bank_execute_fn: self.bank_execute_fn,
             bank_query_fn: self.bank_query_fn,
+            sudo_fn: self.sudo_fn,
         }
     }
 
     pub fn with_before_tx<E7A>(
         self,
         before_tx_fn: BeforeTxFn<E7A>,
-    ) -> ContractBuilder<M1, E1, M2, M3, M5, M6, E2, E3, E4, E5, E6, E7A, E8, E9, E10, E11, E12>
-    {
+    ) -> ContractBuilder<
+        M1,
+        E1,
+        M2,
+        M3,
+        M5,
+        M6,
+        M7,
+        E2,
+        E3,
+        E4,
+        E5,
+        E6,
+        E7A,
+        E8,
+        E9,
+        E10,
+        E11,
+        E12,
+        E13,
+    > {
         ContractBuilder {
             instantiate_fn: self.instantiate_fn,
             execute_fn: self.execute_fn,
@@ -251,14 +381,34 @@ where
             after_block_fn: self.after_block_fn,
             bank_execute_fn: self.bank_execute_fn,
             bank_query_fn: self.bank_query_fn,
+            sudo_fn: self.sudo_fn,
         }
     }
 
     pub fn with_after_tx<E8A>(
         self,
         after_tx_fn: AfterTxFn<E8A>,
-    ) -> ContractBuilder<M1, E1, M2, M3, M5, M6, E2, E3, E4, E5, E6, E7, E8A, E9, E10, E11, E12>
-    {
+    ) -> ContractBuilder<
+        M1,
+        E1,
+        M2,
+        M3,
+        M5,
+        M6,
+        M7,
+        E2,
+        E3,
+        E4,
+        E5,
+        E6,
+        E7,
+        E8A,
+        E9,
+        E10,
+        E11,
+        E12,
+        E13,
+    > {
         ContractBuilder {
             instantiate_fn: self.instantiate_fn,
             execute_fn: self.execute_fn,
@@ -272,14 +422,34 @@ where
             after_block_fn: self.after_block_fn,
             bank_execute_fn: self.bank_execute_fn,
             bank_query_fn: self.bank_query_fn,
+            sudo_fn: self.sudo_fn,
         }
     }
 
     pub fn with_before_block<E9A>(
         self,
         before_block_fn: BeforeBlockFn<E9A>,
-    ) -> ContractBuilder<M1, E1, M2, M3, M5, M6, E2, E3, E4, E5, E6, E7, E8, E9A, E10, E11, E12>
-    {
+    ) -> ContractBuilder<
+        M1,
+        E1,
+        M2,
+        M3,
+        M5,
+        M6,
+        M7,
+        E2,
+        E3,
+        E4,
+        E5,
+        E6,
+        E7,
+        E8,
+        E9A,
+        E10,
+        E11,
+        E12,
+        E13,
+    > {
         ContractBuilder {
             instantiate_fn: self.instantiate_fn,
             execute_fn: self.execute_fn,
@@ -293,14 +463,34 @@ where
             after_block_fn: self.after_block_fn,
             bank_execute_fn: self.bank_execute_fn,
             bank_query_fn: self.bank_query_fn,
+            sudo_fn: self.sudo_fn,
         }
     }
 
     pub fn with_after_block<E10A>(
         self,
         after_block_fn: AfterBlockFn<E10A>,
-    ) -> ContractBuilder<M1, E1, M2, M3, M5, M6, E2, E3, E4, E5, E6, E7, E8, E9, E10A, E11, E12>
-    {
+    ) -> ContractBuilder<
+        M1,
+        E1,
+        M2,
+        M3,
+        M5,
+        M6,
+        M7,
+        E2,
+        E3,
+        E4,
+        E5,
+        E6,
+        E7,
+        E8,
+        E9,
+        E10A,
+        E11,
+        E12,
+        E13,
+    > {
         ContractBuilder {
             instantiate_fn: self.instantiate_fn,
             execute_fn: self.execute_fn,
@@ -314,14 +504,34 @@ where
             after_block_fn: Some(after_block_fn),
             bank_execute_fn: self.bank_execute_fn,
             bank_query_fn: self.bank_query_fn,
+            sudo_fn: self.sudo_fn,
         }
     }
 
     pub fn with_bank_execute<E11A>(
         self,
         bank_execute_fn: BankExecuteFn<E11A>,
-    ) -> ContractBuilder<M1, E1, M2, M3, M5, M6, E2, E3, E4, E5, E6, E7, E8, E9, E10, E11A, E12>
-    {
+    ) -> ContractBuilder<
+        M1,
+        E1,
+        M2,
+        M3,
+        M5,
+        M6,
+        M7,
+        E2,
+        E3,
+        E4,
+        E5,
+        E6,
+        E7,
+        E8,
+        E9,
+        E10,
+        E11A,
+        E12,
+        E13,
+    > {
         ContractBuilder {
             instantiate_fn: self.instantiate_fn,
             execute_fn: self.execute_fn,
@@ -335,14 +545,34 @@ where
             after_block_fn: self.after_block_fn,
             bank_execute_fn: Some(bank_execute_fn),
             bank_query_fn: self.bank_query_fn,
+            sudo_fn: self.sudo_fn,
         }
     }
 
     pub fn with_bank_query<E12A>(
         self,
         bank_query_fn: BankQueryFn<E12A>,
-    ) -> ContractBuilder<M1, E1, M2, M3, M5, M6, E2, E3, E4, E5, E6, E7, E8, E9, E10, E11, E12A>
-    {
+    ) -> ContractBuilder<
+        M1,
+        E1,
+        M2,
+        M3,
+        M5,
+        M6,
+        M7,
+        E2,
+        E3,
+        E4,
+        E5,
+        E6,
+        E7,
+        E8,
+        E9,
+        E10,
+        E11,
+        E12A,
+        E13,
+    > {
         ContractBuilder {
             instantiate_fn: self.instantiate_fn,
             execute_fn: self.execute_fn,
@@ -356,6 +586,52 @@ where
             after_block_fn: self.after_block_fn,
             bank_execute_fn: self.bank_execute_fn,
             bank_query_fn: Some(bank_query_fn),
+            sudo_fn: self.sudo_fn,
+        }
+    }
+
+    pub fn with_sudo<M7A, E13A>(
+        self,
+        sudo_fn: SudoFn<M7A, E13A>,
+    ) -> ContractBuilder<
+        M1,
+        E1,
+        M2,
+        M3,
+        M5,
+        M6,
+        M7A,
+        E2,
+        E3,
+        E4,
+        E5,
+        E6,
+        E7,
+        E8,
+        E9,
+        E10,
+        E11,
+        E12,
+        E13A,
+    >
+    where
+        M7A: DeserializeOwned + 'static,
+        E13A: ToString + 'static,
+    {
+        ContractBuilder {
+            instantiate_fn: self.instantiate_fn,
+            execute_fn: self.execute_fn,
+            migrate_fn: self.migrate_fn,
+            receive_fn: self.receive_fn,
+            reply_fn: self.reply_fn,
+            query_fn: self.query_fn,
+            before_tx_fn: self.before_tx_fn,
+            after_tx_fn: self.after_tx_fn,
+            before_block_fn: self.before_block_fn,
+            after_block_fn: self.after_block_fn,
+            bank_execute_fn: self.bank_execute_fn,
+            bank_query_fn: self.bank_query_fn,
+            sudo_fn: Some(sudo_fn),
         }
     }
 
@@ -375,6 +651,7 @@ where
             after_block_fn: self.after_block_fn,
             bank_execute_fn: self.bank_execute_fn,
             bank_query_fn: self.bank_query_fn,
+            sudo_fn: self.sudo_fn,
         }));
         ContractWrapper { index }
     }
@@ -382,7 +659,27 @@ where
 
 // ----------------------------------- impl ------------------------------------
 
-pub struct ContractImpl<M1, M2, M3, M5, M6, E1, E2, E3, E4, E5, E6, E7, E8, E9, E10, E11, E12> {
+pub struct ContractImpl<
+    M1,
+    M2,
+    M3,
+    M5,
+    M6,
+    M7,
+    E1,
+    E2,
+    E3,
+    E4,
+    E5,
+    E6,
+    E7,
+    E8,
+    E9,
+    E10,
+    E11,
+    E12,
+    E13,
+> {
     instantiate_fn: InstantiateFn<M1, E1>,
     execute_fn: Option<ExecuteFn<M2, E2>>,
     migrate_fn: Option<MigrateFn<M3, E3>>,
@@ -395,16 +692,18 @@ pub struct ContractImpl<M1, M2, M3, M5, M6, E1, E2, E3, E4, E5, E6, E7, E8, E9,
     after_block_fn: Option<AfterBlockFn<E10>>,
     bank_execute_fn: Option<BankExecuteFn<E11>>,
     bank_query_fn: Option<BankQueryFn<E12>>,
+    sudo_fn: Option<SudoFn<M7, E13>>,
 }
 
-impl<M1, M2, M3, M5, M6, E1, E2, E3, E4, E5, E6, E7, E8, E9, E10, E11, E12> Contract
-    for ContractImpl<M1, M2, M3, M5, M6, E1, E2, E3, E4, E5, E6, E7, E8, E9, E10, E11, E12>
+impl<M1, M2, M3, M5, M6, M7, E1, E2, E3, E4, E5, E6, E7, E8, E9, E10, E11, E12, E13> Contract
+    for ContractImpl<M1, M2, M3, M5, M6, M7, E1, E2, E3, E4, E5, E6, E7, E8, E9, E10, E11, E12, E13>
 where
     M1: DeserializeOwned,
     M2: DeserializeOwned,
     M3: DeserializeOwned,
     M5: DeserializeOwned,
     M6: DeserializeOwned,
+    M7: DeserializeOwned,
     E1: ToString,
     E2: ToString,
     E3: ToString,
@@ -417,7 +716,27 @@ where
     E10: ToString,
     E11: ToString,
     E12: ToString,
+    E13: ToString,
 {
+    fn has_entry_point(&self, name: &str) -> bool {
+        match name {
+            "instantiate" => true,
+            "execute" => self.execute_fn.is_some(),
+            "migrate" => self.migrate_fn.is_some(),
+            "receive" => self.receive_fn.is_some(),
+            "reply" => self.reply_fn.is_some(),
+            "query" => self.query_fn.is_some(),
+            "before_tx" => self.before_tx_fn.is_some(),
+            "after_tx" => self.after_tx_fn.is_some(),
+            "before_block" => self.before_block_fn.is_some(),
+            "after_block" => self.after_block_fn.is_some(),
+            "bank_execute" => self.bank_execute_fn.is_some(),
+            "bank_query" => self.bank_query_fn.is_some(),
+            "sudo" => self.sudo_fn.is_some(),
+            _ => false,
+        }
+    }
+
     fn instantiate(
         &self,
         ctx: Context,
@@ -565,4 +884,17 @@ where
         let immutable_ctx = make_immutable_ctx!(ctx, storage, api, querier);
         return_into_generic_result!(self.bank_query_fn.as_ref().unwrap()(immutable_ctx, msg))
     }
+
+    fn sudo(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        msg: Json,
+    ) -> GenericResult<Response> {
+        let sudo_ctx = make_sudo_ctx!(ctx, storage, api, querier);
+        let msg = unwrap_into_generic_result!(from_json_value(msg));
+        return_into_generic_result!(self.sudo_fn.as_ref().unwrap()(sudo_ctx, msg))
+    }
 }
@@ -1,7 +1,8 @@
+mod api;
 mod contract;
 mod error;
 #[rustfmt::skip]
 mod traits;
 mod vm;
 
-pub use {contract::*, error::*, traits::*, vm::*};
+pub use {api::*, contract::*, error::*, traits::*, vm::*};
@@ -9,6 +9,8 @@ use grug_types::{
 };
 
 pub trait Contract {
+    fn has_entry_point(&self, name: &str) -> bool;
+
     fn instantiate(
         &self,
         ctx: Context,
@@ -114,6 +116,15 @@ pub trait Contract {
         querier: &dyn Querier,
         msg: BankQuery,
     ) -> GenericResult<BankQueryResponse>;
+
+    fn sudo(
+        &self,
+        ctx: Context,
+        storage: &mut dyn Storage,
+        api: &dyn Api,
+        querier: &dyn Querier,
+        msg: Json,
+    ) -> GenericResult<Response>;
 }
 
 // Trait aliases are unstable:
@@ -143,3 +154,5 @@ pub type AfterBlockFn<E = StdError> = Box<dyn Fn(SudoCtx) -> Result<Response, E>
 pub type BankExecuteFn<E = StdError> = Box<dyn Fn(SudoCtx, BankMsg) -> Result<Response, E> + Send + Sync>;
 
 pub type BankQueryFn<E = StdError> = Box<dyn Fn(ImmutableCtx, BankQuery) -> Result<BankQueryResponse, E> + Send + Sync>;
+
+pub type SudoFn<M = Empty, E = StdError> = Box<dyn Fn(SudoCtx, M) -> Result<Response, E> + Send + Sync>;
@@ -1,7 +1,7 @@
 use {
-    crate::{ContractWrapper, VmError, VmResult, CONTRACTS},
-    grug_app::{GasTracker, Instance, QuerierProvider, StorageProvider, Vm},
-    grug_types::{from_json_slice, to_json_vec, Context, Hash, MockApi},
+    crate::{ContractWrapper, RustApi, VmError, VmResult, CONTRACTS},
+    grug_app::{GasTracker, Instance, QuerierProvider, StorageProvider, Vm, INTERFACE_VERSION},
+    grug_types::{from_json_slice, to_json_vec, Context, Hash},
 };
 
 macro_rules! get_contract {
@@ -35,21 +35,34 @@ impl Vm for RustVm {
         // (unlike Wasm VM where an FFI is involved).
         _storage_readonly: bool,
         querier: QuerierProvider<Self>,
-        // Rust VM doesn't support gas tracking, so we make no use of the
-        // provided `GasTracker`.
-        _gas_tracker: GasTracker,
+        // Rust VM doesn't meter gas inside contracts, so the `GasTracker` is
+        // only used to let contracts read the gas meter.
+        gas_tracker: GasTracker,
     ) -> VmResult<RustInstance> {
         Ok(RustInstance {
             storage,
             querier,
+            api: RustApi::new(gas_tracker),
             wrapper: ContractWrapper::from_bytes(code),
         })
     }
+
+    fn has_entry_point(&mut self, code: &[u8], _code_hash: &Hash, name: &str) -> VmResult<bool> {
+        let contract = get_contract!(ContractWrapper::from_bytes(code).index);
+        Ok(contract.has_entry_point(name))
+    }
+
+    // Rust contracts are compiled together with the host, so they always
+    // implement the same interface as the host does.
+    fn interface_version(&mut self, _code: &[u8], _code_hash: &Hash) -> VmResult<Option<u32>> {
+        Ok(Some(INTERFACE_VERSION))
+    }
 }
 
 pub struct RustInstance {
     storage: StorageProvider,
     querier: QuerierProvider<RustVm>,
+    api: RustApi,
     wrapper: ContractWrapper,
 }
 
@@ -60,17 +73,18 @@ impl Instance for RustInstance {
         let contract = get_contract!(self.wrapper.index);
         let out = match name {
             "receive" => {
-                let res = contract.receive(ctx.clone(), &mut self.storage, &MockApi, &self.querier);
+                let res =
+                    contract.receive(ctx.clone(), &mut self.storage, &self.api, &self.querier);
                 to_json_vec(&res)?
             },
             "before_block" => {
                 let res =
-                    contract.before_block(ctx.clone(), &mut self.storage, &MockApi, &self.querier);
+                    contract.before_block(ctx.clone(), &mut self.storage, &self.api, &self.querier);
                 to_json_vec(&res)?
             },
             "after_block" => {
                 let res =
-                    contract.after_block(ctx.clone(), &mut self.storage, &MockApi, &self.querier);
+                    contract.after_block(ctx.clone(), &mut self.storage, &self.api, &self.querier);
                 to_json_vec(&res)?
             },
             _ => {
@@ -94,7 +108,7 @@ impl Instance for RustInstance {
                 let res = contract.instantiate(
                     ctx.clone(),
                     &mut self.storage,
-                    &MockApi,
+                    &self.api,
                     &self.querier,
                     msg,
                 );
@@ -102,31 +116,46 @@ impl Instance for RustInstance {
             },
             "execute" => {
                 let msg = from_json_slice(param)?;
-                let res =
-                    contract.execute(ctx.clone(), &mut self.storage, &MockApi, &self.querier, msg);
+                let res = contract.execute(
+                    ctx.clone(),
+                    &mut self.storage,
+                    &self.api,
+                    &self.querier,
+                    msg,
+                );
                 to_json_vec(&res)?
             },
             "migrate" => {
                 let msg = from_json_slice(param)?;
-                let res =
-                    contract.migrate(ctx.clone(), &mut self.storage, &MockApi, &self.querier, msg);
+                let res = contract.migrate(
+                    ctx.clone(),
+                    &mut self.storage,
+                    &self.api,
+                    &self.querier,
+                    msg,
+                );
                 to_json_vec(&res)?
             },
             "query" => {
                 let msg = from_json_slice(param)?;
-                let res = contract.query(ctx.clone(), &self.storage, &MockApi, &self.querier, msg);
+                let res = contract.query(ctx.clone(), &self.storage, &self.api, &self.querier, msg);
                 to_json_vec(&res)?
             },
             "before_tx" => {
                 let tx = from_json_slice(param)?;
-                let res =
-                    contract.before_tx(ctx.clone(), &mut self.storage, &MockApi, &self.querier, tx);
+                let res = contract.before_tx(
+                    ctx.clone(),
+                    &mut self.storage,
+                    &self.api,
+                    &self.querier,
+                    tx,
+                );
                 to_json_vec(&res)?
             },
             "after_tx" => {
                 let tx = from_json_slice(param)?;
                 let res =
-                    contract.after_tx(ctx.clone(), &mut self.storage, &MockApi, &self.querier, tx);
+                    contract.after_tx(ctx.clone(), &mut self.storage, &self.api, &self.querier, tx);
                 to_json_vec(&res)?
             },
             "bank_execute" => {
@@ -134,7 +163,7 @@ impl Instance for RustInstance {
                 let res = contract.bank_execute(
                     ctx.clone(),
                     &mut self.storage,
-                    &MockApi,
+                    &self.api,
                     &self.querier,
                     msg,
                 );
@@ -143,7 +172,18 @@ impl Instance for RustInstance {
             "bank_query" => {
                 let msg = from_json_slice(param)?;
                 let res =
-                    contract.bank_query(ctx.clone(), &self.storage, &MockApi, &self.querier, msg);
+                    contract.bank_query(ctx.clone(), &self.storage, &self.api, &self.querier, msg);
+                to_json_vec(&res)?
+            },
+            "sudo" => {
+                let msg = from_json_slice(param)?;
+                let res = contract.sudo(
+                    ctx.clone(),
+                    &mut self.storage,
+                    &self.api,
+                    &self.querier,
+                    msg,
+                );
                 to_json_vec(&res)?
             },
             _ => {
@@ -175,7 +215,7 @@ impl Instance for RustInstance {
                 let res = contract.reply(
                     ctx.clone(),
                     &mut self.storage,
-                    &MockApi,
+                    &self.api,
                     &self.querier,
                     msg,
                     submsg_res,
@@ -1,10 +1,389 @@
 use {
-    grug_testing::TestBuilder,
-    grug_types::{Coins, Message, NonZero, NumberConst, Uint128},
+    grug_app::{
+        do_mint_direct, do_transfer, query_balance, query_balance_direct, query_supply, App,
+        AppError, Buffer, Db, GasTracker, Shared, CONFIG, CONTRACT_ADDRESS_KEY,
+    },
+    grug_bank::DirectBankStore,
+    grug_db_memory::MemDb,
+    grug_testing::{
+        mock_genesis_block, mock_genesis_state, TestAccount, TestBuilder, TestResult, TestSuite,
+        DEFAULT_CHAIN_ID,
+    },
+    grug_types::{
+        from_json_slice, hash, to_json_value, to_json_vec, Addr, Attribute, AuthCtx, BankAdminMsg,
+        BankMsg, BankQuery, BankQueryResponse, Binary, Coin, Coins, DenomMetadata, Empty, Event,
+        GasConfig, GenesisState, Hash, ImmutableCtx, Json, Message, MutableCtx, NonZero,
+        NumberConst, Op, Order, QueryRequest, Response, StdError, StdResult, Storage, SubMessage,
+        SubMsgResult, SudoCtx, Timestamp, Tx, Udec128, Uint128, GENESIS_SENDER, MAX_MEMO_LENGTH,
+        MAX_TRANSFER_OUTPUTS,
+    },
+    grug_vm_rust::{ContractBuilder, RustVm},
+    std::collections::{BTreeMap, BTreeSet},
 };
 
 const DENOM: &str = "ugrug";
 
+fn instantiate_noop(_ctx: MutableCtx, _msg: Json) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+fn instantiate_unreachable(_ctx: MutableCtx, _msg: Json) -> Result<Response, String> {
+    Err("instantiate should not be called".to_string())
+}
+
+fn instantiate_write_value(ctx: MutableCtx, _msg: Json) -> StdResult<Response> {
+    let storage = ctx.storage;
+    storage.write(b"value", b"hello");
+
+    Ok(Response::new())
+}
+
+fn instantiate_save_fee_collector(ctx: MutableCtx, fee_collector: Addr) -> StdResult<Response> {
+    let storage = ctx.storage;
+    storage.write(b"fee_collector", &to_json_vec(&fee_collector)?);
+
+    Ok(Response::new())
+}
+
+/// Instantiate a contract of the given code, with the salt `child`.
+fn instantiate_child(_ctx: MutableCtx, code_hash: Hash) -> StdResult<Response> {
+    Ok(Response::new().add_message(Message::instantiate(
+        code_hash,
+        &Empty {},
+        "child",
+        Coins::new_empty(),
+        None,
+    )?))
+}
+
+fn execute_self(ctx: MutableCtx, _msg: Empty) -> StdResult<Response> {
+    Ok(Response::new().add_message(Message::execute(
+        ctx.contract,
+        &Empty {},
+        Coins::new_empty(),
+    )?))
+}
+
+fn execute_fan_out(_ctx: MutableCtx, msg: (Addr, Vec<u32>)) -> StdResult<Response> {
+    let (target, numbers) = msg;
+    let msgs = numbers
+        .iter()
+        .map(|number| Message::execute(target.clone(), number, Coins::new_empty()))
+        .collect::<StdResult<Vec<_>>>()?;
+
+    Ok(Response::new().add_messages(msgs))
+}
+
+/// Read the gas meter before and after querying the contract's own balance the
+/// given number of times. Each query costs gas on the host side.
+fn execute_measure_gas(ctx: MutableCtx, times: u32) -> StdResult<Response> {
+    let before = ctx.api.gas_remaining()?;
+    for _ in 0..times {
+        ctx.querier
+            .query_balance(ctx.contract.clone(), DENOM.to_string())?;
+    }
+    let after = ctx.api.gas_remaining()?;
+
+    Ok(Response::new()
+        .add_attribute("before", before)
+        .add_attribute("after", after))
+}
+
+/// Count the call, then execute the first of the given contracts, passing on
+/// the rest of them. The last contract in the chain emits an attribute.
+fn execute_forward(ctx: MutableCtx, targets: Vec<Addr>) -> StdResult<Response> {
+    let hits = ctx
+        .storage
+        .read(b"hits")
+        .map(from_json_slice::<u32>)
+        .transpose()?
+        .unwrap_or(0);
+    ctx.storage.write(b"hits", &to_json_vec(&(hits + 1))?);
+
+    let Some((next, rest)) = targets.split_first() else {
+        return Ok(Response::new().add_attribute("reached", ctx.contract));
+    };
+
+    Ok(Response::new().add_message(Message::execute(
+        next.clone(),
+        &rest.to_vec(),
+        Coins::new_empty(),
+    )?))
+}
+
+fn execute_record_number(_ctx: MutableCtx, msg: u32) -> StdResult<Response> {
+    Ok(Response::new().add_attribute("number", msg))
+}
+
+/// Send the given amount of the chain's fee denom back to the caller. A real
+/// fee collector would of course only refund fees that the caller has paid.
+fn execute_refund_fee(ctx: MutableCtx, amount: Uint128) -> StdResult<Response> {
+    if amount.is_zero() {
+        return Ok(Response::new());
+    }
+
+    let fee_denom = ctx.querier.query_info()?.config.fee_denom;
+
+    Ok(Response::new().add_message(Message::transfer(
+        ctx.sender,
+        Coins::new_one(fee_denom, NonZero::new(amount)),
+    )?))
+}
+
+fn execute_respond_with_size(_ctx: MutableCtx, kilobytes: u32) -> StdResult<Response> {
+    Ok(Response::new().add_attribute("data", "a".repeat(kilobytes as usize * 1000)))
+}
+
+fn execute_emit_transfer_event(_ctx: MutableCtx, _msg: Empty) -> StdResult<Response> {
+    Ok(Response::new().add_event(Event::new("transfer").add_attribute("amount", 1_000_000)))
+}
+
+/// Execute the given contract, recovering from its failure with a reply.
+fn execute_recovering(_ctx: MutableCtx, target: Addr) -> StdResult<Response> {
+    Ok(Response::new().add_submessage(SubMessage::reply_on_error(
+        Message::execute(target, &Empty {}, Coins::new_empty())?,
+        &Empty {},
+    )?))
+}
+
+fn migrate_noop(_ctx: MutableCtx, _msg: Empty) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+fn reply_noop(_ctx: SudoCtx, _payload: Empty, _res: SubMsgResult) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+/// If `start` is true, execute the contract itself with a reply on success;
+/// otherwise, do nothing.
+fn execute_start_reply_loop(ctx: MutableCtx, start: bool) -> StdResult<Response> {
+    if !start {
+        return Ok(Response::new());
+    }
+
+    Ok(Response::new().add_submessage(SubMessage::reply_on_success(
+        Message::execute(ctx.contract, &false, Coins::new_empty())?,
+        &Empty {},
+    )?))
+}
+
+/// Upon each reply, execute the contract itself again with a reply on success,
+/// endlessly.
+fn reply_loop(ctx: SudoCtx, _payload: Empty, _res: SubMsgResult) -> StdResult<Response> {
+    Ok(Response::new().add_submessage(SubMessage::reply_on_success(
+        Message::execute(ctx.contract, &false, Coins::new_empty())?,
+        &Empty {},
+    )?))
+}
+
+fn sudo_record_number(_ctx: SudoCtx, msg: u32) -> StdResult<Response> {
+    Ok(Response::new().add_attribute("privileged", msg))
+}
+
+fn before_tx_noop(_ctx: AuthCtx, _tx: Tx) -> StdResult<Response> {
+    Ok(Response::new())
+}
+
+fn after_tx_record_outcome(ctx: AuthCtx, _tx: Tx) -> StdResult<Response> {
+    let outcome = if ctx.tx_outcome.unwrap().is_ok() {
+        "succeeded"
+    } else {
+        "failed"
+    };
+
+    let storage = ctx.storage;
+    storage.write(b"outcome", outcome.as_bytes());
+
+    Ok(Response::new())
+}
+
+fn after_tx_reject_failed(ctx: AuthCtx, _tx: Tx) -> Result<Response, String> {
+    if !ctx.tx_outcome.unwrap().is_ok() {
+        return Err("refusing to clean up after a failed tx".to_string());
+    }
+
+    Ok(Response::new())
+}
+
+/// Charge one unit of the chain's fee denom per unit of the gas limit, upfront.
+fn before_tx_pay_fee(ctx: AuthCtx, tx: Tx) -> StdResult<Response> {
+    let fee_collector = from_json_slice(ctx.storage.read(b"fee_collector").unwrap())?;
+    let fee_denom = ctx.querier.query_info()?.config.fee_denom;
+    let fee = Coins::new_one(fee_denom, NonZero::new(u128::from(tx.gas_limit)));
+
+    Ok(Response::new().add_message(Message::transfer(fee_collector, fee)?))
+}
+
+/// Claim the fee for the unused gas back from the fee collector.
+fn after_tx_claim_refund(ctx: AuthCtx, _tx: Tx) -> StdResult<Response> {
+    let gas_refund = ctx.tx_outcome.unwrap().gas_refund;
+    let fee_collector = from_json_slice(ctx.storage.read(b"fee_collector").unwrap())?;
+
+    let storage = ctx.storage;
+    storage.write(b"gas_refund", &to_json_vec(&gas_refund)?);
+
+    Ok(Response::new().add_message(Message::execute(
+        fee_collector,
+        &Uint128::new(gas_refund.into()),
+        Coins::new_empty(),
+    )?))
+}
+
+fn receive_record_deposit(ctx: MutableCtx) -> StdResult<Response> {
+    let deposit = to_json_vec(&(ctx.sender, ctx.funds))?;
+    let storage = ctx.storage;
+    storage.write(b"deposit", &deposit);
+
+    Ok(Response::new())
+}
+
+fn execute_count_then_fail(ctx: MutableCtx, _msg: Empty) -> StdResult<Response> {
+    ctx.storage.write(b"hits", &to_json_vec(&1_u32)?);
+
+    Err(StdError::generic_err("failing after counting the call"))
+}
+
+fn execute_paused(_ctx: MutableCtx, _msg: Empty) -> Result<Response, String> {
+    Err("vault is paused; try again after block 100".to_string())
+}
+
+fn bank_execute_unreachable(_ctx: SudoCtx, _msg: BankMsg) -> StdResult<Response> {
+    Err(StdError::generic_err("bank contract should not be called"))
+}
+
+fn bank_query_unreachable(_ctx: ImmutableCtx, _msg: BankQuery) -> StdResult<BankQueryResponse> {
+    Err(StdError::generic_err("bank contract should not be called"))
+}
+
+/// Encode the given data, then decode it back, using each of the encodings
+/// provided by the API.
+fn query_round_trip_encodings(ctx: ImmutableCtx, data: Binary) -> StdResult<Json> {
+    let base64 = ctx.api.encode_base64(&data);
+    let hex = ctx.api.encode_hex(&data);
+
+    to_json_value(&(
+        ctx.api.decode_base64(&base64)?,
+        base64,
+        ctx.api.decode_hex(&hex)?,
+        hex,
+    ))
+}
+
+/// Return the given JSON in the canonical form, as produced by the API.
+fn query_canonical_json(ctx: ImmutableCtx, json: String) -> StdResult<Json> {
+    let canonical = ctx.api.to_json_canonical(json.as_bytes())?;
+    to_json_value(&Binary::from(canonical))
+}
+
+fn query_chain_id(ctx: ImmutableCtx, _msg: Empty) -> StdResult<Json> {
+    to_json_value(&ctx.querier.query_chain_id()?)
+}
+
+fn query_block_timestamp(ctx: ImmutableCtx, _msg: Empty) -> StdResult<Json> {
+    to_json_value(&ctx.querier.query_block_info()?.timestamp)
+}
+
+fn query_self_address(ctx: ImmutableCtx, _msg: Empty) -> StdResult<Json> {
+    to_json_value(&ctx.contract)
+}
+
+fn query_is_contract(ctx: ImmutableCtx, address: Addr) -> StdResult<Json> {
+    to_json_value(&ctx.querier.is_contract(address)?)
+}
+
+fn query_recorded_outcome(ctx: ImmutableCtx, _msg: Empty) -> StdResult<Json> {
+    let outcome = ctx
+        .storage
+        .read(b"outcome")
+        .map(|bytes| String::from_utf8(bytes).unwrap());
+    to_json_value(&outcome)
+}
+
+fn query_recorded_deposit(ctx: ImmutableCtx, _msg: Empty) -> StdResult<Json> {
+    let deposit = ctx
+        .storage
+        .read(b"deposit")
+        .map(from_json_slice::<Json>)
+        .transpose()?;
+    to_json_value(&deposit)
+}
+
+fn query_recorded_gas_refund(ctx: ImmutableCtx, _msg: Empty) -> StdResult<Json> {
+    let gas_refund = ctx
+        .storage
+        .read(b"gas_refund")
+        .map(from_json_slice::<u64>)
+        .transpose()?;
+    to_json_value(&gas_refund)
+}
+
+fn query_hits(ctx: ImmutableCtx, _msg: Empty) -> StdResult<Json> {
+    let hits = ctx
+        .storage
+        .read(b"hits")
+        .map(from_json_slice::<u32>)
+        .transpose()?;
+    to_json_value(&hits.unwrap_or(0))
+}
+
+fn query_all_supplies(ctx: ImmutableCtx, _msg: Empty) -> StdResult<Json> {
+    to_json_value(&ctx.querier.query_all_supplies()?)
+}
+
+/// Read the given contract's storage raw. If it doesn't allow that, return the
+/// contract named by the error.
+fn query_raw_value(ctx: ImmutableCtx, contract: Addr) -> StdResult<Json> {
+    match ctx
+        .querier
+        .query_wasm_raw(contract, b"value".to_vec().into())
+    {
+        Ok(value) => to_json_value(&Ok::<_, Addr>(value)),
+        Err(StdError::RawQueryNotAllowed { contract }) => {
+            to_json_value(&Err::<Option<Binary>, _>(contract))
+        },
+        Err(err) => Err(err),
+    }
+}
+
+fn query_fail(_ctx: ImmutableCtx, _msg: Empty) -> StdResult<Json> {
+    Err(StdError::generic_err("nothing to see here"))
+}
+
+/// Query the given contract, and return the error it fails with.
+fn query_failure_of(ctx: ImmutableCtx, contract: Addr) -> StdResult<Json> {
+    match ctx.querier.query_wasm_smart::<_, Json>(contract, &Empty {}) {
+        Err(StdError::QueryFailed { contract, msg }) => to_json_value(&(contract, msg)),
+        res => Err(StdError::generic_err(format!(
+            "unexpected query result: {res:?}"
+        ))),
+    }
+}
+
+/// Instantiate a contract of the given code under the given salt, with the
+/// instantiate message's flags set by `with_flags`. Return its address.
+fn instantiate_with_flags(
+    suite: &mut TestSuite,
+    sender: &TestAccount,
+    code_hash: &Hash,
+    salt: &str,
+    admin: Option<Addr>,
+    with_flags: fn(Message) -> Message,
+) -> anyhow::Result<Addr> {
+    let msg = Message::instantiate(
+        code_hash.clone(),
+        &Empty {},
+        salt,
+        Coins::new_empty(),
+        admin,
+    )?;
+
+    suite
+        .execute_message(sender, with_flags(msg))?
+        .should_succeed()?;
+
+    Ok(Addr::compute(&sender.address, code_hash, salt.as_bytes()))
+}
+
 #[test]
 fn bank_transfers() -> anyhow::Result<()> {
     let (mut suite, accounts) = TestBuilder::new()
@@ -52,3 +431,2712 @@ fn bank_transfers() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn transferring_to_multiple_recipients() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account(
+            "sender",
+            Coins::try_from([
+                Coin::new("uatom", NonZero::new(100_u128)),
+                Coin::new("uosmo", NonZero::new(100_u128)),
+            ])?,
+        )?
+        .add_account("alice", Coins::new_empty())?
+        .add_account("bob", Coins::new_empty())?
+        .build()?;
+
+    let count_bank_calls = |events: &[Event]| {
+        events
+            .iter()
+            .filter(|event| event.r#type == "bank_execute")
+            .count()
+    };
+
+    // Two recipients getting different denoms, in a single call to the bank
+    // contract.
+    let events = suite
+        .execute_message(
+            &accounts["sender"],
+            Message::transfer_multi(vec![
+                (
+                    accounts["alice"].address.clone(),
+                    Coins::new_one("uatom", NonZero::new(10_u128)),
+                ),
+                (
+                    accounts["bob"].address.clone(),
+                    Coins::new_one("uosmo", NonZero::new(20_u128)),
+                ),
+            ]),
+        )?
+        .should_succeed()?;
+
+    assert_eq!(count_bank_calls(&events), 1);
+
+    suite
+        .query_balance(&accounts["alice"], "uatom")
+        .should_succeed_and_equal(Uint128::new(10))?;
+    suite
+        .query_balance(&accounts["alice"], "uosmo")
+        .should_succeed_and_equal(Uint128::ZERO)?;
+    suite
+        .query_balance(&accounts["bob"], "uatom")
+        .should_succeed_and_equal(Uint128::ZERO)?;
+    suite
+        .query_balance(&accounts["bob"], "uosmo")
+        .should_succeed_and_equal(Uint128::new(20))?;
+
+    // A duplicate recipient receives the sum of its outputs, in a single call
+    // to the bank contract.
+    let events = suite
+        .execute_message(
+            &accounts["sender"],
+            Message::transfer_multi(vec![
+                (
+                    accounts["alice"].address.clone(),
+                    Coins::new_one("uatom", NonZero::new(5_u128)),
+                ),
+                (
+                    accounts["alice"].address.clone(),
+                    Coins::new_one("uatom", NonZero::new(7_u128)),
+                ),
+            ]),
+        )?
+        .should_succeed()?;
+
+    assert_eq!(count_bank_calls(&events), 1);
+
+    suite
+        .query_balance(&accounts["alice"], "uatom")
+        .should_succeed_and_equal(Uint128::new(22))?;
+    suite
+        .query_balance(&accounts["sender"], "uatom")
+        .should_succeed_and_equal(Uint128::new(78))?;
+
+    // The sender can afford each output, but not all of them combined. Nothing
+    // is transferred.
+    let err = suite
+        .execute_message(
+            &accounts["sender"],
+            Message::transfer_multi(vec![
+                (
+                    accounts["alice"].address.clone(),
+                    Coins::new_one("uatom", NonZero::new(50_u128)),
+                ),
+                (
+                    accounts["bob"].address.clone(),
+                    Coins::new_one("uatom", NonZero::new(50_u128)),
+                ),
+            ]),
+        )?
+        .should_fail()?;
+    assert!(matches!(
+        err,
+        AppError::InsufficientFunds { denom, balance, amount, .. }
+            if denom == "uatom" && balance == Uint128::new(78) && amount == Uint128::new(100)
+    ));
+
+    suite
+        .query_balance(&accounts["alice"], "uatom")
+        .should_succeed_and_equal(Uint128::new(22))?;
+    suite
+        .query_balance(&accounts["bob"], "uatom")
+        .should_succeed_and_equal(Uint128::ZERO)?;
+
+    // The number of outputs is capped.
+    let outputs = vec![
+        (
+            accounts["alice"].address.clone(),
+            Coins::new_one("uatom", NonZero::new(1_u128)),
+        );
+        MAX_TRANSFER_OUTPUTS + 1
+    ];
+    suite
+        .execute_message(&accounts["sender"], Message::transfer_multi(outputs))?
+        .should_fail_with_error(format!(
+            "count: {}, max: {MAX_TRANSFER_OUTPUTS}",
+            MAX_TRANSFER_OUTPUTS + 1
+        ))?;
+
+    Ok(())
+}
+
+#[test]
+fn genesis_rejects_bank_without_entry_points() -> anyhow::Result<()> {
+    // A "bank" contract that can be instantiated, but exports neither of the
+    // `bank_execute` and `bank_query` entry points.
+    let bad_bank_code = ContractBuilder::new(Box::new(instantiate_noop))
+        .build()
+        .into_bytes();
+
+    let Err(err) = TestBuilder::new()
+        .set_bank_code(bad_bank_code.into())
+        .add_account("sender", Coins::new_one(DENOM, NonZero::new(100_u128)))?
+        .build()
+    else {
+        panic!("expecting genesis to fail, but it succeeded");
+    };
+
+    assert!(err.to_string().contains("bank_execute"));
+
+    Ok(())
+}
+
+#[test]
+fn querying_block_info() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("owner", Coins::new_empty())?
+        .build()?;
+
+    let code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_query(Box::new(query_block_timestamp))
+        .build()
+        .into_bytes();
+
+    let (_, contract) =
+        suite.upload_and_instantiate(&accounts["owner"], code.into(), "clock", &Empty {})?;
+
+    // The query should observe the timestamp of the last finalized block.
+    let block = suite.query_info().should_succeed()?.last_finalized_block;
+    suite
+        .query_wasm_smart::<_, Timestamp>(contract, &Empty {})
+        .should_succeed_and_equal(block.timestamp)?;
+
+    Ok(())
+}
+
+#[test]
+fn empty_transfer_is_noop() -> anyhow::Result<()> {
+    // A bank contract that errors on every call. If the transfer below were
+    // to reach the bank, the transaction would fail.
+    let bank_code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_bank_execute(Box::new(bank_execute_unreachable))
+        .with_bank_query(Box::new(bank_query_unreachable))
+        .build()
+        .into_bytes();
+
+    let (mut suite, accounts) = TestBuilder::new()
+        .set_bank_code(bank_code.into())
+        .add_account("sender", Coins::new_empty())?
+        .add_account("receiver", Coins::new_empty())?
+        .build()?;
+
+    suite
+        .execute_message(&accounts["sender"], Message::Transfer {
+            to: accounts["receiver"].address.clone(),
+            coins: Coins::new_empty(),
+        })?
+        .should_succeed()?;
+
+    Ok(())
+}
+
+#[test]
+fn encoding_round_trips() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("owner", Coins::new_empty())?
+        .build()?;
+
+    let code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_query(Box::new(query_round_trip_encodings))
+        .build()
+        .into_bytes();
+
+    let (_, contract) =
+        suite.upload_and_instantiate(&accounts["owner"], code.into(), "codecs", &Empty {})?;
+
+    let data = b"hello grug\x00\xff".to_vec();
+    suite
+        .query_wasm_smart::<_, (Vec<u8>, String, Vec<u8>, String)>(
+            contract,
+            &Binary::from(data.clone()),
+        )
+        .should_succeed_and_equal((
+            data.clone(),
+            "aGVsbG8gZ3J1ZwD/".to_string(),
+            data,
+            "68656c6c6f206772756700ff".to_string(),
+        ))?;
+
+    Ok(())
+}
+
+#[test]
+fn canonicalizing_json() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("owner", Coins::new_empty())?
+        .build()?;
+
+    let code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_query(Box::new(query_canonical_json))
+        .build()
+        .into_bytes();
+
+    let (_, contract) =
+        suite.upload_and_instantiate(&accounts["owner"], code.into(), "canonical", &Empty {})?;
+
+    // Logically equal objects, with keys in different orders and different
+    // whitespace, are canonicalized to the same bytes.
+    let expect = Binary::from(br#"{"a":{"c":null,"d":[{"x":1,"y":2}]},"b":1}"#.to_vec());
+    for json in [
+        r#"{"b":1,"a":{"d":[{"y":2,"x":1}],"c":null}}"#,
+        r#"{ "a": { "c": null, "d": [ { "x": 1, "y": 2 } ] }, "b": 1 }"#,
+    ] {
+        suite
+            .query_wasm_smart::<_, Binary>(contract.clone(), &json)
+            .should_succeed_and_equal(expect.clone())?;
+    }
+
+    // Invalid JSON is rejected.
+    suite
+        .query_wasm_smart::<_, Binary>(contract, &r#"{"a":"#)
+        .should_fail_with_error("failed to decode json string")?;
+
+    Ok(())
+}
+
+#[test]
+fn replaying_block() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new_one(DENOM, NonZero::new(100_u128)))?
+        .add_account("receiver", Coins::new_empty())?
+        .build()?;
+
+    let bank = suite.query_info().should_succeed()?.config.bank;
+
+    // Two transactions from the same sender. The second one can only succeed
+    // if it sees the sequence number increased by the first one.
+    let txs = [10_u128, 20]
+        .into_iter()
+        .enumerate()
+        .map(|(sequence, amount)| {
+            accounts["sender"].sign_transaction(
+                vec![Message::Transfer {
+                    to: accounts["receiver"].address.clone(),
+                    coins: Coins::new_one(DENOM, NonZero::new(amount)),
+                }],
+                0,
+                DEFAULT_CHAIN_ID,
+                sequence as u32,
+            )
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let diffs = suite.replay_block(txs.clone())?;
+    assert_eq!(diffs.len(), 2);
+
+    // Each tx should update the sender's sequence number, and the balances
+    // in the bank. Contract storage keys are prefixed with `wasm` followed by
+    // the contract address.
+    let expect = BTreeSet::from([accounts["sender"].address.clone(), bank]);
+    let mut final_changes = BTreeMap::new();
+    for (diff, tx) in diffs.into_iter().zip(&txs) {
+        // The tx is identified by the same hash as when it's delivered.
+        assert_eq!(diff.tx_hash, hash(to_json_vec(tx)?));
+        assert!(diff.result.is_ok());
+
+        for (key, op) in diff.changes {
+            let key = key.strip_prefix(b"wasm").unwrap();
+            let contract = Addr::try_from(&key[..Hash::LENGTH])?;
+            assert!(expect.contains(&contract));
+            final_changes.insert((contract, key[Hash::LENGTH..].to_vec()), op);
+        }
+    }
+
+    // Replaying doesn't change the state.
+    suite
+        .query_balance(&accounts["sender"], DENOM)
+        .should_succeed_and_equal(Uint128::new(100))?;
+
+    // Now deliver the txs for real. The resulting state should be exactly what
+    // the diffs describe, and the results should be found under the hashes
+    // the diffs reported.
+    for result in suite.deliver_txs(txs.clone())? {
+        result.should_succeed()?;
+    }
+
+    for ((contract, key), op) in final_changes {
+        let expect = match op {
+            Op::Insert(value) => Some(Binary::from(value)),
+            Op::Delete => None,
+        };
+        suite
+            .query_wasm_raw(contract, key.into())
+            .should_succeed_and_equal(expect)?;
+    }
+
+    for tx in &txs {
+        suite
+            .query_tx_result(hash(to_json_vec(tx)?))
+            .should_succeed()?;
+    }
+
+    suite
+        .query_balance(&accounts["receiver"], DENOM)
+        .should_succeed_and_equal(Uint128::new(30))?;
+
+    Ok(())
+}
+
+#[test]
+fn removing_unused_code() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("owner", Coins::new_empty())?
+        .add_account("stranger", Coins::new_empty())?
+        .set_owner("owner")
+        .build()?;
+
+    let unused_code = ContractBuilder::new(Box::new(instantiate_noop))
+        .build()
+        .into_bytes();
+    let unused_code_hash = suite.upload(&accounts["owner"], unused_code.clone().into())?;
+
+    let used_code = ContractBuilder::new(Box::new(instantiate_noop))
+        .build()
+        .into_bytes();
+    let (used_code_hash, contract) =
+        suite.upload_and_instantiate(&accounts["owner"], used_code.into(), "used", &Empty {})?;
+
+    // Only the owner can remove codes.
+    suite
+        .execute_message(
+            &accounts["stranger"],
+            Message::remove_code(unused_code_hash.clone()),
+        )?
+        .should_fail_with_error("sender is not the owner")?;
+
+    // A code used by a contract can't be removed. The error should list the
+    // contract.
+    suite
+        .execute_message(&accounts["owner"], Message::remove_code(used_code_hash))?
+        .should_fail_with_error(format!("{contract:?}"))?;
+
+    // A code not used by any contract can be removed.
+    suite
+        .execute_message(&accounts["owner"], Message::remove_code(unused_code_hash))?
+        .should_succeed()?;
+
+    // Now that the code is gone, it can be uploaded again.
+    suite.upload(&accounts["owner"], unused_code.into())?;
+
+    Ok(())
+}
+
+#[test]
+fn message_depth_is_limited() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new_empty())?
+        .set_max_msg_depth(5)
+        .build()?;
+
+    // A contract that executes itself upon being executed, endlessly.
+    let code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_execute(Box::new(execute_self))
+        .build()
+        .into_bytes();
+
+    let (_, contract) =
+        suite.upload_and_instantiate(&accounts["sender"], code.into(), "recursive", &Empty {})?;
+
+    // The depth guard should stop the recursion with an error, instead of
+    // overflowing the stack.
+    suite
+        .execute_message(
+            &accounts["sender"],
+            Message::execute(contract, &Empty {}, Coins::new_empty())?,
+        )?
+        .should_fail_with_error("max message depth exceeded! depth: 6, max: 5")?;
+
+    Ok(())
+}
+
+#[test]
+fn reply_depth_is_limited() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new_empty())?
+        .set_max_msg_depth(5)
+        .build()?;
+
+    // A contract whose every reply emits another submessage with a reply, so
+    // the chain of replies never ends by itself.
+    let code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_execute(Box::new(execute_start_reply_loop))
+        .with_reply(Box::new(reply_loop))
+        .build()
+        .into_bytes();
+
+    let (_, contract) =
+        suite.upload_and_instantiate(&accounts["sender"], code.into(), "replying", &Empty {})?;
+
+    // Each reply counts as one level deeper than the submessage it replies to,
+    // so the chain is stopped by the depth guard.
+    suite
+        .execute_message(
+            &accounts["sender"],
+            Message::execute(contract, &true, Coins::new_empty())?,
+        )?
+        .should_fail_with_error("max message depth exceeded! depth: 6, max: 5")?;
+
+    Ok(())
+}
+
+#[test]
+fn submessages_are_executed_in_order() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new_empty())?
+        .build()?;
+
+    let fan_out_code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_execute(Box::new(execute_fan_out))
+        .build()
+        .into_bytes();
+    let (_, fan_out) = suite.upload_and_instantiate(
+        &accounts["sender"],
+        fan_out_code.into(),
+        "fan_out",
+        &Empty {},
+    )?;
+
+    let recorder_code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_execute(Box::new(execute_record_number))
+        .build()
+        .into_bytes();
+    let (_, recorder) = suite.upload_and_instantiate(
+        &accounts["sender"],
+        recorder_code.into(),
+        "recorder",
+        &Empty {},
+    )?;
+
+    // Deliberately use an order that is neither ascending nor descending.
+    let numbers = vec![3, 1, 4, 2];
+    let events = suite
+        .execute_message(
+            &accounts["sender"],
+            Message::execute(fan_out, &(recorder, numbers.clone()), Coins::new_empty())?,
+        )?
+        .should_succeed()?;
+
+    let recorded = events
+        .iter()
+        .flat_map(|event| &event.attributes)
+        .filter(|attr| attr.key == "number")
+        .map(|attr| attr.value.parse())
+        .collect::<Result<Vec<u32>, _>>()?;
+    assert_eq!(recorded, numbers);
+
+    Ok(())
+}
+
+#[test]
+fn instantiate_can_emit_submessages() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new_empty())?
+        .build()?;
+
+    let child_code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_query(Box::new(query_self_address))
+        .build()
+        .into_bytes();
+    let child_code_hash = suite.upload(&accounts["sender"], child_code.into())?;
+
+    let parent_code = ContractBuilder::new(Box::new(instantiate_child))
+        .build()
+        .into_bytes();
+    let (_, parent) = suite.upload_and_instantiate(
+        &accounts["sender"],
+        parent_code.into(),
+        "parent",
+        &child_code_hash,
+    )?;
+
+    // The child is instantiated by the parent, so the parent is its deployer.
+    let child = Addr::compute(&parent, &child_code_hash, b"child");
+    suite
+        .query_wasm_smart::<_, Addr>(child.clone(), &Empty {})
+        .should_succeed_and_equal(child)?;
+
+    Ok(())
+}
+
+#[test]
+fn nested_execute_submessages() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new_empty())?
+        .build()?;
+
+    let code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_execute(Box::new(execute_forward))
+        .with_query(Box::new(query_hits))
+        .build()
+        .into_bytes();
+    let code_hash = suite.upload(&accounts["sender"], code.into())?;
+
+    let mut contracts = vec![];
+    for salt in ["a", "b", "c"] {
+        contracts.push(suite.instantiate(
+            &accounts["sender"],
+            code_hash.clone(),
+            salt,
+            &Empty {},
+        )?);
+    }
+    let [a, b, c] = contracts.try_into().unwrap();
+
+    // a -> b -> c, i.e. two nested levels of submessages.
+    let events = suite
+        .execute_message(
+            &accounts["sender"],
+            Message::execute(a.clone(), &vec![b.clone(), c.clone()], Coins::new_empty())?,
+        )?
+        .should_succeed()?;
+    assert!(events
+        .iter()
+        .flat_map(|event| &event.attributes)
+        .any(|attr| attr.key == "reached" && attr.value == c.to_string()));
+
+    // Each contract's state change has been committed.
+    for contract in [&a, &b, &c] {
+        suite
+            .query_wasm_smart::<_, u32>(contract.clone(), &Empty {})
+            .should_succeed_and_equal(1)?;
+    }
+
+    // a -> b -> c -> nonexistent. The innermost failure fails the whole call,
+    // and the state changes made along the way are discarded.
+    suite
+        .execute_message(
+            &accounts["sender"],
+            Message::execute(
+                a.clone(),
+                &vec![b.clone(), c.clone(), Addr::mock(123)],
+                Coins::new_empty(),
+            )?,
+        )?
+        .should_fail()?;
+
+    for contract in [a, b, c] {
+        suite
+            .query_wasm_smart::<_, u32>(contract, &Empty {})
+            .should_succeed_and_equal(1)?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn submessage_count_is_limited() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new_empty())?
+        .set_max_submsgs(3)
+        .build()?;
+
+    let fan_out_code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_execute(Box::new(execute_fan_out))
+        .build()
+        .into_bytes();
+    let (_, fan_out) = suite.upload_and_instantiate(
+        &accounts["sender"],
+        fan_out_code.into(),
+        "fan_out",
+        &Empty {},
+    )?;
+
+    let recorder_code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_execute(Box::new(execute_record_number))
+        .build()
+        .into_bytes();
+    let (_, recorder) = suite.upload_and_instantiate(
+        &accounts["sender"],
+        recorder_code.into(),
+        "recorder",
+        &Empty {},
+    )?;
+
+    // Up to the limit is fine.
+    suite
+        .execute_message(
+            &accounts["sender"],
+            Message::execute(
+                fan_out.clone(),
+                &(recorder.clone(), vec![1, 2, 3]),
+                Coins::new_empty(),
+            )?,
+        )?
+        .should_succeed()?;
+
+    // One more than the limit is rejected.
+    suite
+        .execute_message(
+            &accounts["sender"],
+            Message::execute(fan_out, &(recorder, vec![1, 2, 3, 4]), Coins::new_empty())?,
+        )?
+        .should_fail_with_error("too many submessages! count: 4, max: 3")?;
+
+    Ok(())
+}
+
+#[test]
+fn upload_event_includes_code_size() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new_empty())?
+        .build()?;
+
+    let code = ContractBuilder::new(Box::new(instantiate_noop))
+        .build()
+        .into_bytes();
+    let code_hash = hash(&code);
+
+    let events = suite
+        .execute_message(&accounts["sender"], Message::upload(code.clone()))?
+        .should_succeed()?;
+
+    let event = events
+        .into_iter()
+        .find(|event| event.r#type == "upload")
+        .expect("upload event not found");
+    assert_eq!(event.attributes, vec![
+        Attribute::new("code_hash", code_hash),
+        Attribute::new("code_size", code.len()),
+    ]);
+
+    Ok(())
+}
+
+#[test]
+fn exact_balance_distinguishes_spent_from_never_held() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("spender", Coins::new_one(DENOM, NonZero::new(100_u128)))?
+        .add_account("receiver", Coins::new_empty())?
+        .add_account("bystander", Coins::new_empty())?
+        .build()?;
+
+    // Spender sends away its entire balance
+    suite
+        .execute_message(&accounts["spender"], Message::Transfer {
+            to: accounts["receiver"].address.clone(),
+            coins: Coins::new_one(DENOM, NonZero::new(100_u128)),
+        })?
+        .should_succeed()?;
+
+    // The regular balance query can't tell the two accounts apart
+    suite
+        .query_balance(&accounts["spender"], DENOM)
+        .should_succeed_and_equal(Uint128::ZERO)?;
+    suite
+        .query_balance(&accounts["bystander"], DENOM)
+        .should_succeed_and_equal(Uint128::ZERO)?;
+
+    // The exact balance query can
+    suite
+        .query_balance_exact(&accounts["spender"], DENOM)
+        .should_succeed_and_equal(Some(Uint128::ZERO))?;
+    suite
+        .query_balance_exact(&accounts["bystander"], DENOM)
+        .should_succeed_and_equal(None)?;
+    suite
+        .query_balance_exact(&accounts["receiver"], DENOM)
+        .should_succeed_and_equal(Some(Uint128::new(100)))?;
+
+    Ok(())
+}
+
+#[test]
+fn transfer_cap_per_block() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new_one(DENOM, NonZero::new(300_u128)))?
+        .add_account("receiver", Coins::new_empty())?
+        .set_transfer_cap(DENOM, Uint128::new(100))
+        .build()?;
+
+    let transfer = |amount: u128| Message::Transfer {
+        to: accounts["receiver"].address.clone(),
+        coins: Coins::new_one(DENOM, NonZero::new(amount)),
+    };
+
+    // Transferring exactly the cap within one block is fine
+    suite
+        .execute_messages(&accounts["sender"], vec![transfer(60), transfer(40)])?
+        .should_succeed()?;
+
+    // Going over the cap within one block is rejected
+    suite
+        .execute_messages(&accounts["sender"], vec![transfer(60), transfer(50)])?
+        .should_fail_with_error("cap: 100, attempted: 110")?;
+
+    // The cap resets in the next block
+    suite
+        .execute_message(&accounts["sender"], transfer(100))?
+        .should_succeed()?;
+
+    suite
+        .query_balance(&accounts["receiver"], DENOM)
+        .should_succeed_and_equal(Uint128::new(200))?;
+
+    Ok(())
+}
+
+#[test]
+fn transfer_cap_is_shared_by_txs_in_a_block() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("alice", Coins::new_one(DENOM, NonZero::new(300_u128)))?
+        .add_account("bob", Coins::new_one(DENOM, NonZero::new(300_u128)))?
+        .add_account("receiver", Coins::new_empty())?
+        .set_transfer_cap(DENOM, Uint128::new(100))
+        .build()?;
+
+    let transfer = |sender: &str, amount: u128, sequence: u32| {
+        accounts[sender].sign_transaction(
+            vec![Message::Transfer {
+                to: accounts["receiver"].address.clone(),
+                coins: Coins::new_one(DENOM, NonZero::new(amount)),
+            }],
+            u64::MAX,
+            DEFAULT_CHAIN_ID,
+            sequence,
+        )
+    };
+
+    // Several transactions from different senders in one block count toward
+    // the same cap. The one that goes over it is rejected.
+    let mut results = suite
+        .deliver_txs(vec![
+            transfer("alice", 60, 0)?,
+            transfer("bob", 30, 0)?,
+            transfer("alice", 20, 1)?,
+        ])?
+        .into_iter();
+    results.next().unwrap().should_succeed()?;
+    results.next().unwrap().should_succeed()?;
+    results
+        .next()
+        .unwrap()
+        .should_fail_with_error("cap: 100, attempted: 110")?;
+
+    // The tally from the previous block is gone, so the full cap is available
+    // again, even to transactions split across senders.
+    let results = suite.deliver_txs(vec![transfer("alice", 50, 2)?, transfer("bob", 50, 1)?])?;
+    for result in results {
+        result.should_succeed()?;
+    }
+
+    suite
+        .query_balance(&accounts["receiver"], DENOM)
+        .should_succeed_and_equal(Uint128::new(190))?;
+
+    Ok(())
+}
+
+#[test]
+fn querying_all_supplies() -> anyhow::Result<()> {
+    // More denoms than fit in a single page, so that the querier has to make
+    // multiple paginated queries.
+    let alice_balances = (0..45)
+        .map(|i| (format!("denom{i:02}"), Uint128::new(i + 1)))
+        .collect::<BTreeMap<_, _>>();
+    let bob_balances = (0..10)
+        .map(|i| (format!("denom{i:02}"), Uint128::new(100)))
+        .collect::<BTreeMap<_, _>>();
+
+    let mut supplies = alice_balances.clone();
+    for (denom, amount) in &bob_balances {
+        *supplies.get_mut(denom).unwrap() += *amount;
+    }
+
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("alice", Coins::try_from(alice_balances)?)?
+        .add_account("bob", Coins::try_from(bob_balances)?)?
+        .build()?;
+
+    let code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_query(Box::new(query_all_supplies))
+        .build()
+        .into_bytes();
+
+    let (_, contract) =
+        suite.upload_and_instantiate(&accounts["alice"], code.into(), "supplies", &Empty {})?;
+
+    suite
+        .query_wasm_smart::<_, Coins>(contract, &Empty {})
+        .should_succeed_and_equal(Coins::try_from(supplies)?)?;
+
+    Ok(())
+}
+
+#[test]
+fn paginating_supplies() -> anyhow::Result<()> {
+    let balances = Coins::try_from([
+        Coin::new("uosmo", NonZero::new(4_u128)),
+        Coin::new("uatom", NonZero::new(1_u128)),
+        Coin::new("uusdc", NonZero::new(3_u128)),
+        Coin::new("umars", NonZero::new(2_u128)),
+    ])?;
+
+    let (suite, _) = TestBuilder::new()
+        .add_account("holder", balances)?
+        .build()?;
+
+    let first = suite.query_supplies(None, Some(2), None).should_succeed()?;
+    assert_eq!(first.next_key.as_deref(), Some("umars"));
+
+    let second = suite
+        .query_supplies(first.next_key, Some(2), None)
+        .should_succeed()?;
+    assert_eq!(second.next_key, None);
+
+    // The two pages cover all denoms, in order, without overlap.
+    let denoms = first
+        .supplies
+        .into_iter()
+        .chain(second.supplies)
+        .map(|coin| coin.denom)
+        .collect::<Vec<_>>();
+    assert_eq!(denoms, ["uatom", "umars", "uosmo", "uusdc"]);
+
+    // A zero limit still returns one supply, so that `next_key` doesn't
+    // wrongly signal the end.
+    let page = suite.query_supplies(None, Some(0), None).should_succeed()?;
+    assert_eq!(page.supplies.len(), 1);
+    assert_eq!(page.next_key.as_deref(), Some("uatom"));
+
+    Ok(())
+}
+
+#[test]
+fn paginating_supplies_caps_the_limit() -> anyhow::Result<()> {
+    // A single account can't hold more than `Coins::MAX_LEN` denoms at
+    // genesis, so split them between two.
+    let balances = |range: std::ops::Range<usize>| {
+        Coins::try_from(
+            range
+                .map(|i| Coin::new(format!("denom{i:04}"), NonZero::new(1_u128)))
+                .collect::<Vec<_>>(),
+        )
+    };
+
+    let (suite, _) = TestBuilder::new()
+        .add_account("alice", balances(0..Coins::MAX_LEN)?)?
+        .add_account("bob", balances(Coins::MAX_LEN..Coins::MAX_LEN + 10)?)?
+        .build()?;
+
+    // A page is capped to `Coins::MAX_LEN` supplies, however high the limit.
+    let page = suite
+        .query_supplies(None, Some(u32::MAX), None)
+        .should_succeed()?;
+    assert_eq!(page.supplies.len(), Coins::MAX_LEN);
+    assert_eq!(
+        page.next_key,
+        Some(format!("denom{:04}", Coins::MAX_LEN - 1))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn paginating_balances_in_descending_order() -> anyhow::Result<()> {
+    // `Order` is serialized as a snake_case string, both on its own and as
+    // part of a query.
+    assert_eq!(to_json_vec(&Order::Descending)?, br#""descending""#);
+    assert_eq!(
+        from_json_slice::<Order>(br#""ascending""#)?,
+        Order::Ascending
+    );
+
+    let balances = Coins::try_from([
+        Coin::new("uatom", NonZero::new(1_u128)),
+        Coin::new("umars", NonZero::new(2_u128)),
+        Coin::new("uosmo", NonZero::new(3_u128)),
+        Coin::new("uusdc", NonZero::new(4_u128)),
+    ])?;
+
+    let (suite, accounts) = TestBuilder::new()
+        .add_account("holder", balances)?
+        .build()?;
+
+    // In descending order, the page starts from the largest denom. The coins
+    // within the page are still sorted in ascending order, as a `Coins` is.
+    let page = suite
+        .query_balances(&accounts["holder"], None, Some(2), Some(Order::Descending))
+        .should_succeed()?;
+    let denoms = page.into_iter().map(|coin| coin.denom).collect::<Vec<_>>();
+    assert_eq!(denoms, ["uosmo", "uusdc"]);
+
+    // `start_after` is then an exclusive upper bound.
+    suite
+        .query_balances(
+            &accounts["holder"],
+            Some("uosmo".to_string()),
+            Some(2),
+            Some(Order::Descending),
+        )
+        .should_succeed_and_equal(Coins::try_from([
+            Coin::new("uatom", NonZero::new(1_u128)),
+            Coin::new("umars", NonZero::new(2_u128)),
+        ])?)?;
+
+    // Without an order, the query is ascending, as before.
+    suite
+        .query_balances(&accounts["holder"], None, Some(2), None)
+        .should_succeed_and_equal(Coins::try_from([
+            Coin::new("uatom", NonZero::new(1_u128)),
+            Coin::new("umars", NonZero::new(2_u128)),
+        ])?)?;
+
+    Ok(())
+}
+
+#[test]
+fn tx_memo() -> anyhow::Result<()> {
+    let (suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new_one(DENOM, NonZero::new(100_u128)))?
+        .add_account("receiver", Coins::new_empty())?
+        .build()?;
+
+    let sign = |memo: String| {
+        accounts["sender"].sign_transaction_with_memo(
+            vec![Message::Transfer {
+                to: accounts["receiver"].address.clone(),
+                coins: Coins::new_one(DENOM, NonZero::new(10_u128)),
+            }],
+            0,
+            DEFAULT_CHAIN_ID,
+            0,
+            Some(memo),
+        )
+    };
+
+    // A tx with a memo of acceptable length passes the check, and the memo is
+    // included in its events.
+    let mut tx = sign("order 12345".to_string())?;
+    suite.check_tx(&tx).should_succeed()?;
+
+    let mut diffs = suite.replay_block(vec![tx.clone()])?;
+    let events = diffs.pop().unwrap().result?;
+    let memo = events
+        .into_iter()
+        .find(|event| event.r#type == "memo")
+        .expect("memo event not found");
+    assert_eq!(memo.attributes, vec![Attribute::new("memo", "order 12345")]);
+
+    // The memo is signed, so it can't be altered after signing.
+    tx.memo = Some("order 67890".to_string());
+    let mut diffs = suite.replay_block(vec![tx])?;
+    TestResult::from(diffs.pop().unwrap().result)
+        .should_fail_with_error("signature verification failed")?;
+
+    // A memo that is too long is rejected, both when checking the tx and when
+    // it's included in a block without having been checked.
+    let tx = sign("a".repeat(MAX_MEMO_LENGTH + 1))?;
+    suite
+        .check_tx(&tx)
+        .should_fail_with_error(format!("length: {}", MAX_MEMO_LENGTH + 1))?;
+
+    let mut diffs = suite.replay_block(vec![tx])?;
+    TestResult::from(diffs.pop().unwrap().result)
+        .should_fail_with_error(format!("length: {}", MAX_MEMO_LENGTH + 1))?;
+
+    Ok(())
+}
+
+#[test]
+fn contracts_can_read_gas_remaining() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new_empty())?
+        .build()?;
+
+    let code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_execute(Box::new(execute_measure_gas))
+        .build()
+        .into_bytes();
+    let (_, contract) =
+        suite.upload_and_instantiate(&accounts["sender"], code.into(), "gas", &Empty {})?;
+
+    // The gas meter can't be read when the transaction is executed for real.
+    suite
+        .execute_message_with_gas(
+            &accounts["sender"],
+            1_000_000,
+            Message::execute(contract.clone(), &10_u32, Coins::new_empty())?,
+        )?
+        .should_fail_with_error("`gas_remaining` is only available in simulation")?;
+
+    // It can be read in simulation, where there is no gas limit.
+    let events = suite
+        .simulate_messages(&accounts["sender"], vec![Message::execute(
+            contract,
+            &10_u32,
+            Coins::new_empty(),
+        )?])
+        .should_succeed()?
+        .events;
+
+    let read = |key: &str| -> anyhow::Result<u64> {
+        let attr = events
+            .iter()
+            .flat_map(|event| &event.attributes)
+            .find(|attr| attr.key == key)
+            .ok_or_else(|| anyhow::anyhow!("attribute `{key}` not found"))?;
+        Ok(attr.value.parse()?)
+    };
+
+    let before = read("before")?;
+    let after = read("after")?;
+    assert!(before < u64::MAX);
+    assert!(after < before);
+
+    Ok(())
+}
+
+#[test]
+fn tx_results_are_indexed_by_hash() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new_one(DENOM, NonZero::new(100_u128)))?
+        .add_account("receiver", Coins::new_empty())?
+        .build()?;
+
+    let tx = accounts["sender"].sign_transaction(
+        vec![Message::Transfer {
+            to: accounts["receiver"].address.clone(),
+            coins: Coins::new_one(DENOM, NonZero::new(10_u128)),
+        }],
+        u64::MAX,
+        DEFAULT_CHAIN_ID,
+        0,
+    )?;
+
+    let events = suite.deliver_tx(tx.clone())?.should_succeed()?;
+
+    // The result is stored under the hash of the tx's JSON encoding.
+    let tx_hash = hash(to_json_vec(&tx)?);
+    let result = suite.query_tx_result(tx_hash).should_succeed()?;
+    assert!(result.is_ok());
+    assert_eq!(result.height, 1);
+    assert_eq!(result.events, events);
+    assert!(result.gas_used > 0);
+
+    // Querying a hash that hasn't been indexed fails.
+    suite
+        .query_tx_result(Hash::from_slice([0; 32]))
+        .should_fail_with_error("data not found!")?;
+
+    Ok(())
+}
+
+#[test]
+fn tx_results_are_pruned_after_the_retention_window() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new_one(DENOM, NonZero::new(100_u128)))?
+        .add_account("receiver", Coins::new_empty())?
+        .set_tx_result_retention(2)
+        .build()?;
+
+    // Deliver one tx in each of blocks 1, 2, and 3.
+    let mut tx_hashes = vec![];
+    for sequence in 0..3 {
+        let tx = accounts["sender"].sign_transaction(
+            vec![Message::Transfer {
+                to: accounts["receiver"].address.clone(),
+                coins: Coins::new_one(DENOM, NonZero::new(10_u128)),
+            }],
+            u64::MAX,
+            DEFAULT_CHAIN_ID,
+            sequence,
+        )?;
+        suite.deliver_tx(tx.clone())?.should_succeed()?;
+        tx_hashes.push(hash(to_json_vec(&tx)?));
+    }
+
+    // Only the results of the two most recent blocks are kept.
+    suite
+        .query_tx_result(tx_hashes[0].clone())
+        .should_fail_with_error("data not found!")?;
+    for (tx_hash, height) in tx_hashes[1..].iter().zip([2, 3]) {
+        let result = suite.query_tx_result(tx_hash.clone()).should_succeed()?;
+        assert_eq!(result.height, height);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn apps_with_different_namespaces_are_isolated() -> anyhow::Result<()> {
+    // Two apps sharing the same backing store.
+    let db = MemDb::new();
+    let gas_adjustment = Udec128::new(Uint128::ONE);
+    let app_a = App::new(db.clone(), RustVm::new(), None, gas_adjustment).with_namespace("a");
+    let app_b = App::new(db, RustVm::new(), None, gas_adjustment).with_namespace("b");
+
+    // Run genesis for each app with its own config, and with the bank
+    // contract holding a different balance for the same address.
+    let holder = Addr::mock(1);
+    let genesis_state = |fee_denom: &str, amount: u128| -> anyhow::Result<GenesisState> {
+        let mut genesis_state = mock_genesis_state::<RustVm>(BTreeMap::from([(
+            holder.clone(),
+            Coins::new_one(DENOM, NonZero::new(amount)),
+        )]))?;
+        genesis_state.config.fee_denom = fee_denom.to_string();
+        Ok(genesis_state)
+    };
+    app_a.do_init_chain(
+        DEFAULT_CHAIN_ID.to_string(),
+        mock_genesis_block(),
+        genesis_state("ufoo", 100)?,
+    )?;
+    app_b.do_init_chain(
+        DEFAULT_CHAIN_ID.to_string(),
+        mock_genesis_block(),
+        genesis_state("ubar", 200)?,
+    )?;
+
+    // Each app sees its own config and its own bank contract's storage, even
+    // though the bank contract has the same address in both.
+    for (app, fee_denom, amount) in [(&app_a, "ufoo", 100), (&app_b, "ubar", 200)] {
+        let info = app.do_query_app(QueryRequest::Info {}, 0, false)?;
+        assert_eq!(info.as_info().config.fee_denom, fee_denom);
+
+        let balance = app.do_query_app(
+            QueryRequest::Balance {
+                address: holder.clone(),
+                denom: DENOM.to_string(),
+            },
+            0,
+            false,
+        )?;
+        assert_eq!(balance.as_balance().amount, Uint128::new(amount));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn contract_events_are_prefixed() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new_empty())?
+        .build()?;
+
+    let code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_execute(Box::new(execute_emit_transfer_event))
+        .build()
+        .into_bytes();
+
+    let (_, contract) =
+        suite.upload_and_instantiate(&accounts["sender"], code.into(), "spoofer", &Empty {})?;
+
+    let events = suite
+        .execute_message(
+            &accounts["sender"],
+            Message::execute(contract.clone(), &Empty {}, Coins::new_empty())?,
+        )?
+        .should_succeed()?;
+
+    // The contract's `transfer` event must not be passed through as is, since
+    // it could be mistaken for a real transfer.
+    assert!(events.iter().all(|event| event.r#type != "transfer"));
+
+    let event = events
+        .into_iter()
+        .find(|event| event.r#type == "wasm-transfer")
+        .expect("prefixed event not found");
+    assert_eq!(event.attributes, vec![
+        Attribute::new(CONTRACT_ADDRESS_KEY, contract),
+        Attribute::new("amount", 1_000_000),
+    ]);
+
+    Ok(())
+}
+
+#[test]
+fn instantiating_with_sequential_addresses() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new_empty())?
+        .build()?;
+
+    let code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_query(Box::new(query_self_address))
+        .build()
+        .into_bytes();
+
+    let code_hash = suite.upload(&accounts["sender"], code.into())?;
+
+    for _ in 0..2 {
+        suite
+            .execute_message(
+                &accounts["sender"],
+                Message::instantiate_sequential(
+                    code_hash.clone(),
+                    &Empty {},
+                    Coins::new_empty(),
+                    None,
+                )?,
+            )?
+            .should_succeed()?;
+    }
+
+    // The two contracts should be found at the addresses derived from counter
+    // values 1 and 2, respectively.
+    for counter in [1, 2] {
+        let address = Addr::compute_sequential(counter);
+        suite
+            .query_wasm_smart::<_, Addr>(address.clone(), &Empty {})
+            .should_succeed_and_equal(address)?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn querying_whether_address_is_contract() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new_empty())?
+        .build()?;
+
+    let code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_query(Box::new(query_is_contract))
+        .build()
+        .into_bytes();
+
+    let (_, contract) =
+        suite.upload_and_instantiate(&accounts["sender"], code.into(), "checker", &Empty {})?;
+
+    suite
+        .query_wasm_smart::<_, bool>(contract.clone(), &contract)
+        .should_succeed_and_equal(true)?;
+
+    // Nothing has been instantiated at this address.
+    suite
+        .query_wasm_smart::<_, bool>(contract, &Addr::mock(123))
+        .should_succeed_and_equal(false)?;
+
+    Ok(())
+}
+
+#[test]
+fn after_tx_observes_message_outcome() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("owner", Coins::new_empty())?
+        .build()?;
+
+    // An account contract that doesn't authenticate transactions, and records
+    // in `after_tx` whether the messages succeeded.
+    let code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_execute(Box::new(execute_record_number))
+        .with_query(Box::new(query_recorded_outcome))
+        .with_before_tx(Box::new(before_tx_noop))
+        .with_after_tx(Box::new(after_tx_record_outcome))
+        .build()
+        .into_bytes();
+
+    let (code_hash, contract) =
+        suite.upload_and_instantiate(&accounts["owner"], code.into(), "account", &Empty {})?;
+
+    // Send transactions from the contract. As it doesn't verify signatures,
+    // the signing key doesn't matter.
+    let mut sender = TestAccount::new_random(&code_hash, b"account");
+    sender.address = contract.clone();
+
+    suite
+        .execute_message(
+            &sender,
+            Message::execute(contract.clone(), &123_u32, Coins::new_empty())?,
+        )?
+        .should_succeed()?;
+
+    suite
+        .query_wasm_smart::<_, Option<String>>(contract.clone(), &Empty {})
+        .should_succeed_and_equal(Some("succeeded".to_string()))?;
+
+    // The message fails, because the contract expects a number. The tx fails,
+    // but the changes made by `after_tx` are still persisted.
+    suite
+        .execute_message(
+            &sender,
+            Message::execute(contract.clone(), &Empty {}, Coins::new_empty())?,
+        )?
+        .should_fail()?;
+
+    suite
+        .query_wasm_smart::<_, Option<String>>(contract, &Empty {})
+        .should_succeed_and_equal(Some("failed".to_string()))?;
+
+    Ok(())
+}
+
+#[test]
+fn after_tx_error_is_reported_when_messages_fail() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("owner", Coins::new_empty())?
+        .build()?;
+
+    // An account contract that doesn't authenticate transactions, and errors
+    // in `after_tx` if the messages failed.
+    let code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_execute(Box::new(execute_record_number))
+        .with_before_tx(Box::new(before_tx_noop))
+        .with_after_tx(Box::new(after_tx_reject_failed))
+        .build()
+        .into_bytes();
+
+    let (code_hash, contract) =
+        suite.upload_and_instantiate(&accounts["owner"], code.into(), "account", &Empty {})?;
+
+    let mut sender = TestAccount::new_random(&code_hash, b"account");
+    sender.address = contract.clone();
+
+    // The message fails, because the contract expects a number. The tx fails
+    // with both the message's error and `after_tx`'s.
+    let err = suite
+        .execute_message(
+            &sender,
+            Message::execute(contract, &Empty {}, Coins::new_empty())?,
+        )?
+        .should_fail()?;
+
+    let AppError::AfterTxFailed {
+        msgs_error,
+        after_tx_error,
+    } = err
+    else {
+        anyhow::bail!("expecting `AfterTxFailed` error, got: {err}");
+    };
+    assert!(msgs_error.to_string().contains("invalid type"));
+    assert!(after_tx_error
+        .to_string()
+        .contains("refusing to clean up after a failed tx"));
+
+    Ok(())
+}
+
+#[test]
+fn unused_gas_is_refunded() -> anyhow::Result<()> {
+    // Host operations are free, so that the Rust VM consumes no gas at all,
+    // and the refund can be computed exactly.
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("owner", Coins::new_one(DENOM, NonZero::new(1_000_000_u128)))?
+        .set_fee_denom(DENOM)
+        .set_gas_refund_ratio(Udec128::new_percent(50_u128))
+        .set_gas_config(GasConfig {
+            json_codec_per_byte: 0,
+            gas_remaining: 0,
+            upload_per_byte: 0,
+            encoding_per_byte: 0,
+            after_tx_reserve: 10_000,
+        })
+        .build()?;
+
+    let fee_collector_code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_execute(Box::new(execute_refund_fee))
+        .build()
+        .into_bytes();
+
+    let (_, fee_collector) = suite.upload_and_instantiate(
+        &accounts["owner"],
+        fee_collector_code.into(),
+        "fee_collector",
+        &Empty {},
+    )?;
+
+    // An account contract that doesn't authenticate transactions, pays the
+    // fee for the full gas limit in `before_tx`, and claims the refund in
+    // `after_tx`.
+    let account_code = ContractBuilder::new(Box::new(instantiate_save_fee_collector))
+        .with_execute(Box::new(execute_record_number))
+        .with_query(Box::new(query_recorded_gas_refund))
+        .with_before_tx(Box::new(before_tx_pay_fee))
+        .with_after_tx(Box::new(after_tx_claim_refund))
+        .build()
+        .into_bytes();
+
+    let (code_hash, contract) = suite.upload_and_instantiate(
+        &accounts["owner"],
+        account_code.into(),
+        "account",
+        &fee_collector,
+    )?;
+
+    suite
+        .execute_message(
+            &accounts["owner"],
+            Message::transfer(
+                contract.clone(),
+                Coins::new_one(DENOM, NonZero::new(500_000_u128)),
+            )?,
+        )?
+        .should_succeed()?;
+
+    let mut sender = TestAccount::new_random(&code_hash, b"account");
+    sender.address = contract.clone();
+
+    let gas_limit = 100_000;
+    suite
+        .execute_message_with_gas(
+            &sender,
+            gas_limit,
+            Message::execute(contract.clone(), &123_u32, Coins::new_empty())?,
+        )?
+        .should_succeed()?;
+
+    // Half of the unused gas is refunded, except for that reserved for
+    // `after_tx`: (100,000 - 10,000) * 50% = 45,000.
+    let gas_refund = 45_000;
+    suite
+        .query_wasm_smart::<_, Option<u64>>(contract.clone(), &Empty {})
+        .should_succeed_and_equal(Some(gas_refund))?;
+
+    // The sender paid the fee for the full gas limit, then got the refund
+    // credited back.
+    suite
+        .query_balance(&sender, DENOM)
+        .should_succeed_and_equal(Uint128::new(
+            500_000 - u128::from(gas_limit) + u128::from(gas_refund),
+        ))?;
+
+    let mut collector = TestAccount::new_random(&code_hash, b"fee_collector");
+    collector.address = fee_collector;
+
+    suite
+        .query_balance(&collector, DENOM)
+        .should_succeed_and_equal(Uint128::new(u128::from(gas_limit - gas_refund)))?;
+
+    // A refund ratio greater than one is rejected.
+    let Err(err) = TestBuilder::new()
+        .set_gas_refund_ratio(Udec128::new_percent(150_u128))
+        .build()
+    else {
+        panic!("expected genesis to fail");
+    };
+    assert!(err.to_string().contains("gas refund ratio"));
+
+    Ok(())
+}
+
+#[test]
+fn receive_hook_is_opt_in() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new_one(DENOM, NonZero::new(100_u128)))?
+        .build()?;
+
+    let code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_receive(Box::new(receive_record_deposit))
+        .with_query(Box::new(query_recorded_deposit))
+        .build()
+        .into_bytes();
+
+    let code_hash = suite.upload(&accounts["sender"], code.into())?;
+
+    // The vault opts into the receive hook; the other contract doesn't.
+    let sender = &accounts["sender"];
+    let contracts = [
+        instantiate_with_flags(&mut suite, sender, &code_hash, "vault", None, |msg| {
+            msg.with_receive_hook()
+        })?,
+        instantiate_with_flags(&mut suite, sender, &code_hash, "plain", None, |msg| msg)?,
+    ];
+
+    for contract in &contracts {
+        suite
+            .execute_message(
+                &accounts["sender"],
+                Message::transfer(
+                    contract.clone(),
+                    Coins::new_one(DENOM, NonZero::new(30_u128)),
+                )?,
+            )?
+            .should_succeed()?;
+    }
+
+    // The vault's hook records the sender and amount.
+    suite
+        .query_wasm_smart::<_, Option<(Addr, Coins)>>(contracts[0].clone(), &Empty {})
+        .should_succeed_and_equal(Some((
+            accounts["sender"].address.clone(),
+            Coins::new_one(DENOM, NonZero::new(30_u128)),
+        )))?;
+
+    // The other contract is credited without its hook being called.
+    suite
+        .query_wasm_smart::<_, Option<(Addr, Coins)>>(contracts[1].clone(), &Empty {})
+        .should_succeed_and_equal(None)?;
+
+    Ok(())
+}
+
+#[test]
+fn contract_error_message_is_preserved() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new_empty())?
+        .build()?;
+
+    let code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_execute(Box::new(execute_paused))
+        .build()
+        .into_bytes();
+
+    let (_, contract) =
+        suite.upload_and_instantiate(&accounts["sender"], code.into(), "vault", &Empty {})?;
+
+    let err = suite
+        .execute_message(
+            &accounts["sender"],
+            Message::execute(contract, &Empty {}, Coins::new_empty())?,
+        )?
+        .should_fail()?;
+
+    assert!(matches!(
+        err,
+        AppError::Contract { msg } if msg == "vault is paused; try again after block 100"
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn larger_responses_consume_more_gas() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new_empty())?
+        .build()?;
+
+    let code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_execute(Box::new(execute_respond_with_size))
+        .build()
+        .into_bytes();
+
+    let (_, contract) =
+        suite.upload_and_instantiate(&accounts["sender"], code.into(), "responder", &Empty {})?;
+
+    let simulate = |kilobytes: u32| -> anyhow::Result<u64> {
+        let outcome = suite
+            .simulate_messages(&accounts["sender"], vec![Message::execute(
+                contract.clone(),
+                &kilobytes,
+                Coins::new_empty(),
+            )?])
+            .should_succeed()?;
+        Ok(outcome.gas_used)
+    };
+
+    // The messages only differ in the size of the response, which the host
+    // charges gas to deserialize.
+    let small = simulate(1)?;
+    let large = simulate(9)?;
+    assert!(large > small);
+
+    Ok(())
+}
+
+#[test]
+fn json_codec_gas_is_priced_by_the_chain_config() -> anyhow::Result<()> {
+    // Simulate the same message on two chains that only differ in the price of
+    // JSON encoding.
+    let simulate = |json_codec_per_byte: u64| -> anyhow::Result<u64> {
+        let (mut suite, accounts) = TestBuilder::new()
+            .add_account("sender", Coins::new_empty())?
+            .set_gas_config(GasConfig {
+                json_codec_per_byte,
+                ..Default::default()
+            })
+            .build()?;
+
+        let code = ContractBuilder::new(Box::new(instantiate_noop))
+            .with_execute(Box::new(execute_respond_with_size))
+            .build()
+            .into_bytes();
+
+        let (_, contract) = suite.upload_and_instantiate(
+            &accounts["sender"],
+            code.into(),
+            "responder",
+            &Empty {},
+        )?;
+
+        let outcome = suite
+            .simulate_messages(&accounts["sender"], vec![Message::execute(
+                contract,
+                &9_u32,
+                Coins::new_empty(),
+            )?])
+            .should_succeed()?;
+        Ok(outcome.gas_used)
+    };
+
+    // The response alone is over 9000 bytes, so pricing each byte at 10 more
+    // gas must cost at least 90,000 more.
+    let cheap = simulate(1)?;
+    let expensive = simulate(11)?;
+    assert!(expensive >= cheap + 90_000);
+
+    Ok(())
+}
+
+#[test]
+fn uploading_code_is_charged_per_byte() -> anyhow::Result<()> {
+    let code = ContractBuilder::new(Box::new(instantiate_noop))
+        .build()
+        .into_bytes();
+
+    // Simulate uploading the same code on two chains that only differ in the
+    // price of uploading.
+    let simulate = |upload_per_byte: u64| -> anyhow::Result<u64> {
+        let (mut suite, accounts) = TestBuilder::new()
+            .add_account("sender", Coins::new_empty())?
+            .set_gas_config(GasConfig {
+                upload_per_byte,
+                ..Default::default()
+            })
+            .build()?;
+
+        let outcome = suite
+            .simulate_messages(&accounts["sender"], vec![Message::upload(code.clone())])
+            .should_succeed()?;
+        Ok(outcome.gas_used)
+    };
+
+    let free = simulate(0)?;
+    let priced = simulate(5)?;
+    assert_eq!(priced - free, code.len() as u64 * 5);
+
+    Ok(())
+}
+
+#[test]
+fn transfers_emit_signed_balance_changes() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new_one(DENOM, NonZero::new(100_u128)))?
+        .add_account("receiver", Coins::new_empty())?
+        .build()?;
+
+    let bank = suite.query_info().should_succeed()?.config.bank;
+
+    let events = suite
+        .execute_message(
+            &accounts["sender"],
+            Message::transfer(
+                accounts["receiver"].address.clone(),
+                Coins::new_one(DENOM, NonZero::new(30_u128)),
+            )?,
+        )?
+        .should_succeed()?;
+
+    let changes = events
+        .into_iter()
+        .filter(|event| event.r#type == "wasm-balance_change")
+        .map(|event| event.attributes)
+        .collect::<Vec<_>>();
+    assert_eq!(changes, vec![
+        vec![
+            Attribute::new(CONTRACT_ADDRESS_KEY, &bank),
+            Attribute::new("account", &accounts["sender"].address),
+            Attribute::new("delta", "-ugrug:30"),
+        ],
+        vec![
+            Attribute::new(CONTRACT_ADDRESS_KEY, &bank),
+            Attribute::new("account", &accounts["receiver"].address),
+            Attribute::new("delta", "+ugrug:30"),
+        ],
+    ]);
+
+    Ok(())
+}
+
+#[test]
+fn strict_contracts_reject_funds() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new_one(DENOM, NonZero::new(100_u128)))?
+        .build()?;
+
+    let code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_execute(Box::new(execute_record_number))
+        .build()
+        .into_bytes();
+
+    let code_hash = suite.upload(&accounts["sender"], code.into())?;
+
+    let sender = &accounts["sender"];
+    let strict = instantiate_with_flags(&mut suite, sender, &code_hash, "strict", None, |msg| {
+        msg.with_strict_funds()
+    })?;
+    let lenient =
+        instantiate_with_flags(&mut suite, sender, &code_hash, "lenient", None, |msg| msg)?;
+    let funds = Coins::new_one(DENOM, NonZero::new(30_u128));
+
+    // The strict contract rejects a funded call, but accepts an unfunded one.
+    let err = suite
+        .execute_message(
+            &accounts["sender"],
+            Message::execute(strict.clone(), &1_u32, funds.clone())?,
+        )?
+        .should_fail()?;
+    assert!(matches!(
+        err,
+        AppError::UnexpectedFunds { contract, funds: rejected }
+            if contract == strict && rejected == funds
+    ));
+
+    suite
+        .execute_message(
+            &accounts["sender"],
+            Message::execute(strict, &1_u32, Coins::new_empty())?,
+        )?
+        .should_succeed()?;
+
+    // The lenient contract accepts the funds.
+    suite
+        .execute_message(
+            &accounts["sender"],
+            Message::execute(lenient, &1_u32, funds)?,
+        )?
+        .should_succeed()?;
+
+    suite
+        .query_balance(&accounts["sender"], DENOM)
+        .should_succeed_and_equal(Uint128::new(70))?;
+
+    Ok(())
+}
+
+#[test]
+fn oversized_messages_are_rejected() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new_empty())?
+        .set_max_msg_size(1000)
+        .build()?;
+
+    let code = ContractBuilder::new(Box::new(instantiate_unreachable))
+        .build()
+        .into_bytes();
+
+    let code_hash = suite.upload(&accounts["sender"], code.into())?;
+
+    // The message is rejected before the contract's `instantiate` entry point
+    // is called, which would otherwise have failed with a contract error.
+    let payload = "a".repeat(1000);
+    let err = suite
+        .execute_message(
+            &accounts["sender"],
+            Message::instantiate(code_hash, &payload, "big", Coins::new_empty(), None)?,
+        )?
+        .should_fail()?;
+    assert!(matches!(err, AppError::MessageTooLarge { size, max: 1000 } if size > 1000));
+
+    // An upload is sized by its code.
+    let err = suite
+        .execute_message(&accounts["sender"], Message::upload(vec![0; 1001]))?
+        .should_fail()?;
+    assert!(matches!(err, AppError::MessageTooLarge {
+        size: 1001,
+        max: 1000
+    }));
+
+    Ok(())
+}
+
+#[test]
+fn disallowed_entry_points_are_rejected() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new_empty())?
+        .build()?;
+
+    let code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_execute(Box::new(execute_record_number))
+        .with_migrate(Box::new(migrate_noop))
+        .build()
+        .into_bytes();
+
+    let code_hash = suite.upload(&accounts["sender"], code.into())?;
+    let admin = Some(accounts["sender"].address.clone());
+
+    // The restricted contract exports `migrate`, but doesn't allow it.
+    let sender = &accounts["sender"];
+    let contracts = [
+        instantiate_with_flags(
+            &mut suite,
+            sender,
+            &code_hash,
+            "restricted",
+            admin.clone(),
+            |msg| msg.with_entry_points(["instantiate", "execute"]),
+        )?,
+        instantiate_with_flags(
+            &mut suite,
+            sender,
+            &code_hash,
+            "unrestricted",
+            admin,
+            |msg| msg,
+        )?,
+    ];
+
+    // Allowed entry points can still be called.
+    suite
+        .execute_message(
+            &accounts["sender"],
+            Message::execute(contracts[0].clone(), &1_u32, Coins::new_empty())?,
+        )?
+        .should_succeed()?;
+
+    let err = suite
+        .execute_message(
+            &accounts["sender"],
+            Message::migrate(contracts[0].clone(), code_hash.clone(), &Empty {})?,
+        )?
+        .should_fail()?;
+    assert!(matches!(
+        err,
+        AppError::EntryPointNotAllowed { contract, name }
+            if contract == contracts[0] && name == "migrate"
+    ));
+
+    suite
+        .execute_message(
+            &accounts["sender"],
+            Message::migrate(contracts[1].clone(), code_hash, &Empty {})?,
+        )?
+        .should_succeed()?;
+
+    Ok(())
+}
+
+#[test]
+fn migrating_contracts() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("admin", Coins::new_empty())?
+        .add_account("stranger", Coins::new_empty())?
+        .build()?;
+
+    let old_code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_migrate(Box::new(migrate_noop))
+        .build()
+        .into_bytes();
+    let old_code_hash = suite.upload(&accounts["admin"], old_code.into())?;
+
+    // The new code additionally answers queries with the contract's address.
+    let new_code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_migrate(Box::new(migrate_noop))
+        .with_query(Box::new(query_self_address))
+        .build()
+        .into_bytes();
+    let new_code_hash = suite.upload(&accounts["admin"], new_code.into())?;
+
+    let mut contracts = vec![];
+    for (salt, admin) in [
+        ("mutable", Some(accounts["admin"].address.clone())),
+        ("immutable", None),
+    ] {
+        suite
+            .execute_message(
+                &accounts["admin"],
+                Message::instantiate(
+                    old_code_hash.clone(),
+                    &Empty {},
+                    salt,
+                    Coins::new_empty(),
+                    admin,
+                )?,
+            )?
+            .should_succeed()?;
+
+        contracts.push(Addr::compute(
+            &accounts["admin"].address,
+            &old_code_hash,
+            salt.as_bytes(),
+        ));
+    }
+
+    // Only the admin can migrate the contract.
+    suite
+        .execute_message(
+            &accounts["stranger"],
+            Message::migrate(contracts[0].clone(), new_code_hash.clone(), &Empty {})?,
+        )?
+        .should_fail_with_error("sender is not the admin!")?;
+
+    // The new code must exist.
+    suite
+        .execute_message(
+            &accounts["admin"],
+            Message::migrate(contracts[0].clone(), hash(b"nonexistent"), &Empty {})?,
+        )?
+        .should_fail_with_error("data not found!")?;
+
+    // A contract without an admin can't be migrated.
+    suite
+        .execute_message(
+            &accounts["admin"],
+            Message::migrate(contracts[1].clone(), new_code_hash.clone(), &Empty {})?,
+        )?
+        .should_fail_with_error("admin account is not set")?;
+
+    suite
+        .execute_message(
+            &accounts["admin"],
+            Message::migrate(contracts[0].clone(), new_code_hash, &Empty {})?,
+        )?
+        .should_succeed()?;
+
+    // The contract now runs the new code.
+    suite
+        .query_wasm_smart::<_, Addr>(contracts[0].clone(), &Empty {})
+        .should_succeed_and_equal(contracts[0].clone())?;
+
+    Ok(())
+}
+
+#[test]
+fn raw_queries_between_contracts_are_opt_in() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new_empty())?
+        .build()?;
+
+    let value_code = ContractBuilder::new(Box::new(instantiate_write_value))
+        .build()
+        .into_bytes();
+    let value_code_hash = suite.upload(&accounts["sender"], value_code.into())?;
+
+    // Both contracts hold the same value, but only one of them lets other
+    // contracts read it directly.
+    let sender = &accounts["sender"];
+    let contracts = [
+        instantiate_with_flags(&mut suite, sender, &value_code_hash, "open", None, |msg| {
+            msg.with_raw_queries()
+        })?,
+        instantiate_with_flags(
+            &mut suite,
+            sender,
+            &value_code_hash,
+            "closed",
+            None,
+            |msg| msg,
+        )?,
+    ];
+
+    let reader_code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_query(Box::new(query_raw_value))
+        .build()
+        .into_bytes();
+    let (_, reader) = suite.upload_and_instantiate(
+        &accounts["sender"],
+        reader_code.into(),
+        "reader",
+        &Empty {},
+    )?;
+
+    suite
+        .query_wasm_smart::<_, Result<Option<Binary>, Addr>>(reader.clone(), &contracts[0])
+        .should_succeed_and_equal(Ok(Some(Binary::from(b"hello".to_vec()))))?;
+
+    suite
+        .query_wasm_smart::<_, Result<Option<Binary>, Addr>>(reader, &contracts[1])
+        .should_succeed_and_equal(Err(contracts[1].clone()))?;
+
+    Ok(())
+}
+
+#[test]
+fn failed_queries_are_reported_to_the_caller() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new_empty())?
+        .build()?;
+
+    let callee_code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_query(Box::new(query_fail))
+        .build()
+        .into_bytes();
+    let (_, callee) = suite.upload_and_instantiate(
+        &accounts["sender"],
+        callee_code.into(),
+        "callee",
+        &Empty {},
+    )?;
+
+    let caller_code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_query(Box::new(query_failure_of))
+        .build()
+        .into_bytes();
+    let (_, caller) = suite.upload_and_instantiate(
+        &accounts["sender"],
+        caller_code.into(),
+        "caller",
+        &Empty {},
+    )?;
+
+    let (contract, msg) = suite
+        .query_wasm_smart::<_, (Addr, String)>(caller, &callee)
+        .should_succeed()?;
+    assert_eq!(contract, callee);
+    assert!(msg.contains("nothing to see here"));
+
+    Ok(())
+}
+
+#[test]
+fn fee_denom_is_validated_at_genesis() -> anyhow::Result<()> {
+    let (suite, _) = TestBuilder::new().set_fee_denom("uusdc").build()?;
+
+    let info = suite.query_info().should_succeed()?;
+    assert_eq!(info.config.fee_denom, "uusdc");
+
+    for denom in ["", "1usdc", "factory/usdc"] {
+        let Err(err) = TestBuilder::new().set_fee_denom(denom).build() else {
+            panic!("fee denom `{denom}` should have been rejected");
+        };
+        assert!(err.to_string().contains("invalid fee denom"));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn unaffordable_funds_are_rejected_early() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new_one(DENOM, NonZero::new(100_u128)))?
+        .build()?;
+
+    let code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_execute(Box::new(execute_record_number))
+        .build()
+        .into_bytes();
+
+    let (_, contract) =
+        suite.upload_and_instantiate(&accounts["sender"], code.into(), "contract", &Empty {})?;
+
+    let err = suite
+        .execute_message(
+            &accounts["sender"],
+            Message::execute(
+                contract.clone(),
+                &1_u32,
+                Coins::new_one(DENOM, NonZero::new(150_u128)),
+            )?,
+        )?
+        .should_fail()?;
+    assert!(matches!(
+        err,
+        AppError::InsufficientFunds { address, denom, balance, amount }
+            if address == accounts["sender"].address
+                && denom == DENOM
+                && balance == Uint128::new(100)
+                && amount == Uint128::new(150)
+    ));
+
+    // Funds the sender can afford are sent as usual.
+    suite
+        .execute_message(
+            &accounts["sender"],
+            Message::execute(
+                contract,
+                &1_u32,
+                Coins::new_one(DENOM, NonZero::new(100_u128)),
+            )?,
+        )?
+        .should_succeed()?;
+
+    Ok(())
+}
+
+#[test]
+fn recovered_submessage_failures_are_recorded() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new_empty())?
+        .build()?;
+
+    let paused_code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_execute(Box::new(execute_paused))
+        .build()
+        .into_bytes();
+    let (_, paused) = suite.upload_and_instantiate(
+        &accounts["sender"],
+        paused_code.into(),
+        "paused",
+        &Empty {},
+    )?;
+
+    let parent_code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_execute(Box::new(execute_recovering))
+        .with_reply(Box::new(reply_noop))
+        .build()
+        .into_bytes();
+    let (_, parent) = suite.upload_and_instantiate(
+        &accounts["sender"],
+        parent_code.into(),
+        "parent",
+        &Empty {},
+    )?;
+
+    // The parent recovers, so the message succeeds, but the failure is still
+    // recorded.
+    let events = suite
+        .execute_message(
+            &accounts["sender"],
+            Message::execute(parent.clone(), &paused, Coins::new_empty())?,
+        )?
+        .should_succeed()?;
+
+    let failed = events
+        .iter()
+        .find(|event| event.r#type == "submessage_failed")
+        .expect("submessage failure should be recorded");
+    let attr = |key: &str| {
+        failed
+            .attributes
+            .iter()
+            .find(|attr| attr.key == key)
+            .map(|attr| attr.value.clone())
+            .unwrap()
+    };
+
+    assert_eq!(attr("sender"), parent.to_string());
+    assert!(attr("msg").contains(&paused.to_string()));
+    assert!(attr("error").contains("vault is paused"));
+
+    Ok(())
+}
+
+#[test]
+fn recovered_submessages_are_rolled_back_but_charged() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new_empty())?
+        .build()?;
+
+    let child_code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_execute(Box::new(execute_count_then_fail))
+        .with_query(Box::new(query_hits))
+        .build()
+        .into_bytes();
+    let (_, child) =
+        suite.upload_and_instantiate(&accounts["sender"], child_code.into(), "child", &Empty {})?;
+
+    let parent_code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_execute(Box::new(execute_recovering))
+        .with_reply(Box::new(reply_noop))
+        .build()
+        .into_bytes();
+    let (_, parent) = suite.upload_and_instantiate(
+        &accounts["sender"],
+        parent_code.into(),
+        "parent",
+        &Empty {},
+    )?;
+
+    let events = suite
+        .execute_message_with_gas(
+            &accounts["sender"],
+            1_000_000,
+            Message::execute(parent, &child, Coins::new_empty())?,
+        )?
+        .should_succeed()?;
+
+    // The child's write is discarded along with its failure...
+    suite
+        .query_wasm_smart::<_, u32>(child, &Empty {})
+        .should_succeed_and_equal(0)?;
+
+    // ...but the gas it consumed before failing is still charged.
+    let gas_used: u64 = events
+        .iter()
+        .find(|event| event.r#type == "submessage_failed")
+        .and_then(|event| event.attributes.iter().find(|attr| attr.key == "gas_used"))
+        .expect("gas used by the failed submessage should be recorded")
+        .value
+        .parse()?;
+    assert!(gas_used > 0);
+
+    Ok(())
+}
+
+#[test]
+fn querying_chain_id() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .set_chain_id("grug-42")
+        .add_account("sender", Coins::new_empty())?
+        .build()?;
+
+    let code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_query(Box::new(query_chain_id))
+        .build()
+        .into_bytes();
+
+    let (_, contract) =
+        suite.upload_and_instantiate(&accounts["sender"], code.into(), "contract", &Empty {})?;
+
+    suite
+        .query_wasm_smart::<_, String>(contract, &Empty {})
+        .should_succeed_and_equal("grug-42")?;
+
+    Ok(())
+}
+
+#[test]
+fn contract_gas_usage_is_accumulated() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new_empty())?
+        .build()?;
+
+    let code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_execute(Box::new(execute_record_number))
+        .build()
+        .into_bytes();
+
+    let (_, contract) =
+        suite.upload_and_instantiate(&accounts["sender"], code.into(), "contract", &Empty {})?;
+
+    // Instantiation counts too.
+    let mut last = suite.query_contract_gas_used(contract.clone());
+    assert!(last > 0);
+
+    for number in [1_u32, 2] {
+        suite
+            .execute_message(
+                &accounts["sender"],
+                Message::execute(contract.clone(), &number, Coins::new_empty())?,
+            )?
+            .should_succeed()?;
+
+        let total = suite.query_contract_gas_used(contract.clone());
+        assert!(total > last);
+        last = total;
+    }
+
+    // A call that fails still consumes gas, so it's counted as well.
+    suite
+        .execute_message(
+            &accounts["sender"],
+            Message::execute(contract.clone(), &"not a number", Coins::new_empty())?,
+        )?
+        .should_fail_with_error("failed to decode json string")?;
+
+    assert!(suite.query_contract_gas_used(contract) > last);
+
+    Ok(())
+}
+
+#[test]
+fn only_registered_denoms_can_be_transferred() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account(
+            "owner",
+            Coins::try_from(BTreeMap::from([
+                (DENOM.to_string(), Uint128::new(100)),
+                ("uatom".to_string(), Uint128::new(100)),
+            ]))?,
+        )?
+        .add_account("receiver", Coins::new_empty())?
+        .set_owner("owner")
+        .set_registered_denoms([DENOM])
+        .build()?;
+
+    let transfer = |denom: &str| Message::Transfer {
+        to: accounts["receiver"].address.clone(),
+        coins: Coins::new_one(denom, NonZero::new(10_u128)),
+    };
+
+    // A registered denom can be transferred.
+    suite
+        .execute_message(&accounts["owner"], transfer(DENOM))?
+        .should_succeed()?;
+
+    // An unregistered denom can't.
+    suite
+        .execute_message(&accounts["owner"], transfer("uatom"))?
+        .should_fail_with_error("denom `uatom` is not registered")?;
+
+    // Once the owner registers it, it can.
+    let mut cfg = suite.query_info().should_succeed()?.config;
+    cfg.registered_denoms
+        .get_or_insert_with(BTreeSet::new)
+        .insert("uatom".to_string());
+
+    suite
+        .execute_message(&accounts["owner"], Message::configure(cfg))?
+        .should_succeed()?;
+
+    suite
+        .execute_message(&accounts["owner"], transfer("uatom"))?
+        .should_succeed()?;
+
+    suite
+        .query_balance(&accounts["receiver"], "uatom")
+        .should_succeed_and_equal(Uint128::new(10))?;
+
+    Ok(())
+}
+
+#[test]
+fn execute_errors_are_typed() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("sender", Coins::new_empty())?
+        .build()?;
+
+    let code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_execute(Box::new(execute_record_number))
+        .build()
+        .into_bytes();
+
+    let (code_hash, contract) =
+        suite.upload_and_instantiate(&accounts["sender"], code.clone().into(), "a", &Empty {})?;
+
+    // Uploading the same code again.
+    let err = suite
+        .execute_message(&accounts["sender"], Message::upload(code))?
+        .should_fail()?;
+    assert!(matches!(err, AppError::CodeExists { code_hash: existing } if existing == code_hash));
+
+    // Instantiating with the same salt again.
+    let err = suite
+        .execute_message(
+            &accounts["sender"],
+            Message::instantiate(code_hash, &Empty {}, "a", Coins::new_empty(), None)?,
+        )?
+        .should_fail()?;
+    assert!(matches!(err, AppError::AccountExists { address } if address == contract));
+
+    // Executing an account that doesn't exist.
+    let nonexistent = Addr::mock(123);
+    let err = suite
+        .execute_message(
+            &accounts["sender"],
+            Message::execute(nonexistent.clone(), &1_u32, Coins::new_empty())?,
+        )?
+        .should_fail()?;
+    assert!(matches!(err, AppError::AccountNotFound { address } if address == nonexistent));
+
+    // Querying it fails the same way.
+    let err = suite
+        .query_wasm_smart::<_, Json>(nonexistent.clone(), &Empty {})
+        .should_fail()?;
+    assert!(matches!(err, AppError::AccountNotFound { address } if address == nonexistent));
+
+    Ok(())
+}
+
+#[test]
+fn only_denom_admin_can_set_metadata() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("creator", Coins::new_empty())?
+        .add_account("stranger", Coins::new_empty())?
+        .build()?;
+
+    let bank = suite.query_info().should_succeed()?.config.bank;
+
+    // The creator of a factory denom is its admin.
+    let denom = format!("factory/{}/token", accounts["creator"].address);
+    let metadata = DenomMetadata {
+        name: "Token".to_string(),
+        symbol: "TKN".to_string(),
+        decimals: 6,
+        description: None,
+    };
+    let set_metadata = Message::bank_admin(BankAdminMsg::SetMetadata {
+        denom: denom.clone(),
+        metadata: metadata.clone(),
+    });
+    let query_metadata = grug_bank::QueryMsg::Metadata {
+        denom: denom.clone(),
+    };
+
+    // Someone else can't set the metadata.
+    suite
+        .execute_message(&accounts["stranger"], set_metadata.clone())?
+        .should_fail_with_error(format!("sender is not the admin of denom `{denom}`"))?;
+
+    suite
+        .query_wasm_smart::<_, Option<DenomMetadata>>(bank.clone(), &query_metadata)
+        .should_succeed_and_equal(None)?;
+
+    // The admin can.
+    suite
+        .execute_message(&accounts["creator"], set_metadata)?
+        .should_succeed()?;
+
+    suite
+        .query_wasm_smart::<_, Option<DenomMetadata>>(bank, &query_metadata)
+        .should_succeed_and_equal(Some(metadata))?;
+
+    // The admin of a native denom is the chain's owner. There is none here.
+    suite
+        .execute_message(
+            &accounts["creator"],
+            Message::bank_admin(BankAdminMsg::SetMetadata {
+                denom: DENOM.to_string(),
+                metadata: DenomMetadata {
+                    name: "Grug".to_string(),
+                    symbol: "GRUG".to_string(),
+                    decimals: 6,
+                    description: None,
+                },
+            }),
+        )?
+        .should_fail_with_error(format!("sender is not the admin of denom `{DENOM}`"))?;
+
+    Ok(())
+}
+
+fn ran_sudo(events: &[Event]) -> bool {
+    events
+        .iter()
+        .any(|event| event.attributes.contains(&Attribute::new("privileged", 7)))
+}
+
+#[test]
+fn sudo_is_called_by_the_app() -> anyhow::Result<()> {
+    let code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_sudo(Box::new(sudo_record_number))
+        .build()
+        .into_bytes();
+    let code_hash = hash(&code);
+    let contract = Addr::compute(&GENESIS_SENDER, &code_hash, b"sudo");
+
+    let mut genesis_state = mock_genesis_state::<RustVm>(BTreeMap::new())?;
+    genesis_state.msgs.extend([
+        Message::upload(code),
+        Message::instantiate(code_hash, &Empty {}, "sudo", Coins::new_empty(), None)?,
+    ]);
+
+    let db = MemDb::new();
+    let app = App::new(db.clone(), RustVm::new(), None, Udec128::new(Uint128::ONE));
+
+    let genesis_block = mock_genesis_block();
+    app.do_init_chain(
+        DEFAULT_CHAIN_ID.to_string(),
+        genesis_block.clone(),
+        genesis_state,
+    )?;
+
+    // The host calls the contract's privileged handler.
+    let events = app.do_sudo(
+        Box::new(Shared::new(Buffer::new(db.state_storage(None), None))),
+        genesis_block,
+        contract,
+        &to_json_value(&7_u32)?,
+    )?;
+    assert!(ran_sudo(&events));
+
+    Ok(())
+}
+
+#[test]
+fn sudo_is_unreachable_by_messages() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new()
+        .add_account("owner", Coins::new_one(DENOM, NonZero::new(100_u128)))?
+        .set_owner("owner")
+        .build()?;
+
+    let owner = &accounts["owner"];
+
+    // A contract implementing every entry point that a message may lead to,
+    // as well as `sudo`. The owner, who is authorized to send any message, is
+    // also the contract's admin.
+    let code = ContractBuilder::new(Box::new(instantiate_noop))
+        .with_execute(Box::new(execute_record_number))
+        .with_migrate(Box::new(migrate_noop))
+        .with_receive(Box::new(receive_record_deposit))
+        .with_sudo(Box::new(sudo_record_number))
+        .build()
+        .into_bytes();
+    let code_hash = suite.upload(owner, code.into())?;
+    let contract = instantiate_with_flags(
+        &mut suite,
+        owner,
+        &code_hash,
+        "sudo",
+        Some(owner.address.clone()),
+        |msg| msg.with_receive_hook(),
+    )?;
+
+    let unused_code = ContractBuilder::new(Box::new(instantiate_noop))
+        .build()
+        .into_bytes();
+    let cfg = suite.query_info().should_succeed()?.config;
+    let funds = Coins::new_one(DENOM, NonZero::new(10_u128));
+
+    // One message of every kind, aimed at the contract wherever the message
+    // takes a target, and carrying `7` wherever it takes a payload.
+    let msgs = vec![
+        Message::configure(cfg),
+        Message::transfer(contract.clone(), funds.clone())?,
+        Message::transfer_multi(vec![(contract.clone(), funds)]),
+        Message::upload(unused_code.clone()),
+        Message::remove_code(hash(&unused_code)),
+        Message::instantiate(code_hash.clone(), &7_u32, "other", Coins::new_empty(), None)?,
+        Message::instantiate_sequential(code_hash.clone(), &7_u32, Coins::new_empty(), None)?,
+        Message::execute(contract.clone(), &7_u32, Coins::new_empty())?,
+        Message::migrate(contract, code_hash, &Empty {})?,
+        Message::bank_admin(BankAdminMsg::SetMetadata {
+            denom: DENOM.to_string(),
+            metadata: DenomMetadata {
+                name: "Grug".to_string(),
+                symbol: "GRUG".to_string(),
+                decimals: 6,
+                description: None,
+            },
+        }),
+    ];
+
+    // The match has no wildcard arm, so a new `Message` variant doesn't
+    // compile until it's added here, and to the list above.
+    let kinds = msgs
+        .iter()
+        .map(|msg| match msg {
+            Message::Configure { .. } => 0,
+            Message::Transfer { .. } => 1,
+            Message::TransferMulti { .. } => 2,
+            Message::Upload { .. } => 3,
+            Message::RemoveCode { .. } => 4,
+            Message::Instantiate { .. } => 5,
+            Message::InstantiateSequential { .. } => 6,
+            Message::Execute { .. } => 7,
+            Message::Migrate { .. } => 8,
+            Message::BankAdmin { .. } => 9,
+        })
+        .collect::<BTreeSet<_>>();
+    assert_eq!(kinds.len(), 10);
+
+    // Each message is processed, but none of them reaches `sudo`.
+    for msg in msgs {
+        let events = suite.execute_message(owner, msg)?.should_succeed()?;
+        assert!(!ran_sudo(&events));
+    }
+
+    Ok(())
+}
+
+#[test]
+fn transferring_with_missing_bank_contract() -> anyhow::Result<()> {
+    let db = MemDb::new();
+    let app = App::new(db.clone(), RustVm::new(), None, Udec128::new(Uint128::ONE));
+
+    let genesis_block = mock_genesis_block();
+    app.do_init_chain(
+        DEFAULT_CHAIN_ID.to_string(),
+        genesis_block.clone(),
+        mock_genesis_state::<RustVm>(BTreeMap::new())?,
+    )?;
+
+    // Misconfigure the chain by pointing the bank to an address where no
+    // account exists.
+    let shared = Shared::new(Buffer::new(db.state_storage(None), None));
+    let mut storage: Box<dyn Storage> = Box::new(shared.clone());
+    let mut cfg = CONFIG.load(&storage)?;
+    cfg.bank = Addr::mock(1);
+    CONFIG.save(&mut storage, &cfg)?;
+
+    let err = do_transfer(
+        RustVm::new(),
+        storage,
+        GasTracker::new_limitless(),
+        0,
+        genesis_block.clone(),
+        Addr::mock(2),
+        Addr::mock(3),
+        Coins::new_one(DENOM, NonZero::new(1_u128)),
+        true,
+    )
+    .unwrap_err();
+    assert!(matches!(
+        err,
+        AppError::BankContractMissing { address } if address == Addr::mock(1)
+    ));
+
+    // Querying the bank fails the same way.
+    let err = query_balance(
+        RustVm::new(),
+        Box::new(shared),
+        genesis_block,
+        GasTracker::new_limitless(),
+        Addr::mock(2),
+        DENOM.to_string(),
+    )
+    .unwrap_err();
+    assert!(matches!(
+        err,
+        AppError::BankContractMissing { address } if address == Addr::mock(1)
+    ));
+
+    Ok(())
+}
+
+#[test]
+fn minting_directly_skips_the_bank_contract() -> anyhow::Result<()> {
+    const RECIPIENTS: u8 = 200;
+
+    let db = MemDb::new();
+    let app = App::new(db.clone(), RustVm::new(), None, Udec128::new(Uint128::ONE));
+
+    let holder = Addr::mock(0);
+    let genesis_block = mock_genesis_block();
+    app.do_init_chain(
+        DEFAULT_CHAIN_ID.to_string(),
+        genesis_block.clone(),
+        mock_genesis_state::<RustVm>(BTreeMap::from([(
+            holder.clone(),
+            Coins::new_one(DENOM, NonZero::new(u128::from(RECIPIENTS))),
+        )]))?,
+    )?;
+
+    let coins = Coins::new_one(DENOM, NonZero::new(1_u128));
+    let recipients = (1..=RECIPIENTS).map(Addr::mock).collect::<Vec<_>>();
+
+    // Give each recipient one coin by calling the bank contract once per
+    // recipient.
+    let transferred = Shared::new(Buffer::new(db.state_storage(None), None));
+    let mut bank_calls = 0;
+    for recipient in &recipients {
+        let events = do_transfer(
+            RustVm::new(),
+            Box::new(transferred.share()),
+            GasTracker::new_limitless(),
+            0,
+            genesis_block.clone(),
+            holder.clone(),
+            recipient.clone(),
+            coins.clone(),
+            false,
+        )?;
+        bank_calls += events
+            .iter()
+            .filter(|event| event.r#type == "bank_execute")
+            .count();
+    }
+    assert_eq!(bank_calls, usize::from(RECIPIENTS));
+
+    // Mint the same balances by writing to the bank's storage directly. This
+    // takes neither a VM nor a gas tracker, so the bank contract isn't called.
+    let minted = Shared::new(Buffer::new(db.state_storage(None), None));
+    let balances = recipients
+        .iter()
+        .map(|recipient| (recipient.clone(), coins.clone()))
+        .collect();
+    do_mint_direct(Box::new(minted.share()), &DirectBankStore, &balances)?;
+
+    // The bank contract sees the minted balances, and a total supply that
+    // accounts for them.
+    for recipient in &recipients {
+        let balance = query_balance(
+            RustVm::new(),
+            Box::new(minted.share()),
+            genesis_block.clone(),
+            GasTracker::new_limitless(),
+            recipient.clone(),
+            DENOM.to_string(),
+        )?;
+        assert_eq!(balance.amount, Uint128::ONE);
+    }
+
+    let supply = query_supply(
+        RustVm::new(),
+        Box::new(minted.share()),
+        genesis_block,
+        GasTracker::new_limitless(),
+        DENOM.to_string(),
+    )?;
+    assert_eq!(supply.amount, Uint128::new(2 * u128::from(RECIPIENTS)));
+
+    // The direct read agrees with the bank contract.
+    let balance = query_balance_direct(
+        Box::new(transferred.share()),
+        &DirectBankStore,
+        &holder,
+        DENOM,
+    )?;
+    assert_eq!(balance.amount, Uint128::ZERO);
+
+    Ok(())
+}
@@ -4,7 +4,7 @@ use {
     grug_types::Record,
     std::{collections::HashMap, ptr::NonNull},
     wasmer::{AsStoreMut, AsStoreRef, Instance, Memory, MemoryView, Value},
-    wasmer_middlewares::metering::{get_remaining_points, MeteringPoints},
+    wasmer_middlewares::metering::{get_remaining_points, set_remaining_points, MeteringPoints},
 };
 
 /// Necessary stuff for performing Wasm import functions.
@@ -170,6 +170,52 @@ impl Environment {
         Ok(())
     }
 
+    /// Update the gas tracker with the gas consumed inside the Wasmer instance
+    /// since the last checkpoint. Normally this is done after each function
+    /// call, but an import may need the tracker to be up to date mid-call.
+    ///
+    /// `name` is the name of the import, which is reported if gas runs out.
+    pub fn update_gas_checkpoint(
+        &mut self,
+        store: &mut impl AsStoreMut,
+        name: &str,
+    ) -> VmResult<()> {
+        let instance = self.get_wasmer_instance()?;
+        match get_remaining_points(store, instance) {
+            MeteringPoints::Remaining(remaining) => {
+                let consumed = self.gas_checkpoint - remaining;
+                self.gas_tracker.consume(consumed, name)?;
+                self.gas_checkpoint = remaining;
+
+                Ok(())
+            },
+            // The instance would have trapped before it could call an import.
+            MeteringPoints::Exhausted => Err(VmError::GasDepletion),
+        }
+    }
+
+    /// Charge the given amount of gas for work done by an import, such as
+    /// encoding data. The gas is taken out of the Wasmer meter too, so that the
+    /// instance can't go on to spend it.
+    ///
+    /// `name` is the name of the import, which is reported if gas runs out.
+    pub fn consume_gas(
+        &mut self,
+        store: &mut impl AsStoreMut,
+        gas: u64,
+        name: &str,
+    ) -> VmResult<()> {
+        self.update_gas_checkpoint(store, name)?;
+        self.gas_tracker.consume(gas, name)?;
+
+        let remaining = self.gas_checkpoint.saturating_sub(gas);
+        let instance = self.get_wasmer_instance()?;
+        set_remaining_points(store, instance, remaining);
+        self.gas_checkpoint = remaining;
+
+        Ok(())
+    }
+
     fn call_function(
         &mut self,
         store: &mut impl AsStoreMut,
@@ -51,6 +51,9 @@ pub enum VmError {
         data: String,
     },
 
+    #[error("response too large! size: {size}, max: {max}")]
+    ResponseTooLarge { size: u32, max: u32 },
+
     #[error("unexpected return value count! name: {name}, expect: {expect}, actual: {actual}")]
     ReturnCount {
         name: String,
@@ -75,6 +78,41 @@ pub enum VmError {
 
     #[error("db state changed detected on readonly instance")]
     ReadOnly,
+
+    #[error("`gas_remaining` is only available in simulation")]
+    GasRemainingUnavailable,
+}
+
+impl VmError {
+    /// For a runtime error, determine whether it was raised by one of the
+    /// host's import functions, or by the contract itself (i.e. a Wasm trap,
+    /// such as `unreachable`, which is what a panicking contract executes).
+    ///
+    /// Returns `None` if this isn't a runtime error.
+    pub fn runtime_error_origin(&self) -> Option<RuntimeErrorOrigin> {
+        let VmError::Runtime(err) = self else {
+            return None;
+        };
+
+        // Errors returned by import functions are wrapped by Wasmer as user
+        // errors, which we can downcast back to `VmError`.
+        if err.is::<VmError>() {
+            Some(RuntimeErrorOrigin::Host)
+        } else {
+            Some(RuntimeErrorOrigin::Contract)
+        }
+    }
+}
+
+/// Where a `VmError::Runtime` originated from.
+///
+/// See `VmError::runtime_error_origin`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuntimeErrorOrigin {
+    /// The host rejected a call made by the contract to an import function.
+    Host,
+    /// The contract trapped, e.g. it panicked.
+    Contract,
 }
 
 impl From<CompileError> for VmError {
@@ -92,7 +130,9 @@ impl From<InstantiationError> for VmError {
 // required such that VmError can be used in import function signatures
 impl From<VmError> for RuntimeError {
     fn from(err: VmError) -> Self {
-        RuntimeError::new(err.to_string())
+        // Wrap the error as a user error, instead of casting it to a string,
+        // so that `VmError::runtime_error_origin` can recognize it.
+        RuntimeError::user(Box::new(err))
     }
 }
 
@@ -1,10 +1,12 @@
 use {
     crate::{read_from_memory, write_to_memory, Environment, Iterator, VmError, VmResult},
+    data_encoding::{BASE64, HEXLOWER, HEXLOWER_PERMISSIVE},
     grug_types::{
-        decode_sections, from_json_slice, to_json_vec, Addr, Querier, QueryRequest, Record, Storage,
+        decode_sections, from_json_slice, to_json_vec, Addr, Json, Querier, QueryRequest,
+        QueryResult, Record, Storage,
     },
     tracing::info,
-    wasmer::FunctionEnvMut,
+    wasmer::{AsStoreMut, FunctionEnvMut},
 };
 
 pub fn db_read(mut fe: FunctionEnvMut<Environment>, key_ptr: u32) -> VmResult<u32> {
@@ -183,13 +185,25 @@ pub fn debug(mut fe: FunctionEnvMut<Environment>, addr_ptr: u32, msg_ptr: u32) -
     Ok(())
 }
 
+pub fn gas_remaining(mut fe: FunctionEnvMut<Environment>) -> VmResult<u64> {
+    let (env, mut store) = fe.data_and_store_mut();
+
+    env.update_gas_checkpoint(&mut store, "gas_remaining")?;
+
+    env.gas_tracker
+        .read_remaining()?
+        .ok_or(VmError::GasRemainingUnavailable)
+}
+
 pub fn query_chain(mut fe: FunctionEnvMut<Environment>, req_ptr: u32) -> VmResult<u32> {
     let (env, mut store) = fe.data_and_store_mut();
 
     let req_bytes = read_from_memory(env, &store, req_ptr)?;
     let req: QueryRequest = from_json_slice(req_bytes)?;
 
-    let res = env.querier.query_chain(req)?;
+    // A failed query is reported to the contract, which may handle it, rather
+    // than aborting the call.
+    let res = QueryResult::from(env.querier.query_chain(req));
     let res_bytes = to_json_vec(&res)?;
 
     write_to_memory(env, &mut store, &res_bytes)
@@ -319,6 +333,69 @@ impl_hash_method!(blake2s_256);
 impl_hash_method!(blake2b_512);
 impl_hash_method!(blake3);
 
+macro_rules! impl_encode_method {
+    ($name:ident, $encoding:expr) => {
+        pub fn $name(mut fe: FunctionEnvMut<Environment>, data_ptr: u32) -> VmResult<u32> {
+            let (env, mut store) = fe.data_and_store_mut();
+
+            let data = read_from_memory(env, &store, data_ptr)?;
+            consume_encoding_gas(env, &mut store, data.len(), stringify!($name))?;
+
+            let encoded = $encoding.encode(&data);
+
+            write_to_memory(env, &mut store, encoded.as_bytes())
+        }
+    };
+}
+
+macro_rules! impl_decode_method {
+    ($name:ident, $encoding:expr) => {
+        pub fn $name(mut fe: FunctionEnvMut<Environment>, data_ptr: u32) -> VmResult<u32> {
+            let (env, mut store) = fe.data_and_store_mut();
+
+            let data = read_from_memory(env, &store, data_ptr)?;
+            consume_encoding_gas(env, &mut store, data.len(), stringify!($name))?;
+
+            // If the input isn't valid, return a zero pointer.
+            match $encoding.decode(&data) {
+                Ok(decoded) => write_to_memory(env, &mut store, &decoded),
+                Err(_) => Ok(0),
+            }
+        }
+    };
+}
+
+impl_encode_method!(encode_base64, BASE64);
+impl_decode_method!(decode_base64, BASE64);
+impl_encode_method!(encode_hex, HEXLOWER);
+impl_decode_method!(decode_hex, HEXLOWER_PERMISSIVE);
+
+pub fn to_json_canonical(mut fe: FunctionEnvMut<Environment>, json_ptr: u32) -> VmResult<u32> {
+    let (env, mut store) = fe.data_and_store_mut();
+
+    let json = read_from_memory(env, &store, json_ptr)?;
+    consume_encoding_gas(env, &mut store, json.len(), "to_json_canonical")?;
+
+    // If the input isn't valid JSON, return a zero pointer.
+    match from_json_slice::<Json>(&json).and_then(|json| grug_types::to_json_canonical(&json)) {
+        Ok(canonical) => write_to_memory(env, &mut store, &canonical),
+        Err(_) => Ok(0),
+    }
+}
+
+/// Charge gas for an encoding import reading the given number of bytes of
+/// input, at the rate specified by the tracker's `GasConfig`.
+#[inline]
+fn consume_encoding_gas(
+    env: &mut Environment,
+    store: &mut impl AsStoreMut,
+    len: usize,
+    name: &str,
+) -> VmResult<()> {
+    let per_byte = env.gas_tracker.config().encoding_per_byte;
+    env.consume_gas(store, per_byte.saturating_mul(len as u64), name)
+}
+
 /// Pack a KV pair into a single byte array in the following format:
 ///
 /// ```plain
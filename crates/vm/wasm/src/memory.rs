@@ -14,19 +14,38 @@ pub fn read_from_memory(
     // read region
     let region = read_region(&memory, region_ptr)?;
 
-    // read memory area indicated by region
-    let mut buf = vec![0u8; region.length as usize];
-    memory.read(region.offset as u64, &mut buf)?;
-
-    Ok(buf)
+    read_region_data(&memory, &region)
 }
 
+/// The maximum size, in bytes, of the data a contract may return to the host
+/// from an entry point call.
+pub const MAX_RESPONSE_SIZE: u32 = 8 * 1024 * 1024;
+
+/// Read the data a contract returned from an entry point call, then release
+/// its memory.
+///
+/// The length the contract claims is checked against `MAX_RESPONSE_SIZE`
+/// before anything is read, so that the host doesn't allocate or decode an
+/// arbitrarily large response.
 pub fn read_then_wipe(
     env: &mut Environment,
     store: &mut impl AsStoreMut,
     region_ptr: u32,
 ) -> VmResult<Vec<u8>> {
-    let data = read_from_memory(env, store, region_ptr)?;
+    let data = {
+        let memory = env.get_wasmer_memory(&*store)?;
+        let region = read_region(&memory, region_ptr)?;
+
+        if region.length > MAX_RESPONSE_SIZE {
+            return Err(VmError::ResponseTooLarge {
+                size: region.length,
+                max: MAX_RESPONSE_SIZE,
+            });
+        }
+
+        read_region_data(&memory, &region)?
+    };
+
     env.call_function0(store, "deallocate", &[region_ptr.into()])?;
 
     Ok(data)
@@ -70,6 +89,14 @@ fn read_region(memory: &MemoryView, offset: u32) -> VmResult<Region> {
     // TODO: do some sanity checks on the Region?
 }
 
+// read memory area indicated by region
+fn read_region_data(memory: &MemoryView, region: &Region) -> VmResult<Vec<u8>> {
+    let mut buf = vec![0u8; region.length as usize];
+    memory.read(region.offset as u64, &mut buf)?;
+
+    Ok(buf)
+}
+
 fn write_region(memory: &MemoryView, offset: u32, region: Region) -> VmResult<()> {
     let wptr = <WasmPtr<Region>>::new(offset);
     wptr.deref(memory).write(region).map_err(Into::into)
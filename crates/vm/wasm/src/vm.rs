@@ -1,9 +1,10 @@
 use {
     crate::{
         blake2b_512, blake2s_256, blake3, db_next, db_next_key, db_next_value, db_read, db_remove,
-        db_remove_range, db_scan, db_write, debug, ed25519_batch_verify, ed25519_verify, keccak256,
-        query_chain, read_then_wipe, secp256k1_pubkey_recover, secp256k1_verify, secp256r1_verify,
-        sha2_256, sha2_512, sha2_512_truncated, sha3_256, sha3_512, sha3_512_truncated,
+        db_remove_range, db_scan, db_write, debug, decode_base64, decode_hex, ed25519_batch_verify,
+        ed25519_verify, encode_base64, encode_hex, gas_remaining, keccak256, query_chain,
+        read_then_wipe, secp256k1_pubkey_recover, secp256k1_verify, secp256r1_verify, sha2_256,
+        sha2_512, sha2_512_truncated, sha3_256, sha3_512, sha3_512_truncated, to_json_canonical,
         write_to_memory, Cache, Environment, VmError, VmResult,
     },
     grug_app::{GasTracker, Instance, QuerierProvider, StorageProvider, Vm},
@@ -13,6 +14,11 @@ use {
     wasmer_middlewares::{metering::set_remaining_points, Metering},
 };
 
+/// Wasm modules declare the version of the host-guest interface they're built
+/// against by exporting an empty function named this prefix followed by the
+/// version number, e.g. `interface_version_1`.
+const INTERFACE_VERSION_PREFIX: &str = "interface_version_";
+
 /// Gas cost per operation
 ///
 /// TODO: Mocked to 1 now, need to be discussed
@@ -49,29 +55,9 @@ impl Vm for WasmVm {
     ) -> VmResult<WasmInstance> {
         // Attempt to fetch a pre-built Wasmer module from the cache.
         // If not found, build it and insert it into the cache.
-        let (module, engine) = self.cache.get_or_build_with(code_hash, || {
-            let mut compiler = Singlepass::new();
-            compiler.canonicalize_nans(true);
-
-            // Set up the gas metering middleware.
-            //
-            // Set `initial_points` as zero for now, because this engine will be
-            // cached and to be used by other transactions, so it doesn't make
-            // sense to put the current tx's gas limit here.
-            //
-            // We will properly set this tx's gas limit later, once we have
-            // created the `Instance`.
-            //
-            // Also, compiling the module doesn't cost gas, so setting the limit
-            // to zero won't raise out of gas errors.
-            let metering = Metering::new(0, |_| GAS_PER_OPERATION);
-            compiler.push_middleware(Arc::new(metering));
-
-            let engine = Engine::from(compiler);
-            let module = Module::new(&engine, code)?;
-
-            Ok((module, engine))
-        })?;
+        let (module, engine) = self
+            .cache
+            .get_or_build_with(code_hash, || build_module(code))?;
 
         // Compute the amount of gas left for this call. This will be used as
         // the initial points in the Wasmer gas meter.
@@ -124,7 +110,13 @@ impl Vm for WasmVm {
                 "blake2s_256"              => Function::new_typed_with_env(&mut store, &fe, blake2s_256),
                 "blake2b_512"              => Function::new_typed_with_env(&mut store, &fe, blake2b_512),
                 "blake3"                   => Function::new_typed_with_env(&mut store, &fe, blake3),
+                "encode_base64"            => Function::new_typed_with_env(&mut store, &fe, encode_base64),
+                "decode_base64"            => Function::new_typed_with_env(&mut store, &fe, decode_base64),
+                "encode_hex"               => Function::new_typed_with_env(&mut store, &fe, encode_hex),
+                "decode_hex"               => Function::new_typed_with_env(&mut store, &fe, decode_hex),
+                "to_json_canonical"        => Function::new_typed_with_env(&mut store, &fe, to_json_canonical),
                 "debug"                    => Function::new_typed_with_env(&mut store, &fe, debug),
+                "gas_remaining"            => Function::new_typed_with_env(&mut store, &fe, gas_remaining),
                 "query_chain"              => Function::new_typed_with_env(&mut store, &fe, query_chain),
             }
         };
@@ -147,6 +139,54 @@ impl Vm for WasmVm {
             fe,
         })
     }
+
+    fn has_entry_point(&mut self, code: &[u8], code_hash: &Hash, name: &str) -> VmResult<bool> {
+        let (module, _) = self
+            .cache
+            .get_or_build_with(code_hash, || build_module(code))?;
+        Ok(module
+            .exports()
+            .functions()
+            .any(|export| export.name() == name))
+    }
+
+    fn interface_version(&mut self, code: &[u8], code_hash: &Hash) -> VmResult<Option<u32>> {
+        let (module, _) = self
+            .cache
+            .get_or_build_with(code_hash, || build_module(code))?;
+        Ok(module.exports().functions().find_map(|export| {
+            export
+                .name()
+                .strip_prefix(INTERFACE_VERSION_PREFIX)?
+                .parse()
+                .ok()
+        }))
+    }
+}
+
+fn build_module(code: &[u8]) -> VmResult<(Module, Engine)> {
+    let mut compiler = Singlepass::new();
+    compiler.canonicalize_nans(true);
+
+    // Set up the gas metering middleware.
+    //
+    // Set `initial_points` as zero for now, because this engine will be
+    // cached and to be used by other transactions, so it doesn't make
+    // sense to put the current tx's gas limit here.
+    //
+    // We will properly set this tx's gas limit later, once we have
+    // created the `Instance`.
+    //
+    // Also, compiling the module isn't metered by the middleware (the host
+    // charges for it per byte of code at upload), so setting the limit to
+    // zero won't raise out of gas errors.
+    let metering = Metering::new(0, |_| GAS_PER_OPERATION);
+    compiler.push_middleware(Arc::new(metering));
+
+    let engine = Engine::from(compiler);
+    let module = Module::new(&engine, code)?;
+
+    Ok((module, engine))
 }
 
 // --------------------------------- instance ----------------------------------
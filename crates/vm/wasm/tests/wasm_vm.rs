@@ -1,8 +1,13 @@
 use {
+    grug_app::Vm,
     grug_testing::TestBuilder,
-    grug_types::{to_json_value, Binary, Coins, Empty, Message, NonZero, NumberConst, Uint128},
-    grug_vm_wasm::{VmError, WasmVm},
-    std::{fs, io, vec},
+    grug_types::{
+        hash, to_json_value, to_json_vec, Binary, Coins, Empty, GasConfig, Message, NonZero,
+        NumberConst, QueryError, QueryRequest, QueryResult, Udec128, Uint128,
+    },
+    grug_vm_wasm::{RuntimeErrorOrigin, VmError, VmResult, WasmVm, MAX_RESPONSE_SIZE},
+    std::{fs, io, str::FromStr, vec},
+    wasmer::{imports, wat2wasm, Function, Instance, Module, Store},
 };
 
 const WASM_CACHE_CAPACITY: usize = 10;
@@ -13,6 +18,28 @@ fn read_wasm_file(filename: &str) -> io::Result<Binary> {
     fs::read(path).map(Into::into)
 }
 
+/// Assemble a minimal Wasm module that exports a single empty function of the
+/// given name, or no function at all if `None`.
+fn minimal_wasm_module(export: Option<&str>) -> Binary {
+    // magic number and version
+    let mut wasm = b"\0asm\x01\0\0\0".to_vec();
+
+    if let Some(name) = export {
+        // type section: one function type `() -> ()`
+        wasm.extend([0x01, 0x04, 0x01, 0x60, 0x00, 0x00]);
+        // function section: one function of type 0
+        wasm.extend([0x03, 0x02, 0x01, 0x00]);
+        // export section: export function 0 under the given name
+        wasm.extend([0x07, name.len() as u8 + 4, 0x01, name.len() as u8]);
+        wasm.extend(name.as_bytes());
+        wasm.extend([0x00, 0x00]);
+        // code section: one function body with no locals, just `end`
+        wasm.extend([0x0a, 0x04, 0x01, 0x02, 0x00, 0x0b]);
+    }
+
+    wasm.into()
+}
+
 #[test]
 fn bank_transfers() -> anyhow::Result<()> {
     let (mut suite, accounts) = TestBuilder::new_with_vm(WasmVm::new(WASM_CACHE_CAPACITY))
@@ -160,3 +187,623 @@ fn immutable_state() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn modules_are_cached_by_code_hash() -> anyhow::Result<()> {
+    let mut vm = WasmVm::new(1);
+
+    let code_a = minimal_wasm_module(Some("a"));
+    let code_a_hash = hash(&code_a);
+    let code_b = minimal_wasm_module(Some("b"));
+    let code_b_hash = hash(&code_b);
+
+    // Bytes that aren't a valid Wasm module. Building a module from these
+    // fails, so any call that succeeds with them must have hit the cache.
+    let garbage = b"not wasm";
+
+    assert!(vm.has_entry_point(&code_a, &code_a_hash, "a")?);
+    assert!(vm.has_entry_point(garbage, &code_a_hash, "a")?);
+
+    // The cache holds a single module. Building another one evicts the first,
+    // which then has to be rebuilt.
+    assert!(vm.has_entry_point(&code_b, &code_b_hash, "b")?);
+    assert!(vm.has_entry_point(garbage, &code_a_hash, "a").is_err());
+
+    // Clones of the VM share the same cache.
+    assert!(vm.clone().has_entry_point(garbage, &code_b_hash, "b")?);
+
+    Ok(())
+}
+
+#[test]
+fn interface_version() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new_with_vm(WasmVm::new(WASM_CACHE_CAPACITY))
+        .add_account("sender", Coins::new_one(DENOM, NonZero::new(100_u128)))?
+        .build()?;
+
+    // A module that doesn't declare an interface version is rejected.
+    suite
+        .execute_message_with_gas(
+            &accounts["sender"],
+            1_000_000,
+            Message::upload(minimal_wasm_module(None)),
+        )?
+        .should_fail_with_error("does not declare an interface version")?;
+
+    // A module built against an unsupported version is rejected.
+    suite
+        .execute_message_with_gas(
+            &accounts["sender"],
+            1_000_000,
+            Message::upload(minimal_wasm_module(Some("interface_version_0"))),
+        )?
+        .should_fail_with_error("interface version 0")?;
+
+    // A module built against the supported version is accepted.
+    suite.upload_with_gas(
+        &accounts["sender"],
+        1_000_000,
+        minimal_wasm_module(Some("interface_version_1")),
+    )?;
+
+    Ok(())
+}
+
+#[test]
+fn simulating_gas_with_adjustment() -> anyhow::Result<()> {
+    let (suite, accounts) = TestBuilder::new_with_vm(WasmVm::new(WASM_CACHE_CAPACITY))
+        .set_gas_adjustment(Udec128::from_str("1.5")?)
+        .add_account("sender", Coins::new_one(DENOM, NonZero::new(100_u128)))?
+        .add_account("receiver", Coins::new_empty())?
+        .build()?;
+
+    let outcome = suite
+        .simulate_messages(&accounts["sender"], vec![Message::Transfer {
+            to: accounts["receiver"].address.clone(),
+            coins: Coins::new_one(DENOM, NonZero::new(10_u128)),
+        }])
+        .should_succeed()?;
+
+    // The estimate should be the gas used times 1.5, rounded up.
+    assert!(outcome.gas_used > 0);
+    assert_eq!(outcome.gas_estimate, (outcome.gas_used * 3).div_ceil(2));
+
+    // Simulation doesn't change the state.
+    suite
+        .query_balance(&accounts["sender"], DENOM)
+        .should_succeed_and_equal(Uint128::new(100))?;
+
+    Ok(())
+}
+
+/// A bank contract whose `bank_query` entry point loops forever.
+///
+/// The `instantiate` and `bank_execute` entry points return an empty response
+/// stored in the data section, and `allocate` is a simple bump allocator.
+const LOOPING_BANK_WAT: &str = r#"
+(module
+  (memory (export "memory") 1)
+  (global $next (mut i32) (i32.const 4096))
+
+  ;; region at 16 pointing to the response at 1024
+  (data (i32.const 16) "\00\04\00\00\25\00\00\00\25\00\00\00")
+  (data (i32.const 1024) "{\"ok\":{\"submsgs\":[],\"attributes\":[]}}")
+
+  (func (export "interface_version_1"))
+
+  (func (export "allocate") (param $size i32) (result i32)
+    (local $region i32)
+    (local.set $region (global.get $next))
+    (i32.store offset=0 (local.get $region) (i32.add (local.get $region) (i32.const 12)))
+    (i32.store offset=4 (local.get $region) (local.get $size))
+    (i32.store offset=8 (local.get $region) (i32.const 0))
+    (global.set $next
+      (i32.add (i32.add (local.get $region) (i32.const 12)) (local.get $size)))
+    (local.get $region))
+
+  (func (export "deallocate") (param i32))
+
+  (func $empty_response (param i32 i32) (result i32)
+    (i32.const 16))
+  (export "instantiate" (func $empty_response))
+  (export "bank_execute" (func $empty_response))
+
+  (func (export "bank_query") (param i32 i32) (result i32)
+    (loop $forever (br $forever))
+    (unreachable)))
+"#;
+
+#[test]
+fn query_gas_limit() -> anyhow::Result<()> {
+    let bank_code = wat2wasm(LOOPING_BANK_WAT.as_bytes())?.to_vec();
+
+    let (suite, accounts) = TestBuilder::new_with_vm(WasmVm::new(WASM_CACHE_CAPACITY))
+        .set_bank_code(bank_code.into())
+        .set_query_gas_limit(1_000_000)
+        .add_account("sender", Coins::new_empty())?
+        .build()?;
+
+    // The query would run forever if not for the gas limit.
+    suite
+        .query_balance(&accounts["sender"], DENOM)
+        .should_fail_with_error(VmError::GasDepletion)?;
+
+    Ok(())
+}
+
+/// A module that either calls an import function that always fails, or traps.
+const HOST_ERROR_OR_TRAP_WAT: &str = r#"
+(module
+  (import "env" "always_fail" (func $always_fail))
+  (func (export "call_host")
+    call $always_fail)
+  (func (export "trap")
+    unreachable))
+"#;
+
+fn always_fail() -> VmResult<()> {
+    Err(VmError::ReadOnly)
+}
+
+#[test]
+fn classifying_runtime_errors() -> anyhow::Result<()> {
+    let mut store = Store::default();
+    let module = Module::new(&store, wat2wasm(HOST_ERROR_OR_TRAP_WAT.as_bytes())?)?;
+    let import_object = imports! {
+        "env" => {
+            "always_fail" => Function::new_typed(&mut store, always_fail),
+        },
+    };
+    let instance = Instance::new(&mut store, &module, &import_object)?;
+
+    let mut call = |name: &str| -> anyhow::Result<VmError> {
+        let func = instance.exports.get_function(name)?;
+        let err = func.call(&mut store, &[]).unwrap_err();
+        Ok(err.into())
+    };
+
+    // The error is raised by the import function.
+    let err = call("call_host")?;
+    assert_eq!(err.runtime_error_origin(), Some(RuntimeErrorOrigin::Host));
+
+    // The error is raised by the contract.
+    let err = call("trap")?;
+    assert_eq!(
+        err.runtime_error_origin(),
+        Some(RuntimeErrorOrigin::Contract)
+    );
+
+    // Not a runtime error.
+    assert_eq!(VmError::GasDepletion.runtime_error_origin(), None);
+
+    Ok(())
+}
+
+/// A module with a bump allocator, whose `instantiate` entry point returns a
+/// region claiming to be one byte longer than the host accepts.
+fn oversized_response_wat() -> String {
+    let length = MAX_RESPONSE_SIZE + 1;
+
+    format!(
+        r#"
+(module
+  (memory (export "memory") 1)
+  (global $next (mut i32) (i32.const 1024))
+  (func (export "interface_version_1"))
+  (func (export "allocate") (param $size i32) (result i32)
+    (local $region i32)
+    (local.set $region (global.get $next))
+    (i32.store (local.get $region) (i32.add (local.get $region) (i32.const 12)))
+    (i32.store offset=4 (local.get $region) (local.get $size))
+    (i32.store offset=8 (local.get $region) (i32.const 0))
+    (global.set $next
+      (i32.add (global.get $next) (i32.add (local.get $size) (i32.const 12))))
+    (local.get $region))
+  (func (export "deallocate") (param i32))
+  (func (export "instantiate") (param i32 i32) (result i32)
+    (i32.store (i32.const 0) (i32.const 12))
+    (i32.store (i32.const 4) (i32.const {length}))
+    (i32.store (i32.const 8) (i32.const {length}))
+    (i32.const 0)))
+"#
+    )
+}
+
+#[test]
+fn response_too_large() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new_with_vm(WasmVm::new(WASM_CACHE_CAPACITY))
+        .add_account("sender", Coins::new_one(DENOM, NonZero::new(100_u128)))?
+        .build()?;
+
+    let code = wat2wasm(oversized_response_wat().as_bytes())?.to_vec();
+    let code_hash = suite.upload_with_gas(&accounts["sender"], 1_000_000, code.into())?;
+
+    suite
+        .execute_message_with_gas(
+            &accounts["sender"],
+            1_000_000,
+            Message::instantiate(code_hash, &Empty {}, "oversized", Coins::new_empty(), None)?,
+        )?
+        .should_fail_with_error(VmError::ResponseTooLarge {
+            size: MAX_RESPONSE_SIZE + 1,
+            max: MAX_RESPONSE_SIZE,
+        })?;
+
+    Ok(())
+}
+
+/// Encode a region pointing to `length` bytes at `offset`, as a WAT string.
+fn wat_region(offset: u32, length: usize) -> String {
+    [offset, length as u32, length as u32]
+        .iter()
+        .flat_map(|n| n.to_le_bytes())
+        .map(|byte| format!("\\{byte:02x}"))
+        .collect()
+}
+
+/// A module with a bump allocator, whose `query` entry point makes the given
+/// query to the chain, and returns the result as is, whether it's an error or
+/// not.
+fn query_chain_wat(req: &QueryRequest) -> anyhow::Result<String> {
+    let req = String::from_utf8(to_json_vec(req)?)?;
+    let req_region = wat_region(2048, req.len());
+    let req = req.replace('\\', "\\\\").replace('"', "\\\"");
+
+    Ok(format!(
+        r#"
+(module
+  (import "env" "query_chain" (func $query_chain (param i32) (result i32)))
+  (memory (export "memory") 1)
+  (global $next (mut i32) (i32.const 8192))
+
+  ;; region at 16 pointing to the empty response at 1024
+  (data (i32.const 16) "\00\04\00\00\25\00\00\00\25\00\00\00")
+  (data (i32.const 1024) "{{\"ok\":{{\"submsgs\":[],\"attributes\":[]}}}}")
+  ;; region at 32 pointing to the query request at 2048
+  (data (i32.const 32) "{req_region}")
+  (data (i32.const 2048) "{req}")
+  ;; the start of a successful output
+  (data (i32.const 64) "{{\"ok\":")
+
+  (func (export "interface_version_1"))
+
+  (func $allocate (export "allocate") (param $size i32) (result i32)
+    (local $region i32)
+    (local.set $region (global.get $next))
+    (i32.store offset=0 (local.get $region) (i32.add (local.get $region) (i32.const 12)))
+    (i32.store offset=4 (local.get $region) (local.get $size))
+    (i32.store offset=8 (local.get $region) (i32.const 0))
+    (global.set $next
+      (i32.add (i32.add (local.get $region) (i32.const 12)) (local.get $size)))
+    (local.get $region))
+
+  (func (export "deallocate") (param i32))
+
+  (func $copy (param $dst i32) (param $src i32) (param $len i32)
+    (block $done
+      (loop $next
+        (br_if $done (i32.eqz (local.get $len)))
+        (i32.store8 (local.get $dst) (i32.load8_u (local.get $src)))
+        (local.set $dst (i32.add (local.get $dst) (i32.const 1)))
+        (local.set $src (i32.add (local.get $src) (i32.const 1)))
+        (local.set $len (i32.sub (local.get $len) (i32.const 1)))
+        (br $next))))
+
+  (func (export "instantiate") (param i32 i32) (result i32)
+    (i32.const 16))
+
+  ;; output `{{"ok":<result>}}`, where `<result>` is what the host returned
+  (func (export "query") (param i32 i32) (result i32)
+    (local $res i32)
+    (local $len i32)
+    (local $out i32)
+    (local $dst i32)
+    (local.set $res (call $query_chain (i32.const 32)))
+    (local.set $len (i32.load offset=8 (local.get $res)))
+    (local.set $out (call $allocate (i32.add (local.get $len) (i32.const 7))))
+    (local.set $dst (i32.load (local.get $out)))
+    (call $copy (local.get $dst) (i32.const 64) (i32.const 6))
+    (call $copy
+      (i32.add (local.get $dst) (i32.const 6))
+      (i32.load (local.get $res))
+      (local.get $len))
+    (i32.store8
+      (i32.add (i32.add (local.get $dst) (i32.const 6)) (local.get $len))
+      (i32.const 125))
+    (i32.store offset=8 (local.get $out) (i32.add (local.get $len) (i32.const 7)))
+    (local.get $out)))
+"#
+    ))
+}
+
+#[test]
+fn failed_queries_are_reported_to_wasm_contracts() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new_with_vm(WasmVm::new(WASM_CACHE_CAPACITY))
+        .add_account("sender", Coins::new_one(DENOM, NonZero::new(100_u128)))?
+        .build()?;
+
+    // The bank contract doesn't allow its storage to be queried raw.
+    let bank = suite.query_info().should_succeed()?.config.bank;
+
+    let code = wat2wasm(
+        query_chain_wat(&QueryRequest::WasmRaw {
+            contract: bank.clone(),
+            key: b"key".to_vec().into(),
+        })?
+        .as_bytes(),
+    )?
+    .to_vec();
+    let (_, contract) = suite.upload_and_instantiate_with_gas(
+        &accounts["sender"],
+        1_000_000,
+        code.into(),
+        "querier",
+        &Empty {},
+    )?;
+
+    // Rather than the call being aborted, the contract is handed the error,
+    // which tells it why the query failed.
+    suite
+        .query_wasm_smart::<_, QueryResult>(contract, &Empty {})
+        .should_succeed_and_equal(QueryResult::Err(QueryError::RawQueryNotAllowed {
+            contract: bank,
+        }))?;
+
+    Ok(())
+}
+
+/// A module with a bump allocator, whose `instantiate` entry point reads the
+/// remaining gas before returning an empty response.
+const READ_GAS_REMAINING_WAT: &str = r#"
+(module
+  (import "env" "gas_remaining" (func $gas_remaining (result i64)))
+  (memory (export "memory") 1)
+  (global $next (mut i32) (i32.const 4096))
+
+  ;; region at 16 pointing to the response at 1024
+  (data (i32.const 16) "\00\04\00\00\25\00\00\00\25\00\00\00")
+  (data (i32.const 1024) "{\"ok\":{\"submsgs\":[],\"attributes\":[]}}")
+
+  (func (export "interface_version_1"))
+
+  (func (export "allocate") (param $size i32) (result i32)
+    (local $region i32)
+    (local.set $region (global.get $next))
+    (i32.store offset=0 (local.get $region) (i32.add (local.get $region) (i32.const 12)))
+    (i32.store offset=4 (local.get $region) (local.get $size))
+    (i32.store offset=8 (local.get $region) (i32.const 0))
+    (global.set $next
+      (i32.add (i32.add (local.get $region) (i32.const 12)) (local.get $size)))
+    (local.get $region))
+
+  (func (export "deallocate") (param i32))
+
+  (func (export "instantiate") (param i32 i32) (result i32)
+    (drop (call $gas_remaining))
+    (i32.const 16)))
+"#;
+
+#[test]
+fn out_of_gas_names_the_import() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new_with_vm(WasmVm::new(WASM_CACHE_CAPACITY))
+        .set_gas_config(GasConfig {
+            gas_remaining: 10_000_000,
+            ..GasConfig::default()
+        })
+        .add_account("sender", Coins::new_one(DENOM, NonZero::new(100_u128)))?
+        .build()?;
+
+    let code = wat2wasm(READ_GAS_REMAINING_WAT.as_bytes())?.to_vec();
+    let code_hash = suite.upload_with_gas(&accounts["sender"], 1_000_000, code.into())?;
+
+    // Reading the remaining gas costs more than the limit. The error reports
+    // the import that ran out of gas, and what it attempted to charge.
+    suite
+        .execute_message_with_gas(
+            &accounts["sender"],
+            1_000_000,
+            Message::instantiate(code_hash, &Empty {}, "reader", Coins::new_empty(), None)?,
+        )?
+        .should_fail_with_error("attempted to consume 10000000 in `gas_remaining`")?;
+
+    Ok(())
+}
+
+#[test]
+fn gas_remaining_is_only_available_in_simulation() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new_with_vm(WasmVm::new(WASM_CACHE_CAPACITY))
+        .add_account("sender", Coins::new_one(DENOM, NonZero::new(100_u128)))?
+        .build()?;
+
+    let code = wat2wasm(READ_GAS_REMAINING_WAT.as_bytes())?.to_vec();
+    let code_hash = suite.upload_with_gas(&accounts["sender"], 1_000_000, code.into())?;
+
+    let msg = Message::instantiate(code_hash, &Empty {}, "reader", Coins::new_empty(), None)?;
+
+    // The host refuses to read the gas meter for a transaction that is
+    // executed for real.
+    suite
+        .execute_message_with_gas(&accounts["sender"], 1_000_000, msg.clone())?
+        .should_fail_with_error("`gas_remaining` is only available in simulation")?;
+
+    // In simulation, the read succeeds.
+    suite
+        .simulate_messages(&accounts["sender"], vec![msg])
+        .should_succeed()?;
+
+    Ok(())
+}
+
+/// A module with a bump allocator, whose `query` entry point calls the given
+/// encoding import on the query message, which must be a JSON string, and
+/// outputs `{"ok":"<result>"}`, or `{"ok":null}` if the host rejected the
+/// input by returning a zero pointer.
+fn codec_wat(import: &str) -> String {
+    format!(
+        r#"
+(module
+  (import "env" "{import}" (func $codec (param i32) (result i32)))
+  (memory (export "memory") 1)
+  (global $next (mut i32) (i32.const 4096))
+
+  ;; region at 16 pointing to the empty response at 1024
+  (data (i32.const 16) "\00\04\00\00\25\00\00\00\25\00\00\00")
+  (data (i32.const 1024) "{{\"ok\":{{\"submsgs\":[],\"attributes\":[]}}}}")
+  ;; the start of a successful output
+  (data (i32.const 64) "{{\"ok\":\"")
+  ;; the output if the input is rejected
+  (data (i32.const 80) "{{\"ok\":null}}")
+
+  (func (export "interface_version_1"))
+
+  (func $allocate (export "allocate") (param $size i32) (result i32)
+    (local $region i32)
+    (local.set $region (global.get $next))
+    (i32.store offset=0 (local.get $region) (i32.add (local.get $region) (i32.const 12)))
+    (i32.store offset=4 (local.get $region) (local.get $size))
+    (i32.store offset=8 (local.get $region) (i32.const 0))
+    (global.set $next
+      (i32.add (i32.add (local.get $region) (i32.const 12)) (local.get $size)))
+    (local.get $region))
+
+  (func (export "deallocate") (param i32))
+
+  (func $copy (param $dst i32) (param $src i32) (param $len i32)
+    (block $done
+      (loop $next
+        (br_if $done (i32.eqz (local.get $len)))
+        (i32.store8 (local.get $dst) (i32.load8_u (local.get $src)))
+        (local.set $dst (i32.add (local.get $dst) (i32.const 1)))
+        (local.set $src (i32.add (local.get $src) (i32.const 1)))
+        (local.set $len (i32.sub (local.get $len) (i32.const 1)))
+        (br $next))))
+
+  (func (export "instantiate") (param i32 i32) (result i32)
+    (i32.const 16))
+
+  (func (export "query") (param $ctx i32) (param $msg i32) (result i32)
+    (local $res i32)
+    (local $len i32)
+    (local $out i32)
+    (local $dst i32)
+    ;; region at 32 pointing to the message without its quotes
+    (local.set $len (i32.sub (i32.load offset=8 (local.get $msg)) (i32.const 2)))
+    (i32.store offset=0 (i32.const 32) (i32.add (i32.load (local.get $msg)) (i32.const 1)))
+    (i32.store offset=4 (i32.const 32) (local.get $len))
+    (i32.store offset=8 (i32.const 32) (local.get $len))
+    (local.set $res (call $codec (i32.const 32)))
+    (if (i32.eqz (local.get $res))
+      (then
+        (local.set $out (call $allocate (i32.const 11)))
+        (call $copy (i32.load (local.get $out)) (i32.const 80) (i32.const 11))
+        (i32.store offset=8 (local.get $out) (i32.const 11))
+        (return (local.get $out))))
+    (local.set $len (i32.load offset=8 (local.get $res)))
+    (local.set $out (call $allocate (i32.add (local.get $len) (i32.const 9))))
+    (local.set $dst (i32.load (local.get $out)))
+    (call $copy (local.get $dst) (i32.const 64) (i32.const 7))
+    (call $copy
+      (i32.add (local.get $dst) (i32.const 7))
+      (i32.load (local.get $res))
+      (local.get $len))
+    ;; close the string and the object with `"}}`
+    (i32.store16
+      (i32.add (i32.add (local.get $dst) (i32.const 7)) (local.get $len))
+      (i32.const 0x7d22))
+    (i32.store offset=8 (local.get $out) (i32.add (local.get $len) (i32.const 9)))
+    (local.get $out)))
+"#
+    )
+}
+
+#[test]
+fn encoding_imports_round_trip() -> anyhow::Result<()> {
+    let (mut suite, accounts) = TestBuilder::new_with_vm(WasmVm::new(WASM_CACHE_CAPACITY))
+        .add_account("sender", Coins::new_one(DENOM, NonZero::new(100_u128)))?
+        .build()?;
+
+    let mut instantiate = |import: &str| -> anyhow::Result<_> {
+        let code = wat2wasm(codec_wat(import).as_bytes())?.to_vec();
+        let (_, contract) = suite.upload_and_instantiate_with_gas(
+            &accounts["sender"],
+            1_000_000,
+            code.into(),
+            import,
+            &Empty {},
+        )?;
+        Ok(contract)
+    };
+
+    let encoders = [
+        ("encode_base64", "decode_base64"),
+        ("encode_hex", "decode_hex"),
+    ]
+    .into_iter()
+    .map(|(encode, decode)| Ok((instantiate(encode)?, instantiate(decode)?)))
+    .collect::<anyhow::Result<Vec<_>>>()?;
+
+    let data = "hello grug";
+    for ((encoder, decoder), (encoded, invalid)) in encoders.into_iter().zip([
+        ("aGVsbG8gZ3J1Zw==", "aGVsbG8gZ3J1Zw"),
+        ("68656c6c6f2067727567", "68656c6c6f206772756"),
+    ]) {
+        // The data is encoded the same way as by the host's own codecs, and
+        // decodes back to the original.
+        suite
+            .query_wasm_smart::<_, Option<String>>(encoder, &data)
+            .should_succeed_and_equal(Some(encoded.to_string()))?;
+        suite
+            .query_wasm_smart::<_, Option<String>>(decoder.clone(), &encoded)
+            .should_succeed_and_equal(Some(data.to_string()))?;
+
+        // Invalid input is reported to the contract as a zero pointer, rather
+        // than aborting the call.
+        suite
+            .query_wasm_smart::<_, Option<String>>(decoder, &invalid)
+            .should_succeed_and_equal(None)?;
+    }
+
+    Ok(())
+}
+
+#[test]
+fn encoding_imports_charge_gas_per_byte() -> anyhow::Result<()> {
+    const PER_BYTE: u64 = 1_000_000_000;
+
+    // Charge so much per byte that even a single byte of input exceeds the
+    // query gas limit, so that the error reports exactly what was charged.
+    let (mut suite, accounts) = TestBuilder::new_with_vm(WasmVm::new(WASM_CACHE_CAPACITY))
+        .set_gas_config(GasConfig {
+            encoding_per_byte: PER_BYTE,
+            ..GasConfig::default()
+        })
+        .set_query_gas_limit(1_000_000)
+        .add_account("sender", Coins::new_one(DENOM, NonZero::new(100_u128)))?
+        .build()?;
+
+    for import in [
+        "encode_base64",
+        "decode_base64",
+        "encode_hex",
+        "decode_hex",
+        "to_json_canonical",
+    ] {
+        let code = wat2wasm(codec_wat(import).as_bytes())?.to_vec();
+        let (_, contract) = suite.upload_and_instantiate_with_gas(
+            &accounts["sender"],
+            1_000_000,
+            code.into(),
+            import,
+            &Empty {},
+        )?;
+
+        for input in ["ab", "abcdefgh"] {
+            let charged = PER_BYTE * input.len() as u64;
+            suite
+                .query_wasm_smart::<_, Option<String>>(contract.clone(), &input)
+                .should_fail_with_error(format!("attempted to consume {charged} in `{import}`"))?;
+        }
+    }
+
+    Ok(())
+}
@@ -5,8 +5,8 @@ use {
     grug_jmt::Proof,
     grug_types::{
         from_json_slice, from_json_value, hash, to_json_value, to_json_vec, AccountResponse, Addr,
-        Binary, Coin, Coins, Config, Hash, InfoResponse, Message, QueryRequest, QueryResponse,
-        StdError, Tx, WasmRawResponse,
+        Binary, Coin, Coins, Config, Hash, InfoResponse, Message, Order, QueryRequest,
+        QueryResponse, StdError, SuppliesResponse, Tx, WasmRawResponse,
     },
     serde::{de::DeserializeOwned, ser::Serialize},
     std::any::type_name,
@@ -140,11 +140,24 @@ impl Client {
         Ok(res.as_balance())
     }
 
+    pub async fn query_balance_exact(
+        &self,
+        address: Addr,
+        denom: String,
+        height: Option<u64>,
+    ) -> anyhow::Result<Option<Coin>> {
+        let res = self
+            .query_app(&QueryRequest::BalanceExact { address, denom }, height)
+            .await?;
+        Ok(res.as_balance_exact())
+    }
+
     pub async fn query_balances(
         &self,
         address: Addr,
         start_after: Option<String>,
         limit: Option<u32>,
+        order: Option<Order>,
         height: Option<u64>,
     ) -> anyhow::Result<Coins> {
         let res = self
@@ -153,6 +166,7 @@ impl Client {
                     address,
                     start_after,
                     limit,
+                    order,
                 },
                 height,
             )
@@ -171,10 +185,18 @@ impl Client {
         &self,
         start_after: Option<String>,
         limit: Option<u32>,
+        order: Option<Order>,
         height: Option<u64>,
-    ) -> anyhow::Result<Coins> {
+    ) -> anyhow::Result<SuppliesResponse> {
         let res = self
-            .query_app(&QueryRequest::Supplies { start_after, limit }, height)
+            .query_app(
+                &QueryRequest::Supplies {
+                    start_after,
+                    limit,
+                    order,
+                },
+                height,
+            )
             .await?;
         Ok(res.as_supplies())
     }
@@ -188,10 +210,18 @@ impl Client {
         &self,
         start_after: Option<Hash>,
         limit: Option<u32>,
+        order: Option<Order>,
         height: Option<u64>,
     ) -> anyhow::Result<Vec<Hash>> {
         let res = self
-            .query_app(&QueryRequest::Codes { start_after, limit }, height)
+            .query_app(
+                &QueryRequest::Codes {
+                    start_after,
+                    limit,
+                    order,
+                },
+                height,
+            )
             .await?;
         Ok(res.as_codes())
     }
@@ -211,10 +241,18 @@ impl Client {
         &self,
         start_after: Option<Addr>,
         limit: Option<u32>,
+        order: Option<Order>,
         height: Option<u64>,
     ) -> anyhow::Result<Vec<AccountResponse>> {
         let res = self
-            .query_app(&QueryRequest::Accounts { start_after, limit }, height)
+            .query_app(
+                &QueryRequest::Accounts {
+                    start_after,
+                    limit,
+                    order,
+                },
+                height,
+            )
             .await?;
         Ok(res.as_accounts())
     }
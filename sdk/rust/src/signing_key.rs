@@ -121,6 +121,7 @@ impl SigningKey {
             &sender,
             chain_id,
             sequence,
+            None,
         )?;
 
         // Sign the sign bytes
@@ -132,6 +133,7 @@ impl SigningKey {
             sender,
             msgs,
             credential: signature.into(),
+            memo: None,
         })
     }
 